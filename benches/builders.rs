@@ -0,0 +1,79 @@
+//! Compares the encoding strategies (`cargo bench --bench builders`).
+
+mod common;
+
+use common::*;
+use protobin::{builders::*, wire::WireVarInt};
+
+fn main() {
+    let b = Bencher::from_args();
+
+    // the baseline lengths have to match the library at the 7 bit boundaries
+    for value in [0, 127, 128, 16383, 16384, u64::MAX] {
+        assert_eq!(
+            WireVarInt::uint64_byte_len(value) as usize,
+            baseline::varint_len(value)
+        );
+    }
+
+    for num_level1 in [2, 100] {
+        let root = Root::new(num_level1);
+
+        // make sure all builders produce the same message
+        let mut two_pass = MsgBuilder::new();
+        let mut single_pass = SinglePassBuilder::new();
+        single_pass.set_compact(true);
+        let mut reverse = ReverseBuilder::new();
+        let expected = {
+            let mut s = two_pass.start(None);
+            ser_root(&root, &mut s);
+            let mut s = s.end();
+            ser_root(&root, &mut s);
+            s.end().to_vec()
+        };
+        {
+            let mut s = single_pass.start();
+            ser_root(&root, &mut s);
+            assert_eq!(expected, s.end().unwrap());
+        }
+        {
+            let mut s = reverse.start();
+            ser_root_rev(&root, &mut s);
+            assert_eq!(expected, s.end().unwrap());
+        }
+        let mut baseline_buf = Vec::new();
+        baseline::encode_root(&root, &mut baseline_buf);
+        assert_eq!(expected, baseline_buf);
+        let size = format!("{} bytes", expected.len());
+
+        b.bench(&format!("builders/two_pass/{size}"), || {
+            let mut s = two_pass.start(None);
+            ser_root(&root, &mut s);
+            let mut s = s.end();
+            ser_root(&root, &mut s);
+            s.end().len()
+        });
+        b.bench(&format!("builders/two_pass/{size}/baseline"), || {
+            baseline_buf.clear();
+            baseline::encode_root(&root, &mut baseline_buf);
+            baseline_buf.len()
+        });
+        b.bench(&format!("builders/single_pass/{size}"), || {
+            single_pass.set_compact(false);
+            let mut s = single_pass.start();
+            ser_root(&root, &mut s);
+            s.end().unwrap().len()
+        });
+        b.bench(&format!("builders/single_pass_compact/{size}"), || {
+            single_pass.set_compact(true);
+            let mut s = single_pass.start();
+            ser_root(&root, &mut s);
+            s.end().unwrap().len()
+        });
+        b.bench(&format!("builders/reverse/{size}"), || {
+            let mut s = reverse.start();
+            ser_root_rev(&root, &mut s);
+            s.end().unwrap().len()
+        });
+    }
+}
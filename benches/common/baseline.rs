@@ -0,0 +1,208 @@
+//! Hand-rolled encoding & decoding functions (in the style of generated
+//! protobuf code) used as baselines in the benchmarks.
+
+use super::{Level1, Level2, Root};
+
+/// Encodes a VARINT with a simple loop.
+#[inline]
+pub fn encode_varint(mut value: u64, buf: &mut Vec<u8>) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+/// Decodes a VARINT with a simple loop (`None` if the data ends or the
+/// VARINT is longer than 10 bytes).
+#[inline]
+pub fn decode_varint(data: &mut &[u8]) -> Option<u64> {
+    let mut result = 0u64;
+    for i in 0..10 {
+        let (&b, rest) = data.split_first()?;
+        *data = rest;
+        result |= u64::from(b & 0x7f) << (7 * i);
+        if b < 0x80 {
+            return Some(result);
+        }
+    }
+    None
+}
+
+/// Byte length of a VARINT.
+#[inline]
+pub fn varint_len(value: u64) -> usize {
+    ((64 - (value | 1).leading_zeros() as usize) * 9 + 64) / 64
+}
+
+#[inline]
+fn tag(field_number: u32, wire_type: u32, buf: &mut Vec<u8>) {
+    encode_varint(u64::from((field_number << 3) | wire_type), buf);
+}
+
+#[inline]
+fn tag_len(field_number: u32) -> usize {
+    varint_len(u64::from(field_number << 3))
+}
+
+#[inline]
+fn int32(field_number: u32, value: i32, buf: &mut Vec<u8>) {
+    tag(field_number, 0, buf);
+    encode_varint(value as i64 as u64, buf);
+}
+
+#[inline]
+fn int32_len(field_number: u32, value: i32) -> usize {
+    tag_len(field_number) + varint_len(value as i64 as u64)
+}
+
+#[inline]
+fn zigzag32(value: i32) -> u64 {
+    u64::from(((value << 1) ^ (value >> 31)) as u32)
+}
+
+#[inline]
+fn zigzag64(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+#[inline]
+fn string(field_number: u32, value: &str, buf: &mut Vec<u8>) {
+    tag(field_number, 2, buf);
+    encode_varint(value.len() as u64, buf);
+    buf.extend_from_slice(value.as_bytes());
+}
+
+#[inline]
+fn string_len(field_number: u32, value: &str) -> usize {
+    tag_len(field_number) + varint_len(value.len() as u64) + value.len()
+}
+
+fn level2_len(p: &Level2) -> usize {
+    tag_len(1) + varint_len(p.a) + string_len(2, &p.b)
+}
+
+fn level1_len(p: &Level1) -> usize {
+    let c: usize =
+        p.c.iter()
+            .map(|l| {
+                let len = level2_len(l);
+                tag_len(3) + varint_len(len as u64) + len
+            })
+            .sum();
+    tag_len(1) + varint_len(zigzag32(p.a)) + int32_len(2, p.b) + c + int32_len(4, p.d)
+}
+
+/// Byte length of the encoded [`Root`] message.
+pub fn root_len(p: &Root) -> usize {
+    let mut len = string_len(1, &p.name) + int32_len(2, p.id) + string_len(3, &p.email);
+    for l in &p.level1 {
+        let l_len = level1_len(l);
+        len += tag_len(4) + varint_len(l_len as u64) + l_len;
+    }
+    for (i, v) in p.u32s.iter().enumerate() {
+        len += tag_len(5 + i as u32) + varint_len(u64::from(*v));
+    }
+    for (i, v) in p.i32s.iter().enumerate() {
+        len += int32_len(8 + i as u32, *v);
+    }
+    for (i, v) in p.s32s.iter().enumerate() {
+        len += tag_len(11 + i as u32) + varint_len(zigzag32(*v));
+    }
+    for (i, v) in p.u64s.iter().enumerate() {
+        len += tag_len(14 + i as u32) + varint_len(*v);
+    }
+    for (i, v) in p.i64s.iter().enumerate() {
+        len += tag_len(17 + i as u32) + varint_len(*v as u64);
+    }
+    for (i, v) in p.s64s.iter().enumerate() {
+        len += tag_len(20 + i as u32) + varint_len(zigzag64(*v));
+    }
+    len
+}
+
+fn encode_level2(p: &Level2, buf: &mut Vec<u8>) {
+    tag(1, 0, buf);
+    encode_varint(p.a, buf);
+    string(2, &p.b, buf);
+}
+
+fn encode_level1(p: &Level1, buf: &mut Vec<u8>) {
+    tag(1, 0, buf);
+    encode_varint(zigzag32(p.a), buf);
+    int32(2, p.b, buf);
+    for l in &p.c {
+        tag(3, 2, buf);
+        encode_varint(level2_len(l) as u64, buf);
+        encode_level2(l, buf);
+    }
+    int32(4, p.d, buf);
+}
+
+/// Encodes the [`Root`] message (lengths of sub-messages are recomputed
+/// on every nesting level, same as in code generated by prost).
+pub fn encode_root(p: &Root, buf: &mut Vec<u8>) {
+    buf.reserve(root_len(p));
+    string(1, &p.name, buf);
+    int32(2, p.id, buf);
+    string(3, &p.email, buf);
+    for l in &p.level1 {
+        tag(4, 2, buf);
+        encode_varint(level1_len(l) as u64, buf);
+        encode_level1(l, buf);
+    }
+    for (i, v) in p.u32s.iter().enumerate() {
+        tag(5 + i as u32, 0, buf);
+        encode_varint(u64::from(*v), buf);
+    }
+    for (i, v) in p.i32s.iter().enumerate() {
+        int32(8 + i as u32, *v, buf);
+    }
+    for (i, v) in p.s32s.iter().enumerate() {
+        tag(11 + i as u32, 0, buf);
+        encode_varint(zigzag32(*v), buf);
+    }
+    for (i, v) in p.u64s.iter().enumerate() {
+        tag(14 + i as u32, 0, buf);
+        encode_varint(*v, buf);
+    }
+    for (i, v) in p.i64s.iter().enumerate() {
+        tag(17 + i as u32, 0, buf);
+        encode_varint(*v as u64, buf);
+    }
+    for (i, v) in p.s64s.iter().enumerate() {
+        tag(20 + i as u32, 0, buf);
+        encode_varint(zigzag64(*v), buf);
+    }
+}
+
+/// Iterates over all records of a message & returns the number of records
+/// & the sum of all VARINT values (`None` if the data is malformed).
+///
+/// `nested` contains the field number of the sub-messages to recurse into
+/// for each nesting level.
+pub fn walk(mut data: &[u8], nested: &[u64]) -> Option<(usize, u64)> {
+    let mut count = 0;
+    let mut sum = 0u64;
+    while !data.is_empty() {
+        let tag = decode_varint(&mut data)?;
+        count += 1;
+        match tag & 0b111 {
+            0 => sum = sum.wrapping_add(decode_varint(&mut data)?),
+            1 => data = data.get(8..)?,
+            2 => {
+                let len = decode_varint(&mut data)? as usize;
+                let value = data.get(..len)?;
+                data = &data[len..];
+                if nested.first() == Some(&(tag >> 3)) {
+                    let (c, s) = walk(value, &nested[1..])?;
+                    count += c;
+                    sum = sum.wrapping_add(s);
+                }
+            }
+            5 => data = data.get(4..)?,
+            _ => return None,
+        }
+    }
+    Some((count, sum))
+}
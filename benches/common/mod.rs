@@ -0,0 +1,250 @@
+//! Shared helpers of the benchmarks (a minimal criterion style harness &
+//! the message of the `gen_complex_msg` example).
+#![allow(dead_code)]
+// the benchmarks are not part of the MSRV build (black_box needs 1.66)
+#![allow(clippy::incompatible_msrv)]
+
+pub mod baseline;
+
+use protobin::builders::MsgScribe;
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+/// Number of measured samples per benchmark.
+const SAMPLES: usize = 50;
+
+/// Minimal duration of a single sample.
+const SAMPLE_DURATION: Duration = Duration::from_millis(2);
+
+/// Runs & prints benchmarks (only the ones containing the first command
+/// line argument if one is given, e.g. `cargo bench -- reverse`).
+pub struct Bencher {
+    filter: Option<String>,
+}
+
+impl Bencher {
+    pub fn from_args() -> Bencher {
+        Bencher {
+            // cargo passes "--bench" to the benchmark binaries
+            filter: std::env::args().skip(1).find(|arg| !arg.starts_with("--")),
+        }
+    }
+
+    /// Measures the given function & prints the median, fastest & slowest
+    /// sample in nanoseconds per iteration.
+    pub fn bench<R>(&self, name: &str, mut f: impl FnMut() -> R) {
+        if let Some(filter) = &self.filter {
+            if !name.contains(filter.as_str()) {
+                return;
+            }
+        }
+
+        // warm up & determine the iterations needed per sample
+        let mut iters: u64 = 1;
+        loop {
+            let start = Instant::now();
+            for _ in 0..iters {
+                black_box(f());
+            }
+            if start.elapsed() >= SAMPLE_DURATION {
+                break;
+            }
+            iters *= 2;
+        }
+
+        let mut samples: Vec<f64> = (0..SAMPLES)
+            .map(|_| {
+                let start = Instant::now();
+                for _ in 0..iters {
+                    black_box(f());
+                }
+                start.elapsed().as_nanos() as f64 / iters as f64
+            })
+            .collect();
+        samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        println!(
+            "{:<48} {:>10.1} ns/iter  [{:.1} .. {:.1}]",
+            name,
+            samples[SAMPLES / 2],
+            samples[0],
+            samples[SAMPLES - 1]
+        );
+    }
+}
+
+pub struct Level2 {
+    pub a: u64,
+    pub b: String,
+}
+
+pub struct Level1 {
+    pub a: i32,
+    pub b: i32,
+    pub c: Vec<Level2>,
+    pub d: i32,
+}
+
+/// Message of the `gen_complex_msg` example.
+pub struct Root {
+    pub name: String,
+    pub id: i32,
+    pub email: String,
+    pub level1: Vec<Level1>,
+    pub u32s: [u32; 3],
+    pub i32s: [i32; 3],
+    pub s32s: [i32; 3],
+    pub u64s: [u64; 3],
+    pub i64s: [i64; 3],
+    pub s64s: [i64; 3],
+}
+
+impl Root {
+    /// Data of the `gen_complex_msg` example (with the given number of
+    /// `level1` entries).
+    pub fn new(num_level1: usize) -> Root {
+        Root {
+            name: "Greg".to_owned(),
+            id: 1234,
+            email: "greg@greg.net".to_owned(),
+            level1: (0..num_level1)
+                .map(|i| Level1 {
+                    a: 1,
+                    b: 2,
+                    c: (0..i % 3)
+                        .map(|j| Level2 {
+                            a: j as u64 * 300,
+                            b: "bstring".repeat(j + 1),
+                        })
+                        .collect(),
+                    d: 4,
+                })
+                .collect(),
+            u32s: [u32::MIN, 0, u32::MAX],
+            i32s: [i32::MIN, 0, i32::MAX],
+            s32s: [i32::MIN, 0, i32::MAX],
+            u64s: [u64::MIN, 0, u64::MAX],
+            i64s: [i64::MIN, 0, i64::MAX],
+            s64s: [i64::MIN, 0, i64::MAX],
+        }
+    }
+}
+
+fn f(value: u32) -> protobin::FieldNumber {
+    value.try_into().unwrap()
+}
+
+fn ser_level2<S: MsgScribe>(p: &Level2, s: &mut S) {
+    s.add_uint64(f(1), p.a);
+    s.add_string(f(2), &p.b);
+}
+
+fn ser_level1<S: MsgScribe>(p: &Level1, s: &mut S) {
+    s.add_sint32(f(1), p.a);
+    s.add_int32(f(2), p.b);
+    for l in &p.c {
+        s.start_msg(f(3));
+        ser_level2(l, s);
+        s.end_msg(f(3));
+    }
+    s.add_int32(f(4), p.d);
+}
+
+/// Serializes the message (for the two pass & single pass builders).
+pub fn ser_root<S: MsgScribe>(p: &Root, s: &mut S) {
+    s.add_string(f(1), &p.name);
+    s.add_int32(f(2), p.id);
+    s.add_string(f(3), &p.email);
+    for l in &p.level1 {
+        s.start_msg(f(4));
+        ser_level1(l, s);
+        s.end_msg(f(4));
+    }
+    for (i, v) in p.u32s.iter().enumerate() {
+        s.add_uint32(f(5 + i as u32), *v);
+    }
+    for (i, v) in p.i32s.iter().enumerate() {
+        s.add_int32(f(8 + i as u32), *v);
+    }
+    for (i, v) in p.s32s.iter().enumerate() {
+        s.add_sint32(f(11 + i as u32), *v);
+    }
+    for (i, v) in p.u64s.iter().enumerate() {
+        s.add_uint64(f(14 + i as u32), *v);
+    }
+    for (i, v) in p.i64s.iter().enumerate() {
+        s.add_int64(f(17 + i as u32), *v);
+    }
+    for (i, v) in p.s64s.iter().enumerate() {
+        s.add_sint64(f(20 + i as u32), *v);
+    }
+}
+
+fn ser_level2_rev<S: MsgScribe>(p: &Level2, s: &mut S) {
+    s.add_string(f(2), &p.b);
+    s.add_uint64(f(1), p.a);
+}
+
+fn ser_level1_rev<S: MsgScribe>(p: &Level1, s: &mut S) {
+    s.add_int32(f(4), p.d);
+    for l in p.c.iter().rev() {
+        s.start_msg(f(3));
+        ser_level2_rev(l, s);
+        s.end_msg(f(3));
+    }
+    s.add_int32(f(2), p.b);
+    s.add_sint32(f(1), p.a);
+}
+
+/// Serializes the message with the fields in reverse order (for the
+/// `ReverseBuilder`).
+pub fn ser_root_rev<S: MsgScribe>(p: &Root, s: &mut S) {
+    for (i, v) in p.s64s.iter().enumerate().rev() {
+        s.add_sint64(f(20 + i as u32), *v);
+    }
+    for (i, v) in p.i64s.iter().enumerate().rev() {
+        s.add_int64(f(17 + i as u32), *v);
+    }
+    for (i, v) in p.u64s.iter().enumerate().rev() {
+        s.add_uint64(f(14 + i as u32), *v);
+    }
+    for (i, v) in p.s32s.iter().enumerate().rev() {
+        s.add_sint32(f(11 + i as u32), *v);
+    }
+    for (i, v) in p.i32s.iter().enumerate().rev() {
+        s.add_int32(f(8 + i as u32), *v);
+    }
+    for (i, v) in p.u32s.iter().enumerate().rev() {
+        s.add_uint32(f(5 + i as u32), *v);
+    }
+    for l in p.level1.iter().rev() {
+        s.start_msg(f(4));
+        ser_level1_rev(l, s);
+        s.end_msg(f(4));
+    }
+    s.add_string(f(3), &p.email);
+    s.add_int32(f(2), p.id);
+    s.add_string(f(1), &p.name);
+}
+
+/// Deterministic pseudo random values with VARINT lengths between 1 &
+/// `max_bytes` bytes (evenly distributed).
+pub fn varint_values(count: usize, max_bytes: u32) -> Vec<u64> {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    (0..count)
+        .map(|_| {
+            // xorshift
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let bytes = (state % u64::from(max_bytes)) as u32 + 1;
+            let bits = (7 * bytes).min(64);
+            if bits == 64 {
+                state | (1 << 63)
+            } else {
+                (state >> (64 - bits)) | (1 << (bits - 1))
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,72 @@
+//! Benchmarks of the message decoding (`cargo bench --bench decode`).
+
+mod common;
+
+use common::*;
+use protobin::{
+    builders::{MsgBuilder, MsgScribe},
+    decode::MsgDecoder,
+    wire::WireValueRef,
+};
+
+/// Iterates over all records with the [`MsgDecoder`] (same result as
+/// [`baseline::walk`]).
+fn walk(data: &[u8], nested: &[u64]) -> Option<(usize, u64)> {
+    let mut count = 0;
+    let mut sum = 0u64;
+    for record in MsgDecoder::new(data) {
+        let record = record.ok()?;
+        count += 1;
+        match record.value {
+            WireValueRef::VarInt(v) => sum = sum.wrapping_add(v.as_uint64()),
+            WireValueRef::Len(len)
+                if nested.first() == Some(&u64::from(record.field_number.value())) =>
+            {
+                let (c, s) = walk(len.data, &nested[1..])?;
+                count += c;
+                sum = sum.wrapping_add(s);
+            }
+            _ => {}
+        }
+    }
+    Some((count, sum))
+}
+
+/// Flat message with many VARINT fields (e.g. metrics or ids).
+fn ser_flat<S: MsgScribe>(values: &[u64], s: &mut S) {
+    for (i, v) in values.iter().enumerate() {
+        s.add_uint64(((i % 15) as u32 + 1).try_into().unwrap(), *v);
+    }
+}
+
+fn main() {
+    let b = Bencher::from_args();
+
+    // nested message of the gen_complex_msg example
+    let root = Root::new(100);
+    let mut builder = MsgBuilder::new();
+    let mut s = builder.start(None);
+    ser_root(&root, &mut s);
+    let mut s = s.end();
+    ser_root(&root, &mut s);
+    let complex = s.end().to_vec();
+
+    let values = varint_values(1000, 10);
+    let mut s = builder.start(None);
+    ser_flat(&values, &mut s);
+    let mut s = s.end();
+    ser_flat(&values, &mut s);
+    let flat = s.end().to_vec();
+
+    for (name, data, nested) in [
+        ("complex", &complex, &[4, 3][..]),
+        ("flat_varints", &flat, &[][..]),
+    ] {
+        assert_eq!(walk(data, nested), baseline::walk(data, nested));
+        let size = format!("{} bytes", data.len());
+        b.bench(&format!("decode/{name}/{size}"), || walk(data, nested));
+        b.bench(&format!("decode/{name}/{size}/baseline"), || {
+            baseline::walk(data, nested)
+        });
+    }
+}
@@ -0,0 +1,59 @@
+//! Benchmarks of the VARINT encoding & decoding (`cargo bench --bench wire`).
+
+mod common;
+
+use common::*;
+use protobin::wire::{WireDecoder, WireEncoder};
+
+fn main() {
+    let b = Bencher::from_args();
+
+    for (name, max_bytes) in [("1byte", 1), ("2bytes", 2), ("mixed", 10)] {
+        let values = varint_values(1000, max_bytes);
+
+        // encoded values (identical for protobin & the baseline)
+        let mut encoder = WireEncoder::new();
+        let mut baseline_buf = Vec::new();
+        for v in &values {
+            encoder.add_var_uint64(*v);
+            baseline::encode_varint(*v, &mut baseline_buf);
+        }
+        assert_eq!(encoder.buf, baseline_buf);
+        let encoded = baseline_buf.clone();
+
+        b.bench(&format!("wire/add_var_uint64/{name}/1000"), || {
+            encoder.buf.clear();
+            for v in &values {
+                encoder.add_var_uint64(*v);
+            }
+            encoder.buf.len()
+        });
+        b.bench(&format!("wire/add_var_uint64/{name}/1000/baseline"), || {
+            baseline_buf.clear();
+            for v in &values {
+                baseline::encode_varint(*v, &mut baseline_buf);
+            }
+            baseline_buf.len()
+        });
+
+        b.bench(&format!("wire/read_var_uint64/{name}/1000"), || {
+            let mut decoder = WireDecoder::new(&encoded);
+            let mut sum = 0u64;
+            while !decoder.data.is_empty() {
+                sum = sum.wrapping_add(decoder.read_var_uint64().unwrap());
+            }
+            sum
+        });
+        b.bench(
+            &format!("wire/read_var_uint64/{name}/1000/baseline"),
+            || {
+                let mut data = &encoded[..];
+                let mut sum = 0u64;
+                while !data.is_empty() {
+                    sum = sum.wrapping_add(baseline::decode_varint(&mut data).unwrap());
+                }
+                sum
+            },
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use protobin::builders::{MsgBuilder, MsgScribe};
-use std::{fs::File, i32, i64, io::Write, process::ExitCode, u32};
+use std::{fs::File, io::Write, process::ExitCode};
 
-const PROTO_FILE: &'static str = "
+const PROTO_FILE: &str = "
 syntax = \"proto3\";
 
 package dummy;
@@ -110,7 +110,7 @@ fn ser_level1<S: MsgScribe>(p: &Level1, mut s: S) -> S {
     s.add_int32(2.try_into().unwrap(), p.b);
     for l in &p.c {
         s.start_msg(3.try_into().unwrap());
-        s = ser_level2(&l, s);
+        s = ser_level2(l, s);
         s.end_msg(3.try_into().unwrap());
     }
     s.add_sint32(4.try_into().unwrap(), p.d);
@@ -124,7 +124,7 @@ fn ser_root<S: MsgScribe>(p: &Root, mut s: S) -> S {
 
     for l in &p.level1 {
         s.start_msg(4.try_into().unwrap());
-        s = ser_level1(&l, s);
+        s = ser_level1(l, s);
         s.end_msg(4.try_into().unwrap());
     }
 
@@ -227,7 +227,7 @@ fn main() -> ExitCode {
     let result = ser_root(&root, step2).end();
 
     // write to file
-    binary_file.write_all(&result).unwrap();
+    binary_file.write_all(result).unwrap();
     drop(binary_file);
 
     ExitCode::SUCCESS
@@ -1,7 +1,7 @@
 use protobin::builders::{MsgBuilder, MsgScribe};
 use std::{fs::File, io::Write, process::ExitCode};
 
-const PROTO_FILE: &'static str = "
+const PROTO_FILE: &str = "
 syntax = \"proto3\";
 
 package dummy;
@@ -61,7 +61,7 @@ fn main() -> ExitCode {
     let result = ser_person(&person, step2);
 
     // write to file
-    binary_file.write_all(&result).unwrap();
+    binary_file.write_all(result).unwrap();
     drop(binary_file);
 
     ExitCode::SUCCESS
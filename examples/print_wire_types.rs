@@ -16,8 +16,7 @@ fn main() {
     file.read_to_end(&mut data).unwrap();
     drop(file);
 
-    let mut msg_decoder = MsgDecoder::new(&data);
-    while let Some(re) = msg_decoder.next() {
+    for re in MsgDecoder::new(&data) {
         let value = re.unwrap();
         println!("{}: {:?}", value.field_number.value(), value.value);
     }
@@ -0,0 +1,130 @@
+use std::fmt::Write;
+
+use protobin::{
+    decode::*,
+    wire::{LenKind, WireValueRef},
+};
+
+/// Formats the records of a message as indented tree.
+///
+/// VARINT values are printed as unsigned decimal, I64 & I32 values as hex
+/// with 16 & 8 digits & LEN values as quoted string or nested block (see
+/// [`WireLenRef::kind_at_depth`](protobin::wire::WireLenRef::kind_at_depth)). The output can be encoded again via
+/// [`from_text`](crate::encode::from_text).
+pub fn tree(data: &[u8]) -> Result<String, DecodeError> {
+    let mut out = String::new();
+    write_msg(&mut out, data, 0)?;
+    Ok(out)
+}
+
+fn write_msg(out: &mut String, data: &[u8], depth: usize) -> Result<(), DecodeError> {
+    let mut depth = depth;
+    for record in MsgDecoder::new(data) {
+        let record = record?;
+        let n = record.field_number.value();
+        if let WireValueRef::EGroup = record.value {
+            depth = depth.saturating_sub(1);
+            indent(out, depth);
+            out.push_str("}\n");
+            continue;
+        }
+        indent(out, depth);
+        match record.value {
+            WireValueRef::VarInt(v) => writeln!(out, "{}: {}", n, v.as_uint64()).unwrap(),
+            WireValueRef::I64(v) => writeln!(out, "{}: 0x{:016x}", n, v.0).unwrap(),
+            WireValueRef::I32(v) => writeln!(out, "{}: 0x{:08x}", n, v.0).unwrap(),
+            WireValueRef::SGroup => {
+                writeln!(out, "{} {{", n).unwrap();
+                depth += 1;
+            }
+            WireValueRef::EGroup => unreachable!(),
+            WireValueRef::Len(l) => {
+                let bytes = l.as_bytes();
+                if l.kind_at_depth(depth) == LenKind::SubMsg {
+                    writeln!(out, "{} {{", n).unwrap();
+                    write_msg(out, bytes, depth + 1)?;
+                    indent(out, depth);
+                    out.push_str("}\n");
+                } else {
+                    writeln!(out, "{}: \"{}\"", n, escape(bytes)).unwrap();
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn indent(out: &mut String, depth: usize) {
+    for _ in 0..depth {
+        out.push_str("  ");
+    }
+}
+
+/// Escapes the data for a quoted string (valid UTF-8 is kept, other
+/// bytes are written as `\xHH`).
+pub fn escape(data: &[u8]) -> String {
+    let mut result = String::new();
+    let mut rest = data;
+    while !rest.is_empty() {
+        let (valid, invalid) = match std::str::from_utf8(rest) {
+            Ok(valid) => (valid, &[][..]),
+            Err(err) => {
+                let (valid, invalid) = rest.split_at(err.valid_up_to());
+                let invalid_len = err.error_len().unwrap_or(invalid.len());
+                // valid_up_to guarantees the first part is valid
+                (std::str::from_utf8(valid).unwrap(), &invalid[..invalid_len])
+            }
+        };
+        for c in valid.chars() {
+            match c {
+                '"' => result.push_str("\\\""),
+                '\\' => result.push_str("\\\\"),
+                '\n' => result.push_str("\\n"),
+                '\r' => result.push_str("\\r"),
+                '\t' => result.push_str("\\t"),
+                c if c.is_control() => {
+                    let mut buf = [0; 4];
+                    for b in c.encode_utf8(&mut buf).bytes() {
+                        write!(result, "\\x{:02x}", b).unwrap();
+                    }
+                }
+                c => result.push(c),
+            }
+        }
+        for b in invalid {
+            write!(result, "\\x{:02x}", b).unwrap();
+        }
+        rest = &rest[valid.len() + invalid.len()..];
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tree_format() {
+        let data = [
+            0x08, 0xac, 0x02, // 1: 300
+            0x11, 1, 0, 0, 0, 0, 0, 0, 0, // 2: fixed64 1
+            0x1d, 2, 0, 0, 0, // 3: fixed32 2
+            0x22, 0x03, b'a', b'"', b'b', // 4: "a\"b"
+            0x2a, 0x04, 0x08, 0x01, 0x10, 0x02, // 5 { 1: 1 2: 2 }
+            0x32, 0x02, 0xff, 0x00, // 6: bytes
+            0x3b, 0x08, 0x05, 0x3c, // 7 group { 1: 5 }
+        ];
+        assert_eq!(
+            Ok("1: 300\n\
+                2: 0x0000000000000001\n\
+                3: 0x00000002\n\
+                4: \"a\\\"b\"\n\
+                5 {\n  1: 1\n  2: 2\n}\n\
+                6: \"\\xff\\x00\"\n\
+                7 {\n  1: 5\n}\n"
+                .to_string()),
+            tree(&data)
+        );
+        assert_eq!(Err(DecodeError::Len), tree(&[0x0a, 0x05]));
+    }
+}
@@ -0,0 +1,339 @@
+use std::{iter::Peekable, str::Chars};
+
+use protobin::{
+    decode::Message,
+    wire::{WireI32, WireI64, WireValue, WireVarInt},
+    FieldNumber,
+};
+
+/// Encodes a message from the text format printed by `decode-raw`.
+///
+/// Every field is written as `<field number>: <value>` or as
+/// `<field number> { <fields> }` for sub-messages (groups printed by
+/// `decode-raw` are encoded as sub-messages). Values can be
+///
+/// * decimal integers (VARINT, negative values are encoded as `int64`),
+///   `true` or `false`,
+/// * hex integers with 8 digits (I32), 16 digits (I64) or any other number
+///   of digits (VARINT), e.g. `0x0000000000000001`,
+/// * floating point numbers (I64 `double`, I32 `float` with `f` suffix),
+///   e.g. `1.5` or `1.5f`,
+/// * quoted strings with the escapes `\"`, `\\`, `\n`, `\r`, `\t`, `\xHH` &
+///   `\NNN` (octal), encoded as LEN.
+///
+/// `#` starts a comment that ends at the end of the line.
+pub fn from_text(text: &str) -> Result<Vec<u8>, String> {
+    let mut tokens = Tokenizer {
+        chars: text.chars().peekable(),
+    };
+    Ok(parse_fields(&mut tokens, false)?.encode())
+}
+
+/// Encodes a message from JSON as printed by `decode-raw --json`.
+///
+/// The top level value has to be an object with field numbers as keys.
+/// Integers are encoded as VARINT (negative values as `int64`), other
+/// numbers as `double`, strings as LEN, booleans as VARINT, objects as
+/// sub-messages & arrays as repeated fields (`null` values are skipped).
+/// Note that I64 & I32 values printed by `decode-raw --json` are encoded
+/// as VARINT & bytes as repeated VARINT.
+pub fn from_json(text: &str) -> Result<Vec<u8>, String> {
+    let value: serde_json::Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    Ok(json_msg(&value)?.encode())
+}
+
+fn field_number(text: &str) -> Result<FieldNumber, String> {
+    text.parse::<u32>()
+        .ok()
+        .and_then(|v| FieldNumber::try_from(v).ok())
+        .ok_or_else(|| format!("invalid field number '{}'", text))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Str(Vec<u8>),
+    Colon,
+    Open,
+    Close,
+}
+
+struct Tokenizer<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn string(&mut self) -> Result<Vec<u8>, String> {
+        let mut result = Vec::new();
+        loop {
+            let c = self.chars.next().ok_or("unterminated string")?;
+            match c {
+                '"' => return Ok(result),
+                '\\' => {
+                    let c = self.chars.next().ok_or("unterminated string")?;
+                    match c {
+                        'n' => result.push(b'\n'),
+                        'r' => result.push(b'\r'),
+                        't' => result.push(b'\t'),
+                        'x' => {
+                            let hex: String = (0..2).filter_map(|_| self.chars.next()).collect();
+                            let b = u8::from_str_radix(&hex, 16)
+                                .map_err(|_| format!("invalid escape '\\x{}'", hex))?;
+                            result.push(b);
+                        }
+                        '0'..='7' => {
+                            let mut value = c.to_digit(8).unwrap();
+                            for _ in 0..2 {
+                                match self.chars.peek().and_then(|c| c.to_digit(8)) {
+                                    Some(d) => {
+                                        value = value * 8 + d;
+                                        self.chars.next();
+                                    }
+                                    None => break,
+                                }
+                            }
+                            let b = u8::try_from(value)
+                                .map_err(|_| format!("invalid octal escape '{:o}'", value))?;
+                            result.push(b);
+                        }
+                        c => {
+                            let mut buf = [0; 4];
+                            result.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                        }
+                    }
+                }
+                c => {
+                    let mut buf = [0; 4];
+                    result.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Result<Token, String>;
+
+    fn next(&mut self) -> Option<Result<Token, String>> {
+        loop {
+            let c = self.chars.next()?;
+            return Some(Ok(match c {
+                c if c.is_whitespace() => continue,
+                '#' => {
+                    for c in self.chars.by_ref() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                    continue;
+                }
+                ':' => Token::Colon,
+                '{' => Token::Open,
+                '}' => Token::Close,
+                '"' => return Some(self.string().map(Token::Str)),
+                c => {
+                    let mut word = c.to_string();
+                    while let Some(&c) = self.chars.peek() {
+                        if c.is_whitespace() || matches!(c, ':' | '{' | '}' | '"' | '#') {
+                            break;
+                        }
+                        word.push(c);
+                        self.chars.next();
+                    }
+                    Token::Word(word)
+                }
+            }));
+        }
+    }
+}
+
+fn parse_fields(tokens: &mut Tokenizer<'_>, nested: bool) -> Result<Message, String> {
+    let mut msg = Message::new();
+    loop {
+        match tokens.next().transpose()? {
+            None if nested => return Err("missing '}'".to_string()),
+            None => return Ok(msg),
+            Some(Token::Close) if nested => return Ok(msg),
+            Some(Token::Word(word)) => {
+                let field_number = field_number(&word)?;
+                match tokens.next().transpose()? {
+                    Some(Token::Colon) => {
+                        let value = match tokens.next().transpose()? {
+                            Some(Token::Word(word)) => parse_value(&word)?,
+                            Some(Token::Str(data)) => WireValue::Len(data),
+                            _ => return Err(format!("missing value of field {}", word)),
+                        };
+                        msg.push(field_number, value);
+                    }
+                    Some(Token::Open) => {
+                        let sub = parse_fields(tokens, true)?;
+                        msg.push(field_number, WireValue::Len(sub.encode()));
+                    }
+                    _ => return Err(format!("expected ':' or '{{' after field {}", word)),
+                }
+            }
+            Some(token) => return Err(format!("unexpected {:?}", token)),
+        }
+    }
+}
+
+fn parse_value(word: &str) -> Result<WireValue, String> {
+    let invalid = || format!("invalid value '{}'", word);
+    if word == "true" || word == "false" {
+        return Ok(WireValue::VarInt(WireVarInt::from_bool(word == "true")));
+    }
+    if let Some(hex) = word.strip_prefix("0x") {
+        return match hex.len() {
+            8 => u32::from_str_radix(hex, 16).map(|v| WireValue::I32(WireI32(v))),
+            16 => u64::from_str_radix(hex, 16).map(|v| WireValue::I64(WireI64(v))),
+            _ => {
+                u64::from_str_radix(hex, 16).map(|v| WireValue::VarInt(WireVarInt::from_uint64(v)))
+            }
+        }
+        .map_err(|_| invalid());
+    }
+    let is_float = word.contains(['.', 'e', 'E']) || word.contains("inf") || word.contains("nan");
+    if is_float {
+        return match word.strip_suffix('f') {
+            Some(f) => f
+                .parse::<f32>()
+                .map(|v| WireValue::I32(WireI32(v.to_bits()))),
+            None => word
+                .parse::<f64>()
+                .map(|v| WireValue::I64(WireI64(v.to_bits()))),
+        }
+        .map_err(|_| invalid());
+    }
+    if word.starts_with('-') {
+        word.parse::<i64>()
+            .map(|v| WireValue::VarInt(WireVarInt::from_int64(v)))
+            .map_err(|_| invalid())
+    } else {
+        word.parse::<u64>()
+            .map(|v| WireValue::VarInt(WireVarInt::from_uint64(v)))
+            .map_err(|_| invalid())
+    }
+}
+
+fn json_msg(value: &serde_json::Value) -> Result<Message, String> {
+    let object = value
+        .as_object()
+        .ok_or_else(|| format!("expected an object, got {}", value))?;
+    // keys are sorted as strings, restore the field number order
+    let mut fields = object
+        .iter()
+        .map(|(key, value)| Ok((field_number(key)?, key, value)))
+        .collect::<Result<Vec<_>, String>>()?;
+    fields.sort_by_key(|(field_number, _, _)| *field_number);
+
+    let mut msg = Message::new();
+    for (field_number, key, value) in fields {
+        match value {
+            serde_json::Value::Array(values) => {
+                for value in values {
+                    if value.is_array() {
+                        return Err(format!("nested array in field {}", key));
+                    }
+                    json_value(&mut msg, field_number, value)?;
+                }
+            }
+            value => json_value(&mut msg, field_number, value)?,
+        }
+    }
+    Ok(msg)
+}
+
+fn json_value(
+    msg: &mut Message,
+    field_number: FieldNumber,
+    value: &serde_json::Value,
+) -> Result<(), String> {
+    use serde_json::Value::*;
+    let value = match value {
+        Null => return Ok(()),
+        Bool(v) => WireValue::VarInt(WireVarInt::from_bool(*v)),
+        Number(n) => {
+            if let Some(v) = n.as_u64() {
+                WireValue::VarInt(WireVarInt::from_uint64(v))
+            } else if let Some(v) = n.as_i64() {
+                WireValue::VarInt(WireVarInt::from_int64(v))
+            } else {
+                let v = n.as_f64().ok_or_else(|| format!("invalid number {}", n))?;
+                WireValue::I64(WireI64(v.to_bits()))
+            }
+        }
+        String(s) => WireValue::Len(s.as_bytes().to_vec()),
+        Object(_) => WireValue::Len(json_msg(value)?.encode()),
+        Array(_) => unreachable!("handled by json_msg"),
+    };
+    msg.push(field_number, value);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode_raw::tree;
+
+    #[test]
+    fn text() {
+        let text = "1: 300 # comment\n\
+                    2: 0x0000000000000001\n\
+                    3: 0x00000002\n\
+                    4: \"a\\\"b\\x00\\101\"\n\
+                    5 {\n  1: -1\n  2 { 3: true }\n}\n\
+                    6: 1.5\n\
+                    7: 1.5f\n\
+                    8: 0x10\n";
+        let data = from_text(text).unwrap();
+        assert_eq!(
+            vec![
+                0x08, 0xac, 0x02, // 1: 300
+                0x11, 1, 0, 0, 0, 0, 0, 0, 0, // 2: fixed64 1
+                0x1d, 2, 0, 0, 0, // 3: fixed32 2
+                0x22, 0x05, b'a', b'"', b'b', 0x00, b'A', // 4: string
+                0x2a, 0x0f, 0x08, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01, 0x12,
+                0x02, 0x18, 0x01, // 5 { 1: -1 2 { 3: true } }
+                0x31, 0, 0, 0, 0, 0, 0, 0xf8, 0x3f, // 6: 1.5
+                0x3d, 0, 0, 0xc0, 0x3f, // 7: 1.5f
+                0x40, 0x10, // 8: 16
+            ],
+            data
+        );
+
+        // decode-raw output can be encoded again
+        let printed = tree(&data).unwrap();
+        assert_eq!(Ok(data), from_text(&printed));
+    }
+
+    #[test]
+    fn text_error() {
+        assert!(from_text("0: 1").is_err());
+        assert!(from_text("1 2").is_err());
+        assert!(from_text("1: x").is_err());
+        assert!(from_text("1 { 2: 3").is_err());
+        assert!(from_text("}").is_err());
+        assert!(from_text("1: \"abc").is_err());
+        assert!(from_text("1:").is_err());
+    }
+
+    #[test]
+    fn json() {
+        let data =
+            from_json(r#"{"1": [300, -1], "2": {"1": "x", "2": null}, "3": true, "10": 0.5}"#)
+                .unwrap();
+        assert_eq!(
+            vec![
+                0x08, 0xac, 0x02, // 1: 300
+                0x08, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01, // 1: -1
+                0x12, 0x03, 0x0a, 0x01, b'x', // 2 { 1: "x" }
+                0x18, 0x01, // 3: true
+                0x51, 0, 0, 0, 0, 0, 0, 0xe0, 0x3f, // 10: 0.5
+            ],
+            data
+        );
+        assert!(from_json("[1]").is_err());
+        assert!(from_json(r#"{"a": 1}"#).is_err());
+        assert!(from_json(r#"{"1": [[1]]}"#).is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use std::{fmt::Write, ops::Range};
+
+use protobin::{
+    decode::*,
+    wire::{LenKind, WireLenRef, WireValueRef},
+};
+
+use crate::decode_raw::escape;
+
+/// Number of bytes shown per line.
+const BYTES_PER_LINE: usize = 16;
+
+/// Maximum number of characters of a string shown in the annotation.
+const MAX_STR_CHARS: usize = 32;
+
+/// ANSI colors of the tag, length prefix & value bytes.
+const TAG_COLOR: &str = "\x1b[33m";
+const LEN_COLOR: &str = "\x1b[36m";
+const VALUE_COLOR: &str = "\x1b[32m";
+const RESET_COLOR: &str = "\x1b[0m";
+
+/// Formats an annotated hex dump of a message.
+///
+/// Every line starts with the offset of its first byte in hex followed by
+/// the bytes of a record & its annotation (field number, wire type &
+/// value). Sub-messages (see [`WireLenRef::kind_at_depth`]) are shown as a line containing
+/// the tag & length followed by the indented records of the sub-message.
+/// If `color` is set the tag, length prefix & value bytes are colored via
+/// ANSI escape codes.
+///
+/// On error the message contains the offset of the record that could not
+/// be decoded.
+pub fn hex_dump(data: &[u8], color: bool) -> Result<String, String> {
+    let mut out = String::new();
+    let mut tree = MsgSpanTree::new(data, |span| {
+        WireLenRef {
+            data: &data[span.value.clone()],
+        }
+        .kind_at_depth(span.depth)
+            == LenKind::SubMsg
+    });
+    while let Some(span) = tree.next() {
+        let span = span.map_err(|err| {
+            format!(
+                "{:?} at offset 0x{:08x}",
+                err,
+                tree.error_offset().unwrap_or_default()
+            )
+        })?;
+        let n = span.record.field_number.value();
+        let mut end = span.value.end;
+        let note = match span.record.value {
+            WireValueRef::VarInt(v) => format!("{}: VARINT {}", n, v.as_uint64()),
+            WireValueRef::I64(v) => format!("{}: I64 0x{:016x}", n, v.0),
+            WireValueRef::I32(v) => format!("{}: I32 0x{:08x}", n, v.0),
+            WireValueRef::SGroup => format!("{}: SGROUP", n),
+            WireValueRef::EGroup => format!("{}: EGROUP", n),
+            WireValueRef::Len(l) => {
+                let bytes = l.as_bytes();
+                match l.kind_at_depth(span.depth) {
+                    LenKind::SubMsg => {
+                        // only tag & length, the records of the sub-message follow
+                        end = span.value.start;
+                        format!("{}: LEN {} message", n, bytes.len())
+                    }
+                    LenKind::String => {
+                        let s: String = l
+                            .try_as_string()
+                            .unwrap_or_default()
+                            .chars()
+                            .take(MAX_STR_CHARS)
+                            .collect();
+                        let more = if s.len() < bytes.len() { "..." } else { "" };
+                        let s = escape(s.as_bytes());
+                        format!("{}: LEN {} \"{}\"{}", n, bytes.len(), s, more)
+                    }
+                    LenKind::PackedVarInt => format!("{}: LEN {} packed VARINT", n, bytes.len()),
+                    LenKind::PackedI32 => format!("{}: LEN {} packed I32", n, bytes.len()),
+                    LenKind::PackedI64 => format!("{}: LEN {} packed I64", n, bytes.len()),
+                    LenKind::Bytes => format!("{}: LEN {} bytes", n, bytes.len()),
+                }
+            }
+        };
+        line(&mut out, data, &span, end, &note, color);
+    }
+    Ok(out)
+}
+
+/// Writes the bytes of the span up to `end` (multiple lines if needed) with
+/// the note after the first line.
+fn line(out: &mut String, data: &[u8], span: &RecordSpan<'_>, end: usize, note: &str, color: bool) {
+    let start = span.tag.start;
+    let mut pos = start;
+    while pos < end {
+        let line_end = end.min(pos + BYTES_PER_LINE);
+        write!(out, "{:08x}  {:indent$}", pos, "", indent = span.depth * 2).unwrap();
+        for (i, b) in data[pos..line_end].iter().enumerate() {
+            if i > 0 {
+                out.push(' ');
+            }
+            if color {
+                out.push_str(byte_color(span, pos + i));
+                write!(out, "{:02x}", b).unwrap();
+                out.push_str(RESET_COLOR);
+            } else {
+                write!(out, "{:02x}", b).unwrap();
+            }
+        }
+        if pos == start {
+            let padding = (BYTES_PER_LINE - (line_end - pos)) * 3;
+            writeln!(out, "{:padding$}  {}", "", note, padding = padding).unwrap();
+        } else {
+            out.push('\n');
+        }
+        pos = line_end;
+    }
+}
+
+fn byte_color(span: &RecordSpan<'_>, index: usize) -> &'static str {
+    let contains = |range: &Range<usize>| range.contains(&index);
+    if contains(&span.tag) {
+        TAG_COLOR
+    } else if span.len.as_ref().map_or(false, contains) {
+        LEN_COLOR
+    } else {
+        VALUE_COLOR
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dump() {
+        let data = [
+            0x08, 0xac, 0x02, // 1: 300
+            0x12, 0x04, 0x0a, 0x02, b'h', b'i', // 2 { 1: "hi" }
+            0x1d, 2, 0, 0, 0, // 3: fixed32 2
+            0x22, 0x02, 0x01, 0x02, // 4: packed [1, 2]
+        ];
+        let expected = "\
+00000000  08 ac 02                                         1: VARINT 300
+00000003  12 04                                            2: LEN 4 message
+00000005    0a 02 68 69                                      1: LEN 2 \"hi\"
+00000009  1d 02 00 00 00                                   3: I32 0x00000002
+0000000e  22 02 01 02                                      4: LEN 2 packed VARINT
+";
+        assert_eq!(Ok(expected.to_string()), hex_dump(&data, false));
+
+        // values longer than one line
+        let mut data = vec![0x0a, 20];
+        data.extend_from_slice(&[0xff; 20]);
+        let expected = "\
+00000000  0a 14 ff ff ff ff ff ff ff ff ff ff ff ff ff ff  1: LEN 20 bytes
+00000010  ff ff ff ff ff ff
+";
+        assert_eq!(Ok(expected.to_string()), hex_dump(&data, false));
+
+        assert_eq!(
+            Err("Len at offset 0x00000002".to_string()),
+            hex_dump(&[0x08, 0x01, 0x0a, 0x05], false)
+        );
+
+        // colored tag, length & value bytes
+        let expected = "\
+00000000  \x1b[33m0a\x1b[0m \x1b[36m01\x1b[0m \x1b[32m78\x1b[0m                                         1: LEN 1 \"x\"
+";
+        assert_eq!(
+            Ok(expected.to_string()),
+            hex_dump(&[0x0a, 0x01, b'x'], true)
+        );
+    }
+}
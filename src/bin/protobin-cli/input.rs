@@ -0,0 +1,54 @@
+use std::{io::Read, path::Path};
+
+/// Reads the given file or stdin if no file or `-` is given.
+pub fn read(file: Option<&Path>) -> Result<Vec<u8>, String> {
+    let mut data = Vec::new();
+    match file {
+        Some(path) if path != Path::new("-") => {
+            data = std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        }
+        _ => {
+            std::io::stdin()
+                .read_to_end(&mut data)
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(data)
+}
+
+/// Decodes hex text (whitespace is ignored).
+pub fn from_hex(text: &[u8]) -> Result<Vec<u8>, String> {
+    let digits: Vec<u8> = text
+        .iter()
+        .copied()
+        .filter(|c| !c.is_ascii_whitespace())
+        .collect();
+    if digits.len() % 2 != 0 {
+        return Err("odd number of hex digits".to_string());
+    }
+    digits
+        .chunks(2)
+        .map(|pair| {
+            let s = std::str::from_utf8(pair).map_err(|e| e.to_string())?;
+            u8::from_str_radix(s, 16).map_err(|_| format!("invalid hex digits '{}'", s))
+        })
+        .collect()
+}
+
+/// Encodes the data as lowercase hex text.
+pub fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex() {
+        assert_eq!(Ok(vec![0x08, 0xac, 0x02]), from_hex(b"08 AC\n02\n"));
+        assert_eq!("08ac02", to_hex(&[0x08, 0xac, 0x02]));
+        assert!(from_hex(b"0").is_err());
+        assert!(from_hex(b"0g").is_err());
+    }
+}
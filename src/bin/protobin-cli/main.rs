@@ -0,0 +1,180 @@
+use std::{io::Write, path::PathBuf, process::ExitCode};
+
+use clap::{Args, Parser, Subcommand};
+
+mod decode_raw;
+mod encode;
+mod hex_dump;
+mod input;
+mod split;
+
+/// Inspect & build protobuf binary messages without a schema.
+#[derive(Parser)]
+#[command(name = "protobin-cli", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the records of a message as tree (similar to
+    /// `protoc --decode_raw`).
+    DecodeRaw {
+        #[command(flatten)]
+        input: BinInput,
+        /// Print the message as JSON (field numbers as keys).
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print an annotated hex dump showing the bytes of every record.
+    Hex {
+        #[command(flatten)]
+        input: BinInput,
+        /// Color the tag, length prefix & value bytes of every record.
+        #[arg(long)]
+        color: bool,
+    },
+    /// Encode a message from text (as printed by `decode-raw`) or JSON.
+    ///
+    /// Only the text format round-trips: JSON does not contain the wire
+    /// types, so I32 & I64 values are encoded as VARINT & bytes as
+    /// repeated VARINT.
+    Encode {
+        /// Input file (stdin if not given or `-`).
+        file: Option<PathBuf>,
+        /// The input is JSON (as printed by `decode-raw --json`, lossy).
+        #[arg(long)]
+        json: bool,
+        /// Write the message as hex text instead of binary data.
+        #[arg(long)]
+        hex: bool,
+    },
+    /// Infer a probable `.proto` schema from samples of the same message
+    /// type.
+    Infer {
+        /// Sample files (one message per file).
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// The samples are hex text instead of binary data.
+        #[arg(long)]
+        hex: bool,
+        /// Name of the inferred message.
+        #[arg(long, default_value = "Message")]
+        name: String,
+    },
+    /// Split a stream of length-delimited messages (VARINT length prefix
+    /// before every message).
+    Split {
+        #[command(flatten)]
+        input: BinInput,
+        /// Write every message to `<index>.bin` in the given directory
+        /// instead of printing one hex line per message.
+        #[arg(long)]
+        out_dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Args)]
+struct BinInput {
+    /// Input file (stdin if not given or `-`).
+    file: Option<PathBuf>,
+    /// The input is hex text instead of binary data.
+    #[arg(long)]
+    hex: bool,
+}
+
+impl BinInput {
+    fn read(&self) -> Result<Vec<u8>, String> {
+        let data = input::read(self.file.as_deref())?;
+        if self.hex {
+            input::from_hex(&data)
+        } else {
+            Ok(data)
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    let mut stdout = std::io::stdout().lock();
+    let output = match cli.command {
+        Command::DecodeRaw { input, json } => {
+            let data = input.read()?;
+            if json {
+                let decoder = protobin::decode::MsgDecoder::new(&data);
+                let mut text = serde_json::to_string_pretty(&decoder).map_err(|e| e.to_string())?;
+                text.push('\n');
+                text.into_bytes()
+            } else {
+                decode_raw::tree(&data)
+                    .map_err(|e| format!("{:?}", e))?
+                    .into_bytes()
+            }
+        }
+        Command::Hex { input, color } => hex_dump::hex_dump(&input.read()?, color)?.into_bytes(),
+        Command::Encode { file, json, hex } => {
+            let text = input::read(file.as_deref())?;
+            let text = String::from_utf8(text).map_err(|e| e.to_string())?;
+            let data = if json {
+                encode::from_json(&text)?
+            } else {
+                encode::from_text(&text)?
+            };
+            if hex {
+                let mut text = input::to_hex(&data);
+                text.push('\n');
+                text.into_bytes()
+            } else {
+                data
+            }
+        }
+        Command::Infer { files, hex, name } => {
+            let mut inferrer = protobin::schema::SchemaInferrer::new();
+            for file in files {
+                let input = BinInput {
+                    file: Some(file),
+                    hex,
+                };
+                let data = input.read()?;
+                let path = input.file.unwrap_or_default();
+                inferrer
+                    .add(&data)
+                    .map_err(|e| format!("{}: {:?}", path.display(), e))?;
+            }
+            inferrer.infer(&name).to_proto().into_bytes()
+        }
+        Command::Split { input, out_dir } => {
+            let data = input.read()?;
+            let messages = split::split(&data).map_err(|e| format!("{:?}", e))?;
+            match out_dir {
+                Some(dir) => {
+                    for (index, msg) in messages.iter().enumerate() {
+                        let path = dir.join(format!("{:06}.bin", index));
+                        std::fs::write(&path, msg)
+                            .map_err(|e| format!("{}: {}", path.display(), e))?;
+                    }
+                    Vec::new()
+                }
+                None => {
+                    let mut text = String::new();
+                    for msg in messages {
+                        text.push_str(&input::to_hex(msg));
+                        text.push('\n');
+                    }
+                    text.into_bytes()
+                }
+            }
+        }
+    };
+    stdout.write_all(&output).map_err(|e| e.to_string())
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use protobin::{decode::DecodeError, wire::WireDecoder};
+
+/// Splits a stream of messages that are each prefixed with their length
+/// as VARINT.
+pub fn split(data: &[u8]) -> Result<Vec<&[u8]>, DecodeError> {
+    let mut decoder = WireDecoder::new(data);
+    let mut result = Vec::new();
+    while !decoder.data.is_empty() {
+        let len = decoder.read_var_uint64()?;
+        let len = usize::try_from(len).map_err(|_| DecodeError::Len)?;
+        result.push(decoder.take_nbyte(len)?);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_stream() {
+        let data = [0x02, 0x08, 0x01, 0x00, 0x01, 0x10];
+        assert_eq!(Ok(vec![&[0x08, 0x01][..], &[], &[0x10]]), split(&data));
+        assert_eq!(Ok(vec![]), split(&[]));
+        assert_eq!(Err(DecodeError::Len), split(&[0x03, 0x08, 0x01]));
+    }
+}
@@ -0,0 +1,132 @@
+use crate::builders::*;
+use bytes::BytesMut;
+
+/// Serialization pass that appends the message to a [`BytesMut`] (use
+/// [`MsgLenBuilder::end_bytes_mut`] to create).
+///
+/// Same as [`MsgSerBuilder`] but instead of the internal buffer of the
+/// [`MsgBuilder`] the message is written to the given [`BytesMut`].
+/// [`end`](MsgScribe::end) splits the bytes written by the serialization
+/// pass off as [`bytes::Bytes`] without copying, so the encoded message can
+/// be passed on (e.g. to `tokio` or `hyper`). Bytes that were already in
+/// the [`BytesMut`] before stay in it. If it was empty, it keeps the
+/// remaining capacity for the next message.
+///
+/// ```
+/// use bytes::BytesMut;
+/// use protobin::builders::*;
+///
+/// fn ser<S: MsgScribe>(s: &mut S) {
+///     let f = 1.try_into().unwrap();
+///     s.start_msg(f);
+///     s.add_string(2.try_into().unwrap(), "a");
+///     s.end_msg(f);
+/// }
+///
+/// let mut builder = MsgBuilder::new();
+/// let mut out = BytesMut::with_capacity(1024);
+///
+/// let mut s = builder.start(None);
+/// ser(&mut s);
+/// let mut s = s.end_bytes_mut(&mut out);
+/// ser(&mut s);
+/// let bytes = s.end();
+/// assert_eq!(&bytes[..], &[0x0a, 0x03, 0x12, 0x01, b'a']);
+/// ```
+pub type BytesMsgSerBuilder<'a, 'b> = MsgSerBuilder<'a, &'b mut BytesMut>;
+
+#[cfg(test)]
+mod tests {
+    use crate::{builders::*, FieldNumber};
+    use bytes::BytesMut;
+
+    const MSG: FieldNumber = FieldNumber(1);
+    const STR: FieldNumber = FieldNumber(2);
+    const PACKED: FieldNumber = FieldNumber(3);
+    const SCALAR: FieldNumber = FieldNumber(4);
+
+    fn ser<S: MsgScribe>(depth: usize, s: &mut S) {
+        s.add_string(STR, "abc")
+            .add_bytes(STR, &[0xff; 130])
+            .add_encoded_msg(MSG, &[0x08, 0x01])
+            .add_raw_record(&[0x20, 0x01]);
+        s.add_display_str(STR, &1.5).unwrap();
+        s.add_debug_str(STR, &"y").unwrap();
+        s.start_packed(PACKED)
+            .add_uint32(300)
+            .add_sint32(-1)
+            .add_int32(-2)
+            .add_bool(true)
+            .add_fixed32(7)
+            .add_double(1.5);
+        s.end_packed(PACKED);
+        s.add_int64(SCALAR, -4)
+            .add_sint64(SCALAR, -5)
+            .add_enum(SCALAR, -6)
+            .add_sfixed32(SCALAR, -7)
+            .add_fixed64(SCALAR, 8)
+            .add_float(SCALAR, 9.5);
+        if depth > 0 {
+            s.start_msg(MSG);
+            ser(depth - 1, s);
+            s.end_msg(MSG);
+        }
+    }
+
+    #[test]
+    fn same_as_ser_builder() {
+        let mut builder = MsgBuilder::new();
+        let mut out = BytesMut::new();
+        for depth in 0..3 {
+            let expected = {
+                let mut s = builder.start(None);
+                ser(depth, &mut s);
+                let mut s = s.end();
+                ser(depth, &mut s);
+                s.end().to_vec()
+            };
+
+            let mut s = builder.start(None);
+            ser(depth, &mut s);
+            let mut s = s.end_bytes_mut(&mut out);
+            ser(depth, &mut s);
+            assert_eq!(expected, s.end());
+            assert!(out.is_empty());
+        }
+    }
+
+    #[test]
+    fn keeps_existing_data() {
+        let mut builder = MsgBuilder::new();
+        let mut out = BytesMut::new();
+        out.extend_from_slice(&[0xaa]);
+
+        let mut s = builder.start(None);
+        s.add_uint32(MSG, 1);
+        let mut s = s.end_bytes_mut(&mut out);
+        s.add_uint32(MSG, 1);
+        assert_eq!(&[0x08, 0x01][..], &s.end()[..]);
+        assert_eq!(&[0xaa][..], &out[..]);
+    }
+
+    #[test]
+    fn try_end() {
+        let mut builder = MsgBuilder::new();
+        let mut out = BytesMut::new();
+
+        let mut s = builder.start(None);
+        s.start_msg(MSG);
+        s.add_uint32(SCALAR, 1);
+        s.end_msg(MSG);
+        let mut s = s.end_bytes_mut(&mut out);
+        s.try_start_msg_field(MSG).unwrap();
+        s.add_uint32(SCALAR, 1);
+        assert_eq!(
+            Err(EncodeError::Unclosed {
+                field_number: MSG,
+                t: LenAreaType::Msg
+            }),
+            s.try_end()
+        );
+    }
+}
@@ -0,0 +1,6 @@
+use crate::builders::*;
+use bytes::BytesMut;
+
+/// Serializes the values of a packed field into a `BytesMut` (see
+/// [`BytesMsgSerBuilder`]).
+pub type BytesMsgSerPackedScribe<'a, 'b, 'c> = MsgSerPackedScribe<'a, 'c, &'b mut BytesMut>;
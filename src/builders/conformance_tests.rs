@@ -0,0 +1,329 @@
+//! Conformance tests pinning the encoding of every scalar type across
+//! all builder & scribe implementations ([`MsgLenBuilder`],
+//! [`MsgSerBuilder`], [`MsgLenPackedScribe`] & [`MsgSerPackedScribe`])
+//! and the matching decoding accessors.
+//!
+//! The expected encodings match the output of the protobuf reference
+//! implementation.
+
+use crate::{builders::*, decode::*, wire::*, FieldNumber};
+
+/// Scalar value together with its protobuf type.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Scalar<'a> {
+    Int32(i32),
+    Int64(i64),
+    UInt32(u32),
+    UInt64(u64),
+    SInt32(i32),
+    SInt64(i64),
+    Bool(bool),
+    Enum(i32),
+    Fixed32(u32),
+    SFixed32(i32),
+    Float(f32),
+    Fixed64(u64),
+    SFixed64(i64),
+    Double(f64),
+    String(&'a str),
+    Bytes(&'a [u8]),
+}
+
+impl<'a> Scalar<'a> {
+    fn wire_type(&self) -> WireType {
+        use Scalar::*;
+        match self {
+            Int32(_) | Int64(_) | UInt32(_) | UInt64(_) | SInt32(_) | SInt64(_) | Bool(_)
+            | Enum(_) => WireType::VarInt,
+            Fixed32(_) | SFixed32(_) | Float(_) => WireType::I32,
+            Fixed64(_) | SFixed64(_) | Double(_) => WireType::I64,
+            String(_) | Bytes(_) => WireType::Len,
+        }
+    }
+
+    fn add<S: MsgScribe>(&self, s: &mut S, f: FieldNumber) {
+        use Scalar::*;
+        match *self {
+            Int32(v) => s.add_int32(f, v),
+            Int64(v) => s.add_int64(f, v),
+            UInt32(v) => s.add_uint32(f, v),
+            UInt64(v) => s.add_uint64(f, v),
+            SInt32(v) => s.add_sint32(f, v),
+            SInt64(v) => s.add_sint64(f, v),
+            Bool(v) => s.add_bool(f, v),
+            Enum(v) => s.add_enum(f, v),
+            Fixed32(v) => s.add_fixed32(f, v),
+            SFixed32(v) => s.add_sfixed32(f, v),
+            Float(v) => s.add_float(f, v),
+            Fixed64(v) => s.add_fixed64(f, v),
+            SFixed64(v) => s.add_sfixed64(f, v),
+            Double(v) => s.add_double(f, v),
+            String(v) => s.add_string(f, v),
+            Bytes(v) => s.add_bytes(f, v),
+        };
+    }
+
+    /// Adds the value to a packed field (`string` & `bytes` can not be packed
+    /// and are ignored).
+    fn add_packed<P: PackedScribe>(&self, p: &mut P) {
+        use Scalar::*;
+        match *self {
+            Int32(v) => p.add_int32(v),
+            Int64(v) => p.add_int64(v),
+            UInt32(v) => p.add_uint32(v),
+            UInt64(v) => p.add_uint64(v),
+            SInt32(v) => p.add_sint32(v),
+            SInt64(v) => p.add_sint64(v),
+            Bool(v) => p.add_bool(v),
+            Enum(v) => p.add_enum(v),
+            Fixed32(v) => p.add_fixed32(v),
+            SFixed32(v) => p.add_sfixed32(v),
+            Float(v) => p.add_float(v),
+            Fixed64(v) => p.add_fixed64(v),
+            SFixed64(v) => p.add_sfixed64(v),
+            Double(v) => p.add_double(v),
+            String(_) | Bytes(_) => p,
+        };
+    }
+
+    /// Decodes the given value as the same protobuf type as `self`.
+    fn decode(&self, value: &WireValueRef<'a>) -> Result<Scalar<'a>, WireValueIntoError> {
+        use Scalar::*;
+        Ok(match self {
+            Int32(_) => Int32(value.try_as_int32()?),
+            Int64(_) => Int64(value.try_as_int64()?),
+            UInt32(_) => UInt32(value.try_as_uint32()?),
+            UInt64(_) => UInt64(value.try_as_uint64()?),
+            SInt32(_) => SInt32(value.try_as_sint32()?),
+            SInt64(_) => SInt64(value.try_as_sint64()?),
+            Bool(_) => Bool(value.try_as_bool()?),
+            Enum(_) => Enum(value.try_as_enum()?),
+            Fixed32(_) => Fixed32(value.as_fixed32()?),
+            SFixed32(_) => SFixed32(value.as_sfixed32()?),
+            Float(_) => Float(value.as_float()?),
+            Fixed64(_) => Fixed64(value.as_fixed64()?),
+            SFixed64(_) => SFixed64(value.as_sfixed64()?),
+            Double(_) => Double(value.as_double()?),
+            String(_) => String(value.try_as_len()?.try_as_string().unwrap()),
+            Bytes(_) => Bytes(value.try_as_len()?.as_bytes()),
+        })
+    }
+}
+
+/// Scalar values & their expected encoding (without tag).
+const CASES: &[(Scalar<'static>, &[u8])] = {
+    use Scalar::*;
+    const NEG_ONE: &[u8] = &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+    &[
+        // int32 (negative values are sign extended to 10 bytes)
+        (Int32(0), &[0x00]),
+        (Int32(150), &[0x96, 0x01]),
+        (Int32(i32::MAX), &[0xff, 0xff, 0xff, 0xff, 0x07]),
+        (Int32(-1), NEG_ONE),
+        (
+            Int32(i32::MIN),
+            &[0x80, 0x80, 0x80, 0x80, 0xf8, 0xff, 0xff, 0xff, 0xff, 0x01],
+        ),
+        // int64
+        (Int64(0), &[0x00]),
+        (Int64(-1), NEG_ONE),
+        (
+            Int64(i64::MIN),
+            &[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x01],
+        ),
+        // uint32
+        (UInt32(0), &[0x00]),
+        (UInt32(u32::MAX), &[0xff, 0xff, 0xff, 0xff, 0x0f]),
+        // uint64
+        (UInt64(0), &[0x00]),
+        (UInt64(u64::MAX), NEG_ONE),
+        // sint32
+        (SInt32(0), &[0x00]),
+        (SInt32(-1), &[0x01]),
+        (SInt32(1), &[0x02]),
+        (SInt32(i32::MIN), &[0xff, 0xff, 0xff, 0xff, 0x0f]),
+        // sint64
+        (SInt64(-1), &[0x01]),
+        (
+            SInt64(i64::MAX),
+            &[0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01],
+        ),
+        (SInt64(i64::MIN), NEG_ONE),
+        // bool
+        (Bool(false), &[0x00]),
+        (Bool(true), &[0x01]),
+        // enum (same encoding as int32)
+        (Enum(0), &[0x00]),
+        (Enum(2), &[0x02]),
+        (Enum(-1), NEG_ONE),
+        (
+            Enum(i32::MIN),
+            &[0x80, 0x80, 0x80, 0x80, 0xf8, 0xff, 0xff, 0xff, 0xff, 0x01],
+        ),
+        // fixed32, sfixed32 & float
+        (Fixed32(0x1234_5678), &[0x78, 0x56, 0x34, 0x12]),
+        (SFixed32(-2), &[0xfe, 0xff, 0xff, 0xff]),
+        (Float(1.0), &[0x00, 0x00, 0x80, 0x3f]),
+        // fixed64, sfixed64 & double
+        (
+            Fixed64(0x0102_0304_0506_0708),
+            &[0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01],
+        ),
+        (
+            SFixed64(-2),
+            &[0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+        ),
+        (
+            Double(1.0),
+            &[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xf0, 0x3f],
+        ),
+        // string & bytes
+        (String(""), &[0x00]),
+        (String("abc"), &[0x03, b'a', b'b', b'c']),
+        (Bytes(&[0xff, 0x00]), &[0x02, 0xff, 0x00]),
+    ]
+};
+
+const OUTER: FieldNumber = FieldNumber(2);
+
+fn tag(wire_type: WireType) -> u8 {
+    (FieldNumber::ONE.0 << 3) as u8 | wire_type as u8
+}
+
+/// Serializes the value as field 1, both directly in the top level message
+/// and inside the sub-message field 2.
+fn ser_field<S: MsgScribe>(value: &Scalar, mut s: S) -> S::End {
+    value.add(&mut s, FieldNumber::ONE);
+    s.start_msg(OUTER);
+    value.add(&mut s, FieldNumber::ONE);
+    s.end_msg(OUTER);
+    s.end()
+}
+
+/// Serializes the value twice as packed field 1, both directly in the top
+/// level message and inside the sub-message field 2.
+fn ser_packed<S: MsgScribe>(value: &Scalar, mut s: S) -> S::End {
+    {
+        let mut p = s.start_packed(FieldNumber::ONE);
+        value.add_packed(&mut p);
+        value.add_packed(&mut p);
+    }
+    s.end_packed(FieldNumber::ONE);
+    s.start_msg(OUTER);
+    {
+        let mut p = s.start_packed(FieldNumber::ONE);
+        value.add_packed(&mut p);
+        value.add_packed(&mut p);
+    }
+    s.end_packed(FieldNumber::ONE);
+    s.end_msg(OUTER);
+    s.end()
+}
+
+#[test]
+fn scalar_fields() {
+    let mut builder = MsgBuilder::new();
+    for (value, encoded) in CASES {
+        let step2 = ser_field(value, builder.start(None));
+        let bytes = ser_field(value, step2);
+
+        // expected bytes (the sub-message length is only correct if the
+        // length & serialization passes agree)
+        let mut field = vec![tag(value.wire_type())];
+        field.extend_from_slice(encoded);
+        let mut expected = field.clone();
+        expected.push(tag(WireType::Len) + 8);
+        expected.push(field.len() as u8);
+        expected.extend_from_slice(&field);
+        assert_eq!(expected, bytes, "{:?}", value);
+
+        // decode
+        let mut decoder = MsgDecoder::new(bytes);
+        let record = decoder.next().unwrap().unwrap();
+        assert_eq!(FieldNumber::ONE, record.field_number);
+        assert_eq!(Ok(*value), value.decode(&record.value));
+        let record = decoder.next().unwrap().unwrap();
+        let mut sub_decoder = record.value.try_as_len().unwrap().as_sub_msg();
+        let record = sub_decoder.next().unwrap().unwrap();
+        assert_eq!(Ok(*value), value.decode(&record.value));
+        assert!(sub_decoder.next().is_none());
+        assert!(decoder.next().is_none());
+    }
+}
+
+#[test]
+fn packed_scalar_fields() {
+    let mut builder = MsgBuilder::new();
+    for (value, encoded) in CASES {
+        if value.wire_type() == WireType::Len {
+            continue;
+        }
+        let step2 = ser_packed(value, builder.start(None));
+        let bytes = ser_packed(value, step2);
+
+        let mut field = vec![tag(WireType::Len), (encoded.len() * 2) as u8];
+        field.extend_from_slice(encoded);
+        field.extend_from_slice(encoded);
+        let mut expected = field.clone();
+        expected.push(tag(WireType::Len) + 8);
+        expected.push(field.len() as u8);
+        expected.extend_from_slice(&field);
+        assert_eq!(expected, bytes, "{:?}", value);
+
+        // decode the packed values
+        let mut wire_decoder = WireDecoder::new(&bytes[2..field.len()]);
+        for _ in 0..2 {
+            let wire_value = match value.wire_type() {
+                WireType::VarInt => WireValueRef::VarInt(WireVarInt::from_raw(
+                    wire_decoder.read_var_uint64().unwrap(),
+                )),
+                WireType::I32 => WireValueRef::I32(WireI32(wire_decoder.read_fixed32().unwrap())),
+                _ => WireValueRef::I64(WireI64(wire_decoder.read_fixed64().unwrap())),
+            };
+            assert_eq!(Ok(*value), value.decode(&wire_value));
+        }
+        assert!(wire_decoder.data.is_empty());
+    }
+}
+
+#[test]
+fn decode_int32_truncated_negative() {
+    // negative int32 & enum values truncated to 32 bits (5 bytes) as written
+    // by some encoders are still accepted
+    let mut decoder = WireDecoder::new(&[0xff, 0xff, 0xff, 0xff, 0x0f]);
+    let value = WireValueRef::VarInt(WireVarInt::from_raw(decoder.read_var_uint64().unwrap()));
+    assert_eq!(Ok(-1), value.try_as_int32());
+    assert_eq!(Ok(-1), value.try_as_enum());
+
+    // values that are neither sign extended nor fit into 32 bits
+    let value = WireValueRef::VarInt(WireVarInt::from_raw(u64::from(u32::MAX) + 1));
+    assert_eq!(
+        Err(WireValueIntoError::VarIntValueTooBigFor32Bit(
+            u64::from(u32::MAX) + 1
+        )),
+        value.try_as_enum()
+    );
+}
+
+#[test]
+fn decode_unexpected_wire_type() {
+    use WireValueIntoError::UnexpectedType;
+    let var_int = WireValueRef::VarInt(WireVarInt::from_raw(0));
+    let i32_value = WireValueRef::I32(WireI32(0));
+    for (value, _) in CASES {
+        let (value_ref, actual) = if value.wire_type() == WireType::VarInt {
+            (&i32_value, WireType::I32)
+        } else {
+            (&var_int, WireType::VarInt)
+        };
+        assert_eq!(
+            Err(UnexpectedType {
+                expected: value.wire_type(),
+                actual,
+            }),
+            value.decode(value_ref),
+            "{:?}",
+            value
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{builders::LenAreaType, FieldNumber};
+
+/// Error returned by the fallible builders (see [`MsgBuilder::try_start`](super::MsgBuilder::try_start))
+/// if the builder methods were called in an inconsistent way.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum EncodeError {
+    /// `end_msg` or `end_packed` was called without a corresponding
+    /// `start_msg` or `start_packed` left to be closed.
+    EndWithoutStart {
+        field_number: FieldNumber,
+        t: LenAreaType,
+    },
+    /// `end_msg` or `end_packed` was called with a field number or type
+    /// different from the innermost open sub-message or packed field.
+    EndMismatch {
+        expected_field_number: FieldNumber,
+        expected_t: LenAreaType,
+        actual_field_number: FieldNumber,
+        actual_t: LenAreaType,
+    },
+    /// `end` was called before all sub-messages or packed fields were
+    /// ended (contains the innermost open one).
+    Unclosed {
+        field_number: FieldNumber,
+        t: LenAreaType,
+    },
+    /// A sub-message or packed field started in the serialization pass
+    /// does not match the one from the length pass (`expected` is `None`
+    /// if the length pass contained no further sub-messages or packed
+    /// fields).
+    LenPassFieldMismatch {
+        expected: Option<FieldNumber>,
+        actual: FieldNumber,
+    },
+    /// The serialization pass ended before all sub-messages or packed
+    /// fields of the length pass were serialized (contains the first
+    /// missing one).
+    LenPassFieldMissing { expected: FieldNumber },
+    /// The content of a sub-message or packed field serialized in the
+    /// serialization pass has a different byte length than determined in
+    /// the length pass.
+    LenPassLenMismatch {
+        field_number: FieldNumber,
+        expected: usize,
+        actual: usize,
+    },
+    /// A message, sub-message, packed field, string or bytes field is
+    /// longer than the maximum length (see [`MsgBuilder::set_max_len`](super::MsgBuilder::set_max_len)).
+    /// `field_number` is `None` if the message itself is too long.
+    LenTooBig {
+        field_number: Option<FieldNumber>,
+        len: u64,
+        max_len: u64,
+    },
+    /// The encoded message does not fit into the buffer of a
+    /// [`FixedMsgBuilder`](super::FixedMsgBuilder) (`len` is the needed
+    /// byte length).
+    BufCapacityExceeded { len: usize, capacity: usize },
+    /// The message contains more sub-messages & packed fields than a
+    /// [`FixedMsgBuilder`](super::FixedMsgBuilder) can store lengths for.
+    LenCapacityExceeded { capacity: usize },
+}
@@ -0,0 +1,341 @@
+use crate::{builders::*, FieldNumber};
+
+/// Fixed-capacity version of [`MsgBuilder`](super::MsgBuilder) that does not
+/// need an allocator (e.g. for microcontrollers).
+///
+/// `N` is the maximum byte length of the encoded message & `L` the maximum
+/// number of sub-messages & packed fields in a message. The encoding is done
+/// in the same two stages as with [`MsgBuilder`](super::MsgBuilder) & both
+/// stages implement [`MsgScribe`], so the same serialization functions can
+/// be used.
+///
+/// Instead of allocating when the capacity is exceeded the first error is
+/// recorded & returned by [`end`](MsgScribe::end)
+/// ([`EncodeError::BufCapacityExceeded`] or
+/// [`EncodeError::LenCapacityExceeded`]). Mismatched `start_*` & `end_*`
+/// calls & differences between the length & serialization pass are
+/// reported the same way as by the fallible builders (see
+/// [`MsgBuilder::try_start`](super::MsgBuilder::try_start)).
+///
+/// ```
+/// use protobin::builders::*;
+///
+/// // message { uint32 a = 1; message b = 2 { string c = 1; } }
+/// fn ser<S: MsgScribe>(mut s: S) -> S::End {
+///     let a = 1.try_into().unwrap();
+///     let b = 2.try_into().unwrap();
+///     let c = 1.try_into().unwrap();
+///     s.add_uint32(a, 3);
+///     s.start_msg(b);
+///     s.add_string(c, "x");
+///     s.end_msg(b);
+///     s.end()
+/// }
+///
+/// // up to 16 bytes & 4 sub-messages or packed fields
+/// let mut builder = FixedMsgBuilder::<16, 4>::new();
+/// let step2 = ser(builder.start()).unwrap();
+/// let bytes = ser(step2).unwrap();
+/// assert_eq!(bytes, &[0x08, 0x03, 0x12, 0x03, 0x0a, 0x01, b'x']);
+///
+/// // the message does not fit into 4 bytes
+/// let mut builder = FixedMsgBuilder::<4, 4>::new();
+/// assert_eq!(
+///     Err(EncodeError::BufCapacityExceeded { len: 7, capacity: 4 }),
+///     ser(builder.start()).map(|_| ())
+/// );
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct FixedMsgBuilder<const N: usize, const L: usize> {
+    /// Buffer containing the encoded bytes (starting at 0).
+    pub(crate) buf: [u8; N],
+    /// Number of bytes written to `buf`.
+    pub(crate) len: usize,
+    /// Lengths of the sub-messages & packed fields determined in the length
+    /// pass (in the order they were started).
+    pub(crate) lens: [(FieldNumber, usize); L],
+    /// Number of used entries in `lens`.
+    pub(crate) num_lens: usize,
+    /// Open sub-messages & packed fields.
+    pub(crate) stack: [FixedStackEntry; L],
+    /// Number of used entries in `stack`.
+    pub(crate) stack_len: usize,
+}
+
+impl<const N: usize, const L: usize> Default for FixedMsgBuilder<N, L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize, const L: usize> FixedMsgBuilder<N, L> {
+    /// Setup a new [`FixedMsgBuilder`] (can be used to initialize a
+    /// `static`).
+    pub const fn new() -> FixedMsgBuilder<N, L> {
+        FixedMsgBuilder {
+            buf: [0; N],
+            len: 0,
+            lens: [(FieldNumber(1), 0); L],
+            num_lens: 0,
+            stack: [FixedStackEntry {
+                outer: 0,
+                len_index: 0,
+                t: LenAreaType::Msg,
+                field_number: FieldNumber(1),
+            }; L],
+            stack_len: 0,
+        }
+    }
+
+    /// Start encoding a new message (returns length builder as step 1).
+    pub fn start<'a>(&'a mut self) -> FixedMsgLenBuilder<'a, N, L> {
+        // clear all previous data
+        self.len = 0;
+        self.num_lens = 0;
+        self.stack_len = 0;
+
+        FixedMsgLenBuilder {
+            buf: self,
+            cur_len: 0,
+            error: None,
+        }
+    }
+
+    /// Returns the innermost open sub-message or packed field.
+    #[inline]
+    pub(crate) fn stack_top(&self) -> Option<&FixedStackEntry> {
+        self.stack[..self.stack_len].last()
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub(crate) struct FixedStackEntry {
+    /// Length of the outer area when the area was started (length pass) or
+    /// position in the buffer after the length prefix (serialization pass).
+    pub(crate) outer: usize,
+    /// Index of the area in `lens`.
+    pub(crate) len_index: usize,
+    pub(crate) t: LenAreaType,
+    pub(crate) field_number: FieldNumber,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{builders::*, FieldNumber};
+
+    const MSG: FieldNumber = FieldNumber(1);
+    const STR: FieldNumber = FieldNumber(2);
+    const PACKED: FieldNumber = FieldNumber(3);
+    #[cfg(feature = "alloc")]
+    const MAP: FieldNumber = FieldNumber(4);
+    #[cfg(feature = "alloc")]
+    const SCALAR: FieldNumber = FieldNumber(5);
+
+    #[cfg(feature = "alloc")]
+    fn ser<S: MsgScribe>(depth: usize, str_len: usize, s: &mut S) {
+        for _ in 0..2 {
+            s.add_bytes(STR, &[b'x'; 200][..str_len]);
+        }
+        s.add_display_str(STR, &str_len).unwrap();
+        s.add_debug_str(STR, &"y").unwrap();
+        s.start_packed(PACKED)
+            .add_uint32(300)
+            .add_sint32(-1)
+            .add_int32(-2)
+            .add_bool(true)
+            .add_fixed64(7)
+            .add_double(1.5);
+        s.end_packed(PACKED);
+        s.add_map_entry(MAP, "k", -3, S::add_string, S::add_int32);
+        s.add_int64(SCALAR, -4)
+            .add_sint64(SCALAR, -5)
+            .add_enum(SCALAR, 6)
+            .add_fixed32(SCALAR, 7)
+            .add_sfixed64(SCALAR, -8)
+            .add_float(SCALAR, 9.5)
+            .add_encoded_msg(MSG, &[0x08, 0x01])
+            .add_raw_record(&[0x28, 0x01]);
+        if depth > 0 {
+            s.start_msg(MSG);
+            ser(depth - 1, str_len, s);
+            s.end_msg(MSG);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn two_pass(depth: usize, str_len: usize) -> Vec<u8> {
+        let mut builder = MsgBuilder::new();
+        let mut len = builder.start(None);
+        ser(depth, str_len, &mut len);
+        let mut s = len.end();
+        ser(depth, str_len, &mut s);
+        s.end().to_vec()
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn same_as_two_pass() {
+        // builder is reused
+        let mut builder = FixedMsgBuilder::<2048, 8>::new();
+        for str_len in [0, 1, 127, 128, 200] {
+            for depth in 0..3 {
+                let expected = two_pass(depth, str_len);
+                let mut len = builder.start();
+                ser(depth, str_len, &mut len);
+                let mut s = len.end().unwrap();
+                ser(depth, str_len, &mut s);
+                assert_eq!(Ok(&expected[..]), s.end());
+            }
+        }
+    }
+
+    #[test]
+    fn capacity() {
+        // exact fit
+        {
+            let mut builder = FixedMsgBuilder::<4, 1>::new();
+            let mut len = builder.start();
+            len.start_msg(MSG).add_uint32(STR, 1).end_msg(MSG);
+            let mut s = len.end().unwrap();
+            s.start_msg(MSG).add_uint32(STR, 1).end_msg(MSG);
+            assert_eq!(Ok(&[0x0a, 0x02, 0x10, 0x01][..]), s.end());
+        }
+        // buffer too small
+        {
+            let mut builder = FixedMsgBuilder::<3, 1>::new();
+            let mut len = builder.start();
+            len.start_msg(MSG).add_uint32(STR, 1).end_msg(MSG);
+            assert_eq!(
+                Err(EncodeError::BufCapacityExceeded {
+                    len: 4,
+                    capacity: 3
+                }),
+                len.end().map(|_| ())
+            );
+        }
+        // too many sub-messages & packed fields
+        {
+            let mut builder = FixedMsgBuilder::<16, 2>::new();
+            let mut len = builder.start();
+            len.start_msg(MSG).end_msg(MSG);
+            len.start_packed(PACKED).add_uint32(1);
+            len.end_packed(PACKED);
+            len.start_msg(MSG).end_msg(MSG);
+            assert_eq!(
+                Some(&EncodeError::LenCapacityExceeded { capacity: 2 }),
+                len.error()
+            );
+            assert_eq!(
+                Err(EncodeError::LenCapacityExceeded { capacity: 2 }),
+                len.end().map(|_| ())
+            );
+        }
+        // serialization pass writes more than the length pass
+        {
+            let mut builder = FixedMsgBuilder::<2, 0>::new();
+            let mut len = builder.start();
+            len.add_uint32(STR, 1);
+            let mut s = len.end().unwrap();
+            s.add_uint32(STR, 1).add_uint32(STR, 1);
+            assert_eq!(
+                Err(EncodeError::BufCapacityExceeded {
+                    len: 3,
+                    capacity: 2
+                }),
+                s.end()
+            );
+        }
+    }
+
+    #[test]
+    fn error() {
+        use LenAreaType::*;
+
+        let mut builder = FixedMsgBuilder::<16, 4>::new();
+
+        // end without start
+        {
+            let mut s = builder.start();
+            s.end_msg(MSG);
+            assert_eq!(
+                Err(EncodeError::EndWithoutStart {
+                    field_number: MSG,
+                    t: Msg
+                }),
+                s.end().map(|_| ())
+            );
+        }
+        // type mismatch
+        {
+            let mut s = builder.start();
+            s.start_msg(MSG).end_packed(MSG);
+            assert_eq!(
+                Err(EncodeError::EndMismatch {
+                    expected_field_number: MSG,
+                    expected_t: Msg,
+                    actual_field_number: MSG,
+                    actual_t: Packed,
+                }),
+                s.end().map(|_| ())
+            );
+        }
+        // unclosed
+        {
+            let mut s = builder.start();
+            s.start_msg(MSG).start_msg(STR).end_msg(STR);
+            assert_eq!(
+                Err(EncodeError::Unclosed {
+                    field_number: MSG,
+                    t: Msg
+                }),
+                s.end().map(|_| ())
+            );
+        }
+        // different sub-message in the serialization pass
+        {
+            let mut len = builder.start();
+            len.start_msg(MSG).end_msg(MSG);
+            let mut s = len.end().unwrap();
+            s.start_msg(STR).end_msg(STR);
+            assert_eq!(
+                Err(EncodeError::LenPassFieldMismatch {
+                    expected: Some(MSG),
+                    actual: STR
+                }),
+                s.end()
+            );
+        }
+        // missing sub-message in the serialization pass
+        {
+            let mut len = builder.start();
+            len.start_msg(MSG).end_msg(MSG);
+            let s = len.end().unwrap();
+            assert_eq!(
+                Err(EncodeError::LenPassFieldMissing { expected: MSG }),
+                s.end()
+            );
+        }
+        // different content length in the serialization pass
+        {
+            let mut len = builder.start();
+            len.start_msg(MSG).add_uint32(STR, 1).end_msg(MSG);
+            let mut s = len.end().unwrap();
+            s.start_msg(MSG).add_uint32(STR, 300).end_msg(MSG);
+            assert_eq!(
+                Err(EncodeError::LenPassLenMismatch {
+                    field_number: MSG,
+                    expected: 2,
+                    actual: 3
+                }),
+                s.end()
+            );
+        }
+
+        // builder can be reused after an error
+        let mut len = builder.start();
+        len.add_uint32(STR, 1);
+        let mut s = len.end().unwrap();
+        s.add_uint32(STR, 1);
+        assert_eq!(Ok(&[0x10, 0x01][..]), s.end());
+    }
+}
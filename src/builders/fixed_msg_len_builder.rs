@@ -0,0 +1,259 @@
+use crate::{builders::*, wire::WireVarInt, FieldNumber};
+
+/// Length pass of the [`FixedMsgBuilder`] (use [`FixedMsgBuilder::start`]
+/// to create).
+///
+/// The first error is recorded & returned by [`end`](MsgScribe::end).
+/// Calls after an error are still accepted, but their result is discarded.
+#[derive(Debug, Eq, PartialEq, Hash)]
+pub struct FixedMsgLenBuilder<'a, const N: usize, const L: usize> {
+    pub(crate) buf: &'a mut FixedMsgBuilder<N, L>,
+    /// Length of the innermost open area.
+    pub(crate) cur_len: usize,
+    /// First detected error.
+    pub(crate) error: Option<EncodeError>,
+}
+
+impl<'a, const N: usize, const L: usize> FixedMsgLenBuilder<'a, N, L> {
+    /// Returns the first detected error (if any).
+    pub fn error(&self) -> Option<&EncodeError> {
+        self.error.as_ref()
+    }
+
+    /// Records the error if it is the first one.
+    fn record(&mut self, err: EncodeError) {
+        if self.error.is_none() {
+            self.error = Some(err);
+        }
+    }
+
+    #[inline]
+    fn add_tag_and_len(&mut self, field_number: FieldNumber, value_len: i32) -> &mut Self {
+        self.cur_len += (WireVarInt::tag_byte_len(field_number) + value_len) as usize;
+        self
+    }
+
+    #[inline]
+    fn add_len_field(&mut self, field_number: FieldNumber, len: usize) -> &mut Self {
+        self.add_tag_and_len(field_number, WireVarInt::uint64_byte_len(len as u64));
+        self.cur_len += len;
+        self
+    }
+
+    fn start_len_area(&mut self, field_number: FieldNumber, t: LenAreaType) -> &mut Self {
+        if self.error.is_some() {
+            return self;
+        }
+        if self.buf.num_lens == L {
+            self.record(EncodeError::LenCapacityExceeded { capacity: L });
+            return self;
+        }
+        let len_index = self.buf.num_lens;
+        self.buf.lens[len_index] = (field_number, 0);
+        self.buf.num_lens += 1;
+        self.buf.stack[self.buf.stack_len] = FixedStackEntry {
+            outer: self.cur_len,
+            len_index,
+            t,
+            field_number,
+        };
+        self.buf.stack_len += 1;
+        self.cur_len = 0;
+        self
+    }
+
+    fn end_len_area(&mut self, field_number: FieldNumber, t: LenAreaType) -> &mut Self {
+        if self.error.is_some() {
+            return self;
+        }
+        let Some(ended) = self.buf.stack_top().copied() else {
+            self.record(EncodeError::EndWithoutStart { field_number, t });
+            return self;
+        };
+        if (ended.field_number, ended.t) != (field_number, t) {
+            self.record(EncodeError::EndMismatch {
+                expected_field_number: ended.field_number,
+                expected_t: ended.t,
+                actual_field_number: field_number,
+                actual_t: t,
+            });
+            return self;
+        }
+        self.buf.stack_len -= 1;
+        let len = self.cur_len;
+        self.buf.lens[ended.len_index].1 = len;
+        self.cur_len = ended.outer;
+        self.add_len_field(field_number, len)
+    }
+}
+
+impl<'a, const N: usize, const L: usize> MsgScribe for FixedMsgLenBuilder<'a, N, L> {
+    type Packed<'b>
+        = FixedMsgLenPackedScribe<'a, 'b, N, L>
+    where
+        Self: 'b;
+    type End = Result<FixedMsgSerBuilder<'a, N, L>, EncodeError>;
+
+    #[inline]
+    fn add_int32(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.add_tag_and_len(field_number, WireVarInt::int32_byte_len(value))
+    }
+
+    #[inline]
+    fn add_int64(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
+        self.add_tag_and_len(field_number, WireVarInt::int64_byte_len(value))
+    }
+
+    #[inline]
+    fn add_uint32(&mut self, field_number: FieldNumber, value: u32) -> &mut Self {
+        self.add_tag_and_len(field_number, WireVarInt::uint32_byte_len(value))
+    }
+
+    #[inline]
+    fn add_uint64(&mut self, field_number: FieldNumber, value: u64) -> &mut Self {
+        self.add_tag_and_len(field_number, WireVarInt::uint64_byte_len(value))
+    }
+
+    #[inline]
+    fn add_bool(&mut self, field_number: FieldNumber, _: bool) -> &mut Self {
+        self.add_tag_and_len(field_number, 1)
+    }
+
+    #[inline]
+    fn add_enum(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.add_tag_and_len(field_number, WireVarInt::int32_byte_len(value))
+    }
+
+    #[inline]
+    fn add_sint32(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.add_tag_and_len(field_number, WireVarInt::sint32_byte_len(value))
+    }
+
+    #[inline]
+    fn add_sint64(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
+        self.add_tag_and_len(field_number, WireVarInt::sint64_byte_len(value))
+    }
+
+    #[inline]
+    fn add_fixed32(&mut self, field_number: FieldNumber, _: u32) -> &mut Self {
+        self.add_tag_and_len(field_number, 4)
+    }
+
+    #[inline]
+    fn add_sfixed32(&mut self, field_number: FieldNumber, _: i32) -> &mut Self {
+        self.add_tag_and_len(field_number, 4)
+    }
+
+    #[inline]
+    fn add_float(&mut self, field_number: FieldNumber, _: f32) -> &mut Self {
+        self.add_tag_and_len(field_number, 4)
+    }
+
+    #[inline]
+    fn add_fixed64(&mut self, field_number: FieldNumber, _: u64) -> &mut Self {
+        self.add_tag_and_len(field_number, 8)
+    }
+
+    #[inline]
+    fn add_sfixed64(&mut self, field_number: FieldNumber, _: i64) -> &mut Self {
+        self.add_tag_and_len(field_number, 8)
+    }
+
+    #[inline]
+    fn add_double(&mut self, field_number: FieldNumber, _: f64) -> &mut Self {
+        self.add_tag_and_len(field_number, 8)
+    }
+
+    #[inline]
+    fn add_string(&mut self, field_number: FieldNumber, value: &str) -> &mut Self {
+        self.add_len_field(field_number, value.len())
+    }
+
+    #[inline]
+    fn add_bytes(&mut self, field_number: FieldNumber, value: &[u8]) -> &mut Self {
+        self.add_len_field(field_number, value.len())
+    }
+
+    fn add_display_str(
+        &mut self,
+        field_number: FieldNumber,
+        value: &dyn core::fmt::Display,
+    ) -> Result<&mut Self, core::fmt::Error> {
+        use core::fmt::Write;
+
+        let mut counter = FmtByteCounter(0);
+        write!(&mut counter, "{}", value)?;
+        Ok(self.add_len_field(field_number, counter.0))
+    }
+
+    fn add_debug_str(
+        &mut self,
+        field_number: FieldNumber,
+        value: &dyn core::fmt::Debug,
+    ) -> Result<&mut Self, core::fmt::Error> {
+        use core::fmt::Write;
+
+        let mut counter = FmtByteCounter(0);
+        write!(&mut counter, "{:?}", value)?;
+        Ok(self.add_len_field(field_number, counter.0))
+    }
+
+    #[inline]
+    fn add_encoded_msg(&mut self, field_number: FieldNumber, encoded_msg: &[u8]) -> &mut Self {
+        self.add_len_field(field_number, encoded_msg.len())
+    }
+
+    #[inline]
+    fn add_raw_record(&mut self, record: &[u8]) -> &mut Self {
+        self.cur_len += record.len();
+        self
+    }
+
+    #[inline]
+    fn start_msg(&mut self, field_number: FieldNumber) -> &mut Self {
+        self.start_len_area(field_number, LenAreaType::Msg)
+    }
+
+    #[inline]
+    fn end_msg(&mut self, field_number: FieldNumber) -> &mut Self {
+        self.end_len_area(field_number, LenAreaType::Msg)
+    }
+
+    #[inline]
+    fn start_packed<'b>(
+        &'b mut self,
+        field_number: FieldNumber,
+    ) -> FixedMsgLenPackedScribe<'a, 'b, N, L> {
+        FixedMsgLenPackedScribe {
+            parent: self.start_len_area(field_number, LenAreaType::Packed),
+        }
+    }
+
+    #[inline]
+    fn end_packed(&mut self, field_number: FieldNumber) -> &mut Self {
+        self.end_len_area(field_number, LenAreaType::Packed)
+    }
+
+    fn end(mut self) -> Self::End {
+        if let Some(open) = self.buf.stack_top().copied() {
+            self.record(EncodeError::Unclosed {
+                field_number: open.field_number,
+                t: open.t,
+            });
+        }
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+        if self.cur_len > N {
+            return Err(EncodeError::BufCapacityExceeded {
+                len: self.cur_len,
+                capacity: N,
+            });
+        }
+        Ok(FixedMsgSerBuilder {
+            buf: self.buf,
+            next_len_index: 0,
+            error: None,
+        })
+    }
+}
@@ -0,0 +1,332 @@
+use crate::{builders::*, wire::*, FieldNumber};
+
+/// Serialization pass of the [`FixedMsgBuilder`] (returned by the
+/// [`end`](MsgScribe::end) of [`FixedMsgLenBuilder`]).
+///
+/// The first error is recorded & returned by [`end`](MsgScribe::end).
+/// Additionally it is checked that the serialized content of every
+/// sub-message & packed field has the length determined in the length pass.
+/// Calls after an error are still accepted, but their result is discarded.
+#[derive(Debug, Eq, PartialEq, Hash)]
+pub struct FixedMsgSerBuilder<'a, const N: usize, const L: usize> {
+    pub(crate) buf: &'a mut FixedMsgBuilder<N, L>,
+    /// Index of the next length value in `lens`.
+    pub(crate) next_len_index: usize,
+    /// First detected error.
+    pub(crate) error: Option<EncodeError>,
+}
+
+impl<'a, const N: usize, const L: usize> FixedMsgSerBuilder<'a, N, L> {
+    /// Returns the first detected error (if any).
+    pub fn error(&self) -> Option<&EncodeError> {
+        self.error.as_ref()
+    }
+
+    /// Records the error if it is the first one.
+    fn record(&mut self, err: EncodeError) {
+        if self.error.is_none() {
+            self.error = Some(err);
+        }
+    }
+
+    /// Appends the bytes to the buffer (records an error if they do not
+    /// fit).
+    #[inline]
+    pub(crate) fn write(&mut self, data: &[u8]) {
+        let start = self.buf.len;
+        let end = start + data.len();
+        if end > N {
+            self.record(EncodeError::BufCapacityExceeded {
+                len: end,
+                capacity: N,
+            });
+            return;
+        }
+        self.buf.buf[start..end].copy_from_slice(data);
+        self.buf.len = end;
+    }
+
+    /// Appends the value as VARINT.
+    #[inline]
+    pub(crate) fn write_var_uint64(&mut self, value: u64) {
+        self.write(VarIntBytes::new(value).as_slice());
+    }
+
+    #[inline]
+    fn write_tag(&mut self, field_number: FieldNumber, wire_type: u32) {
+        self.write(VarIntBytes::tag(field_number, wire_type).as_slice());
+    }
+
+    /// Formats the value with the given function & writes it as `string`
+    /// field.
+    fn add_fmt_str(
+        &mut self,
+        field_number: FieldNumber,
+        f: impl Fn(&mut dyn core::fmt::Write) -> core::fmt::Result,
+    ) -> Result<&mut Self, core::fmt::Error> {
+        // count byte length of the formatted output
+        let mut counter = FmtByteCounter(0);
+        f(&mut counter)?;
+
+        self.write_tag(field_number, 2);
+        self.write_var_uint64(counter.0 as u64);
+        f(&mut FmtFixedWriter(self))?;
+        Ok(self)
+    }
+
+    fn start_len_area(&mut self, field_number: FieldNumber, t: LenAreaType) -> &mut Self {
+        if self.error.is_some() {
+            return self;
+        }
+        let len_index = self.next_len_index;
+        let expected = self.buf.lens[..self.buf.num_lens].get(len_index).copied();
+        let Some((_, len)) = expected.filter(|(f, _)| *f == field_number) else {
+            self.record(EncodeError::LenPassFieldMismatch {
+                expected: expected.map(|(f, _)| f),
+                actual: field_number,
+            });
+            return self;
+        };
+        self.next_len_index += 1;
+        self.write_tag(field_number, 2);
+        self.write_var_uint64(len as u64);
+        self.buf.stack[self.buf.stack_len] = FixedStackEntry {
+            outer: self.buf.len,
+            len_index,
+            t,
+            field_number,
+        };
+        self.buf.stack_len += 1;
+        self
+    }
+
+    fn end_len_area(&mut self, field_number: FieldNumber, t: LenAreaType) -> &mut Self {
+        if self.error.is_some() {
+            return self;
+        }
+        let Some(ended) = self.buf.stack_top().copied() else {
+            self.record(EncodeError::EndWithoutStart { field_number, t });
+            return self;
+        };
+        if (ended.field_number, ended.t) != (field_number, t) {
+            self.record(EncodeError::EndMismatch {
+                expected_field_number: ended.field_number,
+                expected_t: ended.t,
+                actual_field_number: field_number,
+                actual_t: t,
+            });
+            return self;
+        }
+        self.buf.stack_len -= 1;
+        let expected = self.buf.lens[ended.len_index].1;
+        let actual = self.buf.len - ended.outer;
+        if expected != actual {
+            self.record(EncodeError::LenPassLenMismatch {
+                field_number,
+                expected,
+                actual,
+            });
+        }
+        self
+    }
+}
+
+impl<'a, const N: usize, const L: usize> MsgScribe for FixedMsgSerBuilder<'a, N, L> {
+    type Packed<'b>
+        = FixedMsgSerPackedScribe<'a, 'b, N, L>
+    where
+        Self: 'b;
+    type End = Result<&'a [u8], EncodeError>;
+
+    #[inline]
+    fn add_int32(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.write_tag(field_number, 0);
+        self.write_var_uint64(WireVarInt::from_int32(value).raw());
+        self
+    }
+
+    #[inline]
+    fn add_int64(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
+        self.write_tag(field_number, 0);
+        self.write_var_uint64(WireVarInt::from_int64(value).raw());
+        self
+    }
+
+    #[inline]
+    fn add_uint32(&mut self, field_number: FieldNumber, value: u32) -> &mut Self {
+        self.write_tag(field_number, 0);
+        self.write_var_uint64(u64::from(value));
+        self
+    }
+
+    #[inline]
+    fn add_uint64(&mut self, field_number: FieldNumber, value: u64) -> &mut Self {
+        self.write_tag(field_number, 0);
+        self.write_var_uint64(value);
+        self
+    }
+
+    #[inline]
+    fn add_bool(&mut self, field_number: FieldNumber, value: bool) -> &mut Self {
+        self.write_tag(field_number, 0);
+        self.write(&[u8::from(value)]);
+        self
+    }
+
+    #[inline]
+    fn add_enum(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.write_tag(field_number, 0);
+        self.write_var_uint64(WireVarInt::from_int32(value).raw());
+        self
+    }
+
+    #[inline]
+    fn add_sint32(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.write_tag(field_number, 0);
+        self.write_var_uint64(WireVarInt::from_sint32(value).raw());
+        self
+    }
+
+    #[inline]
+    fn add_sint64(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
+        self.write_tag(field_number, 0);
+        self.write_var_uint64(WireVarInt::from_sint64(value).raw());
+        self
+    }
+
+    #[inline]
+    fn add_fixed32(&mut self, field_number: FieldNumber, value: u32) -> &mut Self {
+        self.write_tag(field_number, 5);
+        self.write(&value.to_le_bytes());
+        self
+    }
+
+    #[inline]
+    fn add_sfixed32(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.write_tag(field_number, 5);
+        self.write(&value.to_le_bytes());
+        self
+    }
+
+    #[inline]
+    fn add_float(&mut self, field_number: FieldNumber, value: f32) -> &mut Self {
+        self.write_tag(field_number, 5);
+        self.write(&value.to_le_bytes());
+        self
+    }
+
+    #[inline]
+    fn add_fixed64(&mut self, field_number: FieldNumber, value: u64) -> &mut Self {
+        self.write_tag(field_number, 1);
+        self.write(&value.to_le_bytes());
+        self
+    }
+
+    #[inline]
+    fn add_sfixed64(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
+        self.write_tag(field_number, 1);
+        self.write(&value.to_le_bytes());
+        self
+    }
+
+    #[inline]
+    fn add_double(&mut self, field_number: FieldNumber, value: f64) -> &mut Self {
+        self.write_tag(field_number, 1);
+        self.write(&value.to_le_bytes());
+        self
+    }
+
+    #[inline]
+    fn add_string(&mut self, field_number: FieldNumber, value: &str) -> &mut Self {
+        self.add_bytes(field_number, value.as_bytes())
+    }
+
+    #[inline]
+    fn add_bytes(&mut self, field_number: FieldNumber, value: &[u8]) -> &mut Self {
+        self.write_tag(field_number, 2);
+        self.write_var_uint64(value.len() as u64);
+        self.write(value);
+        self
+    }
+
+    fn add_display_str(
+        &mut self,
+        field_number: FieldNumber,
+        value: &dyn core::fmt::Display,
+    ) -> Result<&mut Self, core::fmt::Error> {
+        self.add_fmt_str(field_number, |w| write!(w, "{}", value))
+    }
+
+    fn add_debug_str(
+        &mut self,
+        field_number: FieldNumber,
+        value: &dyn core::fmt::Debug,
+    ) -> Result<&mut Self, core::fmt::Error> {
+        self.add_fmt_str(field_number, |w| write!(w, "{:?}", value))
+    }
+
+    #[inline]
+    fn add_encoded_msg(&mut self, field_number: FieldNumber, encoded_msg: &[u8]) -> &mut Self {
+        self.add_bytes(field_number, encoded_msg)
+    }
+
+    #[inline]
+    fn add_raw_record(&mut self, record: &[u8]) -> &mut Self {
+        self.write(record);
+        self
+    }
+
+    #[inline]
+    fn start_msg(&mut self, field_number: FieldNumber) -> &mut Self {
+        self.start_len_area(field_number, LenAreaType::Msg)
+    }
+
+    #[inline]
+    fn end_msg(&mut self, field_number: FieldNumber) -> &mut Self {
+        self.end_len_area(field_number, LenAreaType::Msg)
+    }
+
+    #[inline]
+    fn start_packed<'b>(
+        &'b mut self,
+        field_number: FieldNumber,
+    ) -> FixedMsgSerPackedScribe<'a, 'b, N, L> {
+        FixedMsgSerPackedScribe {
+            parent: self.start_len_area(field_number, LenAreaType::Packed),
+        }
+    }
+
+    #[inline]
+    fn end_packed(&mut self, field_number: FieldNumber) -> &mut Self {
+        self.end_len_area(field_number, LenAreaType::Packed)
+    }
+
+    fn end(mut self) -> Self::End {
+        if let Some(open) = self.buf.stack_top().copied() {
+            self.record(EncodeError::Unclosed {
+                field_number: open.field_number,
+                t: open.t,
+            });
+        }
+        if self.next_len_index < self.buf.num_lens {
+            let expected = self.buf.lens[self.next_len_index].0;
+            self.record(EncodeError::LenPassFieldMissing { expected });
+        }
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+        let buf = self.buf;
+        Ok(&buf.buf[..buf.len])
+    }
+}
+
+/// A [`core::fmt::Write`] implementation that writes UTF-8 bytes directly
+/// into the buffer of a [`FixedMsgSerBuilder`].
+struct FmtFixedWriter<'s, 'a, const N: usize, const L: usize>(&'s mut FixedMsgSerBuilder<'a, N, L>);
+
+impl<const N: usize, const L: usize> core::fmt::Write for FmtFixedWriter<'_, '_, N, L> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.0.write(s.as_bytes());
+        Ok(())
+    }
+}
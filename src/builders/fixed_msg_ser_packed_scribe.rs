@@ -0,0 +1,100 @@
+use crate::{builders::*, wire::WireVarInt};
+
+/// Serializes the values of a packed field (see [`FixedMsgSerBuilder`]).
+#[derive(Debug, Eq, PartialEq, Hash)]
+pub struct FixedMsgSerPackedScribe<'a, 'b, const N: usize, const L: usize> {
+    pub(crate) parent: &'b mut FixedMsgSerBuilder<'a, N, L>,
+}
+
+impl<'a, 'b, const N: usize, const L: usize> PackedScribe
+    for FixedMsgSerPackedScribe<'a, 'b, N, L>
+{
+    #[inline]
+    fn add_int32(&mut self, value: i32) -> &mut Self {
+        self.parent
+            .write_var_uint64(WireVarInt::from_int32(value).raw());
+        self
+    }
+
+    #[inline]
+    fn add_int64(&mut self, value: i64) -> &mut Self {
+        self.parent
+            .write_var_uint64(WireVarInt::from_int64(value).raw());
+        self
+    }
+
+    #[inline]
+    fn add_uint32(&mut self, value: u32) -> &mut Self {
+        self.parent.write_var_uint64(u64::from(value));
+        self
+    }
+
+    #[inline]
+    fn add_uint64(&mut self, value: u64) -> &mut Self {
+        self.parent.write_var_uint64(value);
+        self
+    }
+
+    #[inline]
+    fn add_bool(&mut self, value: bool) -> &mut Self {
+        self.parent.write(&[u8::from(value)]);
+        self
+    }
+
+    #[inline]
+    fn add_enum(&mut self, value: i32) -> &mut Self {
+        self.parent
+            .write_var_uint64(WireVarInt::from_int32(value).raw());
+        self
+    }
+
+    #[inline]
+    fn add_sint32(&mut self, value: i32) -> &mut Self {
+        self.parent
+            .write_var_uint64(WireVarInt::from_sint32(value).raw());
+        self
+    }
+
+    #[inline]
+    fn add_sint64(&mut self, value: i64) -> &mut Self {
+        self.parent
+            .write_var_uint64(WireVarInt::from_sint64(value).raw());
+        self
+    }
+
+    #[inline]
+    fn add_fixed32(&mut self, value: u32) -> &mut Self {
+        self.parent.write(&value.to_le_bytes());
+        self
+    }
+
+    #[inline]
+    fn add_sfixed32(&mut self, value: i32) -> &mut Self {
+        self.parent.write(&value.to_le_bytes());
+        self
+    }
+
+    #[inline]
+    fn add_float(&mut self, value: f32) -> &mut Self {
+        self.parent.write(&value.to_le_bytes());
+        self
+    }
+
+    #[inline]
+    fn add_fixed64(&mut self, value: u64) -> &mut Self {
+        self.parent.write(&value.to_le_bytes());
+        self
+    }
+
+    #[inline]
+    fn add_sfixed64(&mut self, value: i64) -> &mut Self {
+        self.parent.write(&value.to_le_bytes());
+        self
+    }
+
+    #[inline]
+    fn add_double(&mut self, value: f64) -> &mut Self {
+        self.parent.write(&value.to_le_bytes());
+        self
+    }
+}
@@ -0,0 +1,9 @@
+/// Type of a length delimited area (started by `start_msg` or
+/// `start_packed`).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum LenAreaType {
+    /// Sub-message.
+    Msg,
+    /// Packed repeated field.
+    Packed,
+}
@@ -1,20 +1,117 @@
+#[cfg(feature = "bytes")]
+mod bytes_msg_ser_builder;
+#[cfg(feature = "bytes")]
+pub use bytes_msg_ser_builder::*;
+
+#[cfg(feature = "bytes")]
+mod bytes_msg_ser_packed_scribe;
+#[cfg(feature = "bytes")]
+pub use bytes_msg_ser_packed_scribe::*;
+
+mod encode_error;
+pub use encode_error::*;
+
+mod fixed_msg_builder;
+pub use fixed_msg_builder::*;
+
+mod fixed_msg_len_builder;
+pub use fixed_msg_len_builder::*;
+
+mod fixed_msg_len_packed_scribe;
+pub use fixed_msg_len_packed_scribe::*;
+
+mod fixed_msg_ser_builder;
+pub use fixed_msg_ser_builder::*;
+
+mod fixed_msg_ser_packed_scribe;
+pub use fixed_msg_ser_packed_scribe::*;
+
+mod len_area_type;
+pub use len_area_type::*;
+
+#[cfg(feature = "alloc")]
 mod msg_builder;
+#[cfg(feature = "alloc")]
 pub use msg_builder::*;
 
+#[cfg(feature = "alloc")]
 mod msg_len_builder;
+#[cfg(feature = "alloc")]
 pub use msg_len_builder::*;
 
+#[cfg(feature = "alloc")]
 mod msg_len_packed_scribe;
+#[cfg(feature = "alloc")]
 pub use msg_len_packed_scribe::*;
 
 mod msg_scribe;
 pub use msg_scribe::*;
 
+#[cfg(feature = "alloc")]
 mod msg_ser_builder;
+#[cfg(feature = "alloc")]
 pub use msg_ser_builder::*;
 
+#[cfg(feature = "alloc")]
 mod msg_ser_packed_scribe;
+#[cfg(feature = "alloc")]
 pub use msg_ser_packed_scribe::*;
 
+mod oneof_error;
+pub use oneof_error::*;
+
+#[cfg(feature = "alloc")]
+mod oneof_scribe;
+#[cfg(feature = "alloc")]
+pub use oneof_scribe::*;
+
 mod packed_scribe;
 pub use packed_scribe::*;
+
+#[cfg(feature = "alloc")]
+mod reverse_builder;
+#[cfg(feature = "alloc")]
+pub use reverse_builder::*;
+
+#[cfg(feature = "alloc")]
+mod reverse_packed_scribe;
+#[cfg(feature = "alloc")]
+pub use reverse_packed_scribe::*;
+
+#[cfg(feature = "alloc")]
+mod reverse_scribe;
+#[cfg(feature = "alloc")]
+pub use reverse_scribe::*;
+
+#[cfg(feature = "alloc")]
+mod ser_out;
+#[cfg(feature = "alloc")]
+pub(crate) use ser_out::*;
+
+#[cfg(feature = "alloc")]
+mod single_pass_builder;
+#[cfg(feature = "alloc")]
+pub use single_pass_builder::*;
+
+#[cfg(feature = "alloc")]
+mod single_pass_packed_scribe;
+#[cfg(feature = "alloc")]
+pub use single_pass_packed_scribe::*;
+
+#[cfg(feature = "alloc")]
+mod single_pass_scribe;
+#[cfg(feature = "alloc")]
+pub use single_pass_scribe::*;
+
+#[cfg(feature = "alloc")]
+mod try_msg_len_builder;
+#[cfg(feature = "alloc")]
+pub use try_msg_len_builder::*;
+
+#[cfg(feature = "alloc")]
+mod try_msg_ser_builder;
+#[cfg(feature = "alloc")]
+pub use try_msg_ser_builder::*;
+
+#[cfg(all(test, feature = "alloc"))]
+mod conformance_tests;
@@ -1,4 +1,5 @@
 use crate::{builders::*, wire::*, FieldNumber};
+use alloc::vec::Vec;
 
 /// Buffers to serialize a message (should be reused as much as possible
 /// to avoid allocations).
@@ -24,25 +25,87 @@ use crate::{builders::*, wire::*, FieldNumber};
 ///
 /// To avoid this all the length values are determined first and safed so they
 /// can be used in the second serialisation step.
-#[derive(Default, Debug, Clone, Eq, PartialEq, Hash)]
+///
+/// # Maximum length
+///
+/// By default messages, sub-messages, packed fields, strings & bytes are
+/// limited to [`MsgBuilder::MAX_LEN_PROTOBUF`] bytes (2 GiB - 1, the limit of
+/// the protobuf reference implementations). Longer data is reported as
+/// [`EncodeError::LenTooBig`] by the length pass (see
+/// [`MsgBuilder::set_max_len`] to raise the limit for internal formats).
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct MsgBuilder {
-    pub(crate) lens: Vec<(FieldNumber, i32)>,
+    pub(crate) lens: Vec<(FieldNumber, u64)>,
     pub(crate) len_stack: Vec<LenStackEntry>,
+    /// Open length areas during the serialization phase (only used by
+    /// the fallible `try_*` methods).
+    pub(crate) ser_stack: Vec<SerStackEntry>,
     pub(crate) encoder: WireEncoder,
+    /// Maximum allowed byte length.
+    pub(crate) max_len: u64,
+    /// First length error detected in the length pass.
+    pub(crate) len_error: Option<EncodeError>,
+}
+
+impl Default for MsgBuilder {
+    fn default() -> Self {
+        MsgBuilder {
+            lens: Vec::new(),
+            len_stack: Vec::new(),
+            ser_stack: Vec::new(),
+            encoder: WireEncoder::default(),
+            max_len: MsgBuilder::MAX_LEN_PROTOBUF,
+            len_error: None,
+        }
+    }
 }
 
 impl MsgBuilder {
+    /// Default maximum byte length (2 GiB - 1) of messages, sub-messages,
+    /// packed fields, strings & bytes (limit of the protobuf reference
+    /// implementations).
+    pub const MAX_LEN_PROTOBUF: u64 = i32::MAX as u64;
+
+    /// Maximum byte length when using 64 bit length accounting.
+    ///
+    /// Note that messages exceeding [`MsgBuilder::MAX_LEN_PROTOBUF`] can not
+    /// be decoded by most other protobuf implementations.
+    pub const MAX_LEN_64BIT: u64 = u64::MAX;
+
     /// Setup a new [`MsgBuilder`].
     pub fn new() -> MsgBuilder {
         Default::default()
     }
 
+    /// Returns the maximum allowed byte length of messages, sub-messages,
+    /// packed fields, strings & bytes.
+    pub fn max_len(&self) -> u64 {
+        self.max_len
+    }
+
+    /// Sets the maximum allowed byte length of messages, sub-messages,
+    /// packed fields, strings & bytes (e.g. [`MsgBuilder::MAX_LEN_64BIT`]
+    /// to opt into 64 bit length accounting).
+    ///
+    /// ```
+    /// use protobin::builders::*;
+    ///
+    /// let mut builder = MsgBuilder::new();
+    /// assert_eq!(MsgBuilder::MAX_LEN_PROTOBUF, builder.max_len());
+    /// builder.set_max_len(MsgBuilder::MAX_LEN_64BIT);
+    /// ```
+    pub fn set_max_len(&mut self, max_len: u64) {
+        self.max_len = max_len;
+    }
+
     /// Start encoding a new message encoding (returns length builder as step 1).
     pub fn start<'a>(&'a mut self, num_len_values: Option<usize>) -> MsgLenBuilder<'a> {
         // clear all previous data
         self.lens.clear();
         self.len_stack.clear();
+        self.ser_stack.clear();
         self.encoder.buf.clear();
+        self.len_error = None;
 
         // reserve enough memory in case we get a upper limit of len values
         if let Some(num_len_values) = num_len_values {
@@ -56,18 +119,62 @@ impl MsgBuilder {
             cur_len: 0,
         }
     }
+
+    /// Start encoding a new message with the fallible builders (returns
+    /// length builder as step 1).
+    ///
+    /// Instead of panicking on mismatched `start_*` & `end_*` calls or
+    /// differences between the length & serialization pass, the fallible
+    /// builders record the first error & return it from
+    /// [`MsgScribe::end`].
+    ///
+    /// ```
+    /// use protobin::builders::*;
+    ///
+    /// fn ser<S: MsgScribe>(mut s: S) -> S::End {
+    ///     let f = 1.try_into().unwrap();
+    ///     s.start_msg(f);
+    ///     s.add_uint32(2.try_into().unwrap(), 3);
+    ///     // end_msg call missing
+    ///     s.end()
+    /// }
+    ///
+    /// let mut builder = MsgBuilder::new();
+    /// assert!(matches!(
+    ///     ser(builder.try_start(None)),
+    ///     Err(EncodeError::Unclosed { .. })
+    /// ));
+    /// ```
+    pub fn try_start<'a>(&'a mut self, num_len_values: Option<usize>) -> TryMsgLenBuilder<'a> {
+        TryMsgLenBuilder::new(self.start(num_len_values))
+    }
+
+    /// Records a [`EncodeError::LenTooBig`] (if it is the first length error).
+    pub(crate) fn set_len_error(&mut self, field_number: Option<FieldNumber>, len: u64) {
+        if self.len_error.is_none() {
+            self.len_error = Some(EncodeError::LenTooBig {
+                field_number,
+                len,
+                max_len: self.max_len,
+            });
+        }
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub(crate) struct LenStackEntry {
-    pub(crate) len: i32,
-    pub(crate) t: LenStackType,
+    pub(crate) len: u64,
+    pub(crate) t: LenAreaType,
     pub(crate) len_index: usize,
     pub(crate) field_number: FieldNumber,
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-pub(crate) enum LenStackType {
-    Msg,
-    Packed,
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub(crate) struct SerStackEntry {
+    /// Position in the buffer after the length prefix.
+    pub(crate) start: usize,
+    /// Length determined in the length pass.
+    pub(crate) len: u64,
+    pub(crate) t: LenAreaType,
+    pub(crate) field_number: FieldNumber,
 }
@@ -5,175 +5,213 @@ use crate::{builders::*, wire::WireVarInt, *};
 #[derive(Debug, Eq, PartialEq, Hash)]
 pub struct MsgLenBuilder<'a> {
     pub(crate) buf: &'a mut MsgBuilder,
-    pub(crate) cur_len: i32,
+    pub(crate) cur_len: u64,
 }
 
 impl<'a> MsgLenBuilder<'a> {
     /// Adds the byte length of a protobuf `int32` field (VARINT encoded) to the
     /// current length.
     pub fn add_int32_field(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
-        self.cur_len += WireVarInt::tag_byte_len(field_number) + WireVarInt::int32_byte_len(value);
+        self.cur_len +=
+            (WireVarInt::tag_byte_len(field_number) + WireVarInt::int32_byte_len(value)) as u64;
         self
     }
 
     /// Adds the byte length of a protobuf `int64` field (VARINT encoded) to the
     /// current length.
     pub fn add_int64_field(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
-        self.cur_len += WireVarInt::tag_byte_len(field_number) + WireVarInt::int64_byte_len(value);
+        self.cur_len +=
+            (WireVarInt::tag_byte_len(field_number) + WireVarInt::int64_byte_len(value)) as u64;
         self
     }
 
     /// Adds the byte length of a protobuf `uint32` field (VARINT encoded) to the
     /// current length.
     pub fn add_uint32_field(&mut self, field_number: FieldNumber, value: u32) -> &mut Self {
-        self.cur_len += WireVarInt::tag_byte_len(field_number) + WireVarInt::uint32_byte_len(value);
+        self.cur_len +=
+            (WireVarInt::tag_byte_len(field_number) + WireVarInt::uint32_byte_len(value)) as u64;
         self
     }
 
     /// Adds the byte length of a protobuf `uint64` field (VARINT encoded) to the
     /// current length.
     pub fn add_uint64_field(&mut self, field_number: FieldNumber, value: u64) -> &mut Self {
-        self.cur_len += WireVarInt::tag_byte_len(field_number) + WireVarInt::uint64_byte_len(value);
+        self.cur_len +=
+            (WireVarInt::tag_byte_len(field_number) + WireVarInt::uint64_byte_len(value)) as u64;
         self
     }
 
     /// Adds the byte length of a protobuf `bool` field (VARINT encoded, always
     /// 1 byte) to the current length.
     pub fn add_bool_field(&mut self, field_number: FieldNumber) -> &mut Self {
-        self.cur_len += WireVarInt::tag_byte_len(field_number) + 1;
+        self.cur_len += WireVarInt::tag_byte_len(field_number) as u64 + 1;
         self
     }
 
     /// Adds the byte length of a protobuf `enum` field (VARINT encoded) to the
     /// current length.
     pub fn add_enum_field(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
-        self.cur_len += WireVarInt::tag_byte_len(field_number) + WireVarInt::int32_byte_len(value);
+        self.cur_len +=
+            (WireVarInt::tag_byte_len(field_number) + WireVarInt::int32_byte_len(value)) as u64;
         self
     }
 
     /// Adds the byte length of a protobuf `sint32` field (VARINT with ZigZag
     /// encoding) to the current length.
     pub fn add_sint32_field(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
-        self.cur_len += WireVarInt::tag_byte_len(field_number) + WireVarInt::sint32_byte_len(value);
+        self.cur_len +=
+            (WireVarInt::tag_byte_len(field_number) + WireVarInt::sint32_byte_len(value)) as u64;
         self
     }
 
     /// Adds the byte length of a protobuf `sint64` field (VARINT with ZigZag
     /// encoding) to the current length.
     pub fn add_sint64_field(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
-        self.cur_len += WireVarInt::tag_byte_len(field_number) + WireVarInt::sint64_byte_len(value);
+        self.cur_len +=
+            (WireVarInt::tag_byte_len(field_number) + WireVarInt::sint64_byte_len(value)) as u64;
         self
     }
 
     /// Adds the byte length of a protobuf `fixed32` field (4 bytes, little-endian)
     /// to the current length.
     pub fn add_fixed32_field(&mut self, field_number: FieldNumber) -> &mut Self {
-        self.cur_len += WireVarInt::tag_byte_len(field_number) + 4;
+        self.cur_len += WireVarInt::tag_byte_len(field_number) as u64 + 4;
         self
     }
 
     /// Adds the byte length of a protobuf `sfixed32` field (4 bytes, little-endian)
     /// to the current length.
     pub fn add_sfixed32_field(&mut self, field_number: FieldNumber) -> &mut Self {
-        self.cur_len += WireVarInt::tag_byte_len(field_number) + 4;
+        self.cur_len += WireVarInt::tag_byte_len(field_number) as u64 + 4;
         self
     }
 
     /// Adds the byte length of a protobuf `float` field (4 bytes, little-endian)
     /// to the current length.
     pub fn add_float_field(&mut self, field_number: FieldNumber) -> &mut Self {
-        self.cur_len += WireVarInt::tag_byte_len(field_number) + 4;
+        self.cur_len += WireVarInt::tag_byte_len(field_number) as u64 + 4;
         self
     }
 
     /// Adds the byte length of a protobuf `fixed64` field (8 bytes, little-endian)
     /// to the current length.
     pub fn add_fixed64_field(&mut self, field_number: FieldNumber) -> &mut Self {
-        self.cur_len += WireVarInt::tag_byte_len(field_number) + 8;
+        self.cur_len += WireVarInt::tag_byte_len(field_number) as u64 + 8;
         self
     }
 
     /// Adds the byte length of a protobuf `sfixed64` field (8 bytes, little-endian)
     /// to the current length.
     pub fn add_sfixed64_field(&mut self, field_number: FieldNumber) -> &mut Self {
-        self.cur_len += WireVarInt::tag_byte_len(field_number) + 8;
+        self.cur_len += WireVarInt::tag_byte_len(field_number) as u64 + 8;
         self
     }
 
     /// Adds the byte length of a protobuf `double` field (8 bytes, little-endian)
     /// to the current length.
     pub fn add_double_field(&mut self, field_number: FieldNumber) -> &mut Self {
-        self.cur_len += WireVarInt::tag_byte_len(field_number) + 8;
+        self.cur_len += WireVarInt::tag_byte_len(field_number) as u64 + 8;
         self
     }
 
     /// Adds the byte length of a protobuf `string` field (LEN encoded: tag +
     /// varint length + UTF-8 bytes) to the current length.
     pub fn add_string_field(&mut self, field_number: FieldNumber, value: &str) -> &mut Self {
-        // TODO add length error
-        self.cur_len += WireVarInt::tag_byte_len(field_number)
-            + WireVarInt::int32_byte_len(value.len() as i32)
-            + (value.len() as i32);
+        self.add_len_field(field_number, value.len());
         self
     }
 
     /// Adds the byte length of a protobuf `bytes` field (LEN encoded: tag +
     /// varint length + raw bytes) to the current length.
     pub fn add_bytes_field(&mut self, field_number: FieldNumber, value: &[u8]) -> &mut Self {
-        // TODO add length error
-        self.cur_len += WireVarInt::tag_byte_len(field_number)
-            + WireVarInt::int32_byte_len(value.len() as i32)
-            + (value.len() as i32);
+        self.add_len_field(field_number, value.len());
         self
     }
 
     /// Adds the byte length of a protobuf `string` field whose content is
-    /// produced by the [`std::fmt::Display`] implementation of `value`.
+    /// produced by the [`core::fmt::Display`] implementation of `value`.
     ///
-    /// The length is determined by a byte-counting [`std::fmt::Write`] adapter
+    /// The length is determined by a byte-counting [`core::fmt::Write`] adapter
     /// that never allocates, using the same calculation as
     /// [`add_string_field`](Self::add_string_field) afterwards.
     pub fn add_display_str_field(
         &mut self,
         field_number: FieldNumber,
-        value: &dyn std::fmt::Display,
-    ) -> Result<&mut Self, std::fmt::Error> {
-        use std::fmt::Write;
+        value: &dyn core::fmt::Display,
+    ) -> Result<&mut Self, core::fmt::Error> {
+        use core::fmt::Write;
         let mut counter = FmtByteCounter(0);
         write!(&mut counter, "{}", value)?;
         let len = counter.0;
         // same calculation as for str
-        self.cur_len += WireVarInt::tag_byte_len(field_number)
-            + WireVarInt::int32_byte_len(len as i32)
-            + (len as i32);
+        self.add_len_field(field_number, len);
         Ok(self)
     }
 
     /// Adds the byte length of a protobuf `string` field whose content is
-    /// produced by the [`std::fmt::Debug`] implementation of `value`.
+    /// produced by the [`core::fmt::Debug`] implementation of `value`.
     ///
-    /// The length is determined by a byte-counting [`std::fmt::Write`] adapter
+    /// The length is determined by a byte-counting [`core::fmt::Write`] adapter
     /// that never allocates, using the same calculation as
     /// [`add_string_field`](Self::add_string_field) afterwards.
     pub fn add_debug_str_field(
         &mut self,
         field_number: FieldNumber,
-        value: &dyn std::fmt::Debug,
-    ) -> Result<&mut Self, std::fmt::Error> {
-        use std::fmt::Write;
+        value: &dyn core::fmt::Debug,
+    ) -> Result<&mut Self, core::fmt::Error> {
+        use core::fmt::Write;
         let mut counter = FmtByteCounter(0);
         write!(&mut counter, "{:?}", value)?;
         let len = counter.0;
         // same calculation as for str
-        self.cur_len += WireVarInt::tag_byte_len(field_number)
-            + WireVarInt::int32_byte_len(len as i32)
-            + (len as i32);
+        self.add_len_field(field_number, len);
         Ok(self)
     }
 
-    fn start_len_area(&mut self, field_number: FieldNumber, t: LenStackType) -> &mut Self {
+    /// Adds the byte length of an already serialized sub-message field (LEN
+    /// encoded: tag + varint length + encoded message) to the current length.
+    ///
+    /// In contrast to [`start_msg_field`](Self::start_msg_field) no entry
+    /// is added to the length values as the length is already known.
+    pub fn add_encoded_msg_field(
+        &mut self,
+        field_number: FieldNumber,
+        encoded_msg: &[u8],
+    ) -> &mut Self {
+        // same calculation as for bytes
+        self.add_bytes_field(field_number, encoded_msg)
+    }
+
+    /// Adds the byte length of already encoded data (zero or more complete
+    /// records) to the current length.
+    pub fn add_raw(&mut self, data: &[u8]) -> &mut Self {
+        self.add_raw_record(data)
+    }
+
+    /// Adds the byte length of an already encoded record (tag + value) to
+    /// the current length.
+    pub fn add_raw_record(&mut self, record: &[u8]) -> &mut Self {
+        self.cur_len += record.len() as u64;
+        self
+    }
+
+    /// Adds the byte length of a LEN field (tag + varint length + content)
+    /// with the given content length & records an error if the content is
+    /// longer than the maximum length.
+    #[inline]
+    fn add_len_field(&mut self, field_number: FieldNumber, len: usize) {
+        let len = len as u64;
+        if len > self.buf.max_len {
+            self.buf.set_len_error(Some(field_number), len);
+        }
+        self.cur_len += (WireVarInt::tag_byte_len(field_number) + WireVarInt::uint64_byte_len(len))
+            as u64
+            + len;
+    }
+
+    fn start_len_area(&mut self, field_number: FieldNumber, t: LenAreaType) -> &mut Self {
         // add tag (length skipped until after message is done)
-        self.cur_len += WireVarInt::tag_byte_len(field_number);
+        self.cur_len += WireVarInt::tag_byte_len(field_number) as u64;
 
         // save length in stack
         if let Some(e) = self.buf.len_stack.last_mut() {
@@ -193,33 +231,57 @@ impl<'a> MsgLenBuilder<'a> {
         self
     }
 
-    fn end_len_area(&mut self, field_number: FieldNumber, t: LenStackType) -> &mut Self {
-        // finalize length
-        let Some(ended) = self.buf.len_stack.pop() else {
-            panic!("'end_msg_field' or 'end_packed_field' called but no corresponding 'start_msg_field' or 'start_packed_field' left to be closed.");
+    fn end_len_area(&mut self, field_number: FieldNumber, t: LenAreaType) -> &mut Self {
+        match self.try_end_len_area(field_number, t) {
+            Ok(s) => s,
+            Err(EncodeError::EndMismatch {
+                expected_field_number,
+                expected_t,
+                ..
+            }) => panic!("Unexpected end call, expected {expected_field_number:?} {expected_t:?} but got {field_number:?} {t:?}"),
+            Err(_) => panic!("'end_msg_field' or 'end_packed_field' called but no corresponding 'start_msg_field' or 'start_packed_field' left to be closed."),
+        }
+    }
+
+    fn try_end_len_area(
+        &mut self,
+        field_number: FieldNumber,
+        t: LenAreaType,
+    ) -> Result<&mut Self, EncodeError> {
+        // check that the end call matches the innermost open area
+        let Some(ended) = self.buf.len_stack.last() else {
+            return Err(EncodeError::EndWithoutStart { field_number, t });
         };
-        assert_eq!(
-            (ended.field_number, ended.t),
-            (field_number, t),
-            "Unexpected end call, expected {:?} {:?} but got {field_number:?} {t:?}",
-            ended.field_number,
-            ended.t
-        );
-        self.buf.lens[ended.len_index].1 = self.cur_len;
+        if (ended.field_number, ended.t) != (field_number, t) {
+            return Err(EncodeError::EndMismatch {
+                expected_field_number: ended.field_number,
+                expected_t: ended.t,
+                actual_field_number: field_number,
+                actual_t: t,
+            });
+        }
+
+        // finalize length
+        let len_index = ended.len_index;
+        self.buf.len_stack.pop();
+        self.buf.lens[len_index].1 = self.cur_len;
+        if self.cur_len > self.buf.max_len {
+            self.buf.set_len_error(Some(field_number), self.cur_len);
+        }
 
         // restore cur_len
         if let Some(next) = self.buf.len_stack.last() {
-            self.cur_len += next.len + WireVarInt::int32_byte_len(self.cur_len);
+            self.cur_len += next.len + WireVarInt::uint64_byte_len(self.cur_len) as u64;
         } // else just keep the cur_len (makes no difference, no stack left)
 
-        self
+        Ok(self)
     }
 
     /// Begins length tracking for a nested sub-message field. Must be paired
     /// with a matching [`end_msg_field`](Self::end_msg_field) call using the
     /// same `field_number`.
     pub fn start_msg_field(&mut self, field_number: FieldNumber) -> &mut Self {
-        self.start_len_area(field_number, LenStackType::Msg)
+        self.start_len_area(field_number, LenAreaType::Msg)
     }
 
     /// Ends length tracking for a nested sub-message field previously started
@@ -227,7 +289,7 @@ impl<'a> MsgLenBuilder<'a> {
     /// length of the sub-message is stored so that the serialization phase can
     /// emit the correct varint length prefix.
     pub fn end_msg_field(&mut self, field_number: FieldNumber) -> &mut Self {
-        self.end_len_area(field_number, LenStackType::Msg)
+        self.end_len_area(field_number, LenAreaType::Msg)
     }
 
     /// Begins length tracking for a packed repeated field and returns a
@@ -239,7 +301,7 @@ impl<'a> MsgLenBuilder<'a> {
         field_number: FieldNumber,
     ) -> MsgLenPackedScribe<'a, 'b> {
         MsgLenPackedScribe {
-            parent: self.start_len_area(field_number, LenStackType::Packed),
+            parent: self.start_len_area(field_number, LenAreaType::Packed),
         }
     }
 
@@ -248,7 +310,67 @@ impl<'a> MsgLenBuilder<'a> {
     /// byte length is stored so that the serialization phase can emit the
     /// correct varint length prefix.
     pub fn end_packed_field(&mut self, field_number: FieldNumber) -> &mut Self {
-        self.end_len_area(field_number, LenStackType::Packed)
+        self.end_len_area(field_number, LenAreaType::Packed)
+    }
+
+    /// Fallible version of [`end_msg_field`](Self::end_msg_field) that
+    /// returns an error instead of panicking if the call does not match
+    /// the innermost open sub-message or packed field.
+    pub fn try_end_msg_field(
+        &mut self,
+        field_number: FieldNumber,
+    ) -> Result<&mut Self, EncodeError> {
+        self.try_end_len_area(field_number, LenAreaType::Msg)
+    }
+
+    /// Fallible version of [`end_packed_field`](Self::end_packed_field) that
+    /// returns an error instead of panicking if the call does not match
+    /// the innermost open sub-message or packed field.
+    pub fn try_end_packed_field(
+        &mut self,
+        field_number: FieldNumber,
+    ) -> Result<&mut Self, EncodeError> {
+        self.try_end_len_area(field_number, LenAreaType::Packed)
+    }
+
+    /// Fallible version of [`MsgScribe::end`] that returns an error instead
+    /// of panicking if not all sub-messages or packed fields were ended or
+    /// if the message, a sub-message, a packed field, a string or a bytes
+    /// field is longer than the maximum length (see [`MsgBuilder::set_max_len`]).
+    pub fn try_end(self) -> Result<MsgSerBuilder<'a>, EncodeError> {
+        if let Some(open) = self.buf.len_stack.last() {
+            return Err(EncodeError::Unclosed {
+                field_number: open.field_number,
+                t: open.t,
+            });
+        }
+        if self.cur_len > self.buf.max_len {
+            self.buf.set_len_error(None, self.cur_len);
+        }
+        if let Some(err) = self.buf.len_error.take() {
+            return Err(err);
+        }
+        Ok(MsgSerBuilder::new(self.buf))
+    }
+
+    /// Ends the length pass & returns a serialization pass that appends the
+    /// message to `out` (instead of the internal buffer of the
+    /// [`MsgBuilder`]). `out` reserves enough capacity for the whole
+    /// message.
+    ///
+    /// Panics in the same cases as [`MsgScribe::end`].
+    #[cfg(feature = "bytes")]
+    pub fn end_bytes_mut<'b>(self, out: &'b mut bytes::BytesMut) -> BytesMsgSerBuilder<'a, 'b> {
+        let len = self.cur_len as usize;
+        let ser = MsgScribe::end(self);
+        out.reserve(len);
+        MsgSerBuilder {
+            lens: ser.lens,
+            ser_stack: ser.ser_stack,
+            start: out.len(),
+            out,
+            next_len_index: 0,
+        }
     }
 }
 
@@ -343,8 +465,8 @@ impl<'a> MsgScribe for MsgLenBuilder<'a> {
     fn add_display_str(
         &mut self,
         field_number: FieldNumber,
-        value: &dyn std::fmt::Display,
-    ) -> Result<&mut Self, std::fmt::Error> {
+        value: &dyn core::fmt::Display,
+    ) -> Result<&mut Self, core::fmt::Error> {
         self.add_display_str_field(field_number, value)
     }
 
@@ -352,11 +474,21 @@ impl<'a> MsgScribe for MsgLenBuilder<'a> {
     fn add_debug_str(
         &mut self,
         field_number: FieldNumber,
-        value: &dyn std::fmt::Debug,
-    ) -> Result<&mut Self, std::fmt::Error> {
+        value: &dyn core::fmt::Debug,
+    ) -> Result<&mut Self, core::fmt::Error> {
         self.add_debug_str_field(field_number, value)
     }
 
+    #[inline]
+    fn add_encoded_msg(&mut self, field_number: FieldNumber, encoded_msg: &[u8]) -> &mut Self {
+        self.add_encoded_msg_field(field_number, encoded_msg)
+    }
+
+    #[inline]
+    fn add_raw_record(&mut self, record: &[u8]) -> &mut Self {
+        MsgLenBuilder::add_raw_record(self, record)
+    }
+
     #[inline]
     fn start_msg(&mut self, field_number: FieldNumber) -> &mut Self {
         self.start_msg_field(field_number)
@@ -379,35 +511,23 @@ impl<'a> MsgScribe for MsgLenBuilder<'a> {
 
     #[inline]
     fn end(self) -> Self::End {
-        assert!(
-            self.buf.len_stack.is_empty(),
-            "Overall end called before all submessages or packed data were ended"
-        );
-        MsgSerBuilder {
-            buf: self.buf,
-            next_len_index: 0,
+        match self.try_end() {
+            Ok(ser) => ser,
+            Err(EncodeError::Unclosed { .. }) => {
+                panic!("Overall end called before all submessages or packed data were ended")
+            }
+            Err(err) => panic!("Message could not be encoded: {err:?}"),
         }
     }
 }
 
-/// A [`std::fmt::Write`] implementation that only counts the number of
-/// bytes written without storing them.
-struct FmtByteCounter(usize);
-
-impl std::fmt::Write for FmtByteCounter {
-    fn write_str(&mut self, s: &str) -> std::fmt::Result {
-        self.0 += s.len();
-        Ok(())
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::builders::MsgBuilder;
 
     /// Helper to get `cur_len` after calling `add_display_str_field`.
-    fn display_str_cur_len(field_number: FieldNumber, value: &dyn std::fmt::Display) -> i32 {
+    fn display_str_cur_len(field_number: FieldNumber, value: &dyn core::fmt::Display) -> u64 {
         let mut b = MsgBuilder::new();
         let mut len = b.start(None);
         len.add_display_str_field(field_number, value).unwrap();
@@ -415,7 +535,7 @@ mod tests {
     }
 
     /// Helper to get `cur_len` after calling `add_debug_str_field`.
-    fn debug_str_cur_len(field_number: FieldNumber, value: &dyn std::fmt::Debug) -> i32 {
+    fn debug_str_cur_len(field_number: FieldNumber, value: &dyn core::fmt::Debug) -> u64 {
         let mut b = MsgBuilder::new();
         let mut len = b.start(None);
         len.add_debug_str_field(field_number, value).unwrap();
@@ -423,7 +543,7 @@ mod tests {
     }
 
     /// Helper to get `cur_len` after calling `add_string_field`.
-    fn string_cur_len(field_number: FieldNumber, value: &str) -> i32 {
+    fn string_cur_len(field_number: FieldNumber, value: &str) -> u64 {
         let mut b = MsgBuilder::new();
         let mut len = b.start(None);
         len.add_string_field(field_number, value);
@@ -444,8 +564,8 @@ mod tests {
         // empty Display output matches empty string
         {
             struct Empty;
-            impl std::fmt::Display for Empty {
-                fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            impl core::fmt::Display for Empty {
+                fn fmt(&self, _f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
                     Ok(())
                 }
             }
@@ -470,9 +590,9 @@ mod tests {
         // failing Display impl propagates the error
         {
             struct Failing;
-            impl std::fmt::Display for Failing {
-                fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                    Err(std::fmt::Error)
+            impl core::fmt::Display for Failing {
+                fn fmt(&self, _f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    Err(core::fmt::Error)
                 }
             }
             let mut b = MsgBuilder::new();
@@ -504,9 +624,9 @@ mod tests {
         // failing Debug impl propagates the error
         {
             struct Failing;
-            impl std::fmt::Debug for Failing {
-                fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                    Err(std::fmt::Error)
+            impl core::fmt::Debug for Failing {
+                fn fmt(&self, _f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    Err(core::fmt::Error)
                 }
             }
             let mut b = MsgBuilder::new();
@@ -514,4 +634,97 @@ mod tests {
             assert!(len.add_debug_str_field(FieldNumber(1), &Failing).is_err());
         }
     }
+
+    /// Display implementation writing `0` bytes with the given length
+    /// (without allocating them).
+    struct Zeros(u64);
+
+    impl core::fmt::Display for Zeros {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            let chunk = "0".repeat(1 << 20);
+            let mut left = self.0;
+            while left > 0 {
+                let n = left.min(chunk.len() as u64);
+                f.write_str(&chunk[..n as usize])?;
+                left -= n;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn max_len() {
+        const F1: FieldNumber = FieldNumber(1);
+        const F2: FieldNumber = FieldNumber(2);
+        const MAX: u64 = MsgBuilder::MAX_LEN_PROTOBUF;
+
+        fn ser(b: &mut MsgBuilder, strings: &[u64], nested: bool) -> Result<(), EncodeError> {
+            let mut len = b.start(None);
+            if nested {
+                len.start_msg_field(F2);
+            }
+            for s in strings {
+                len.add_display_str_field(F1, &Zeros(*s)).unwrap();
+            }
+            if nested {
+                len.end_msg_field(F2);
+            }
+            len.try_end().map(|_| ())
+        }
+
+        let mut b = MsgBuilder::new();
+
+        // string at the limit (but message above)
+        assert_eq!(
+            Err(EncodeError::LenTooBig {
+                field_number: None,
+                len: 1 + 5 + MAX,
+                max_len: MAX
+            }),
+            ser(&mut b, &[MAX], false)
+        );
+        // string too long
+        assert_eq!(
+            Err(EncodeError::LenTooBig {
+                field_number: Some(F1),
+                len: MAX + 1,
+                max_len: MAX
+            }),
+            ser(&mut b, &[MAX + 1], false)
+        );
+        // sub-message too long
+        assert_eq!(
+            Err(EncodeError::LenTooBig {
+                field_number: Some(F2),
+                len: 2 * (1 + 5 + MAX / 2),
+                max_len: MAX
+            }),
+            ser(&mut b, &[MAX / 2, MAX / 2], true)
+        );
+        // ok with a smaller limit
+        b.set_max_len(10);
+        assert_eq!(Ok(()), ser(&mut b, &[8], false));
+        assert!(ser(&mut b, &[9], false).is_err());
+
+        // 64 bit length accounting
+        b.set_max_len(MsgBuilder::MAX_LEN_64BIT);
+        assert_eq!(Ok(()), ser(&mut b, &[MAX / 2, MAX / 2], true));
+        assert_eq!(Ok(()), ser(&mut b, &[MAX + 1], true));
+        // lengths of sub-messages are determined with 64 bits
+        let mut len = b.start(None);
+        len.start_msg_field(F2);
+        len.add_display_str_field(F1, &Zeros(MAX + 1)).unwrap();
+        len.end_msg_field(F2);
+        assert_eq!(1 + 5 + MAX + 1, b.lens[0].1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn end_panics_on_max_len() {
+        let mut b = MsgBuilder::new();
+        b.set_max_len(1);
+        let mut len = b.start(None);
+        len.add_string_field(FieldNumber(1), "ab");
+        len.end();
+    }
 }
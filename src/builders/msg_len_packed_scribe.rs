@@ -8,25 +8,25 @@ pub struct MsgLenPackedScribe<'a, 'b> {
 impl<'a, 'b> PackedScribe for MsgLenPackedScribe<'a, 'b> {
     #[inline]
     fn add_int32(&mut self, value: i32) -> &mut Self {
-        self.parent.cur_len += WireVarInt::int32_byte_len(value);
+        self.parent.cur_len += WireVarInt::int32_byte_len(value) as u64;
         self
     }
 
     #[inline]
     fn add_int64(&mut self, value: i64) -> &mut Self {
-        self.parent.cur_len += WireVarInt::int64_byte_len(value);
+        self.parent.cur_len += WireVarInt::int64_byte_len(value) as u64;
         self
     }
 
     #[inline]
     fn add_uint32(&mut self, value: u32) -> &mut Self {
-        self.parent.cur_len += WireVarInt::uint32_byte_len(value);
+        self.parent.cur_len += WireVarInt::uint32_byte_len(value) as u64;
         self
     }
 
     #[inline]
     fn add_uint64(&mut self, value: u64) -> &mut Self {
-        self.parent.cur_len += WireVarInt::uint64_byte_len(value);
+        self.parent.cur_len += WireVarInt::uint64_byte_len(value) as u64;
         self
     }
 
@@ -38,19 +38,19 @@ impl<'a, 'b> PackedScribe for MsgLenPackedScribe<'a, 'b> {
 
     #[inline]
     fn add_enum(&mut self, value: i32) -> &mut Self {
-        self.parent.cur_len += WireVarInt::int32_byte_len(value);
+        self.parent.cur_len += WireVarInt::int32_byte_len(value) as u64;
         self
     }
 
     #[inline]
     fn add_sint32(&mut self, value: i32) -> &mut Self {
-        self.parent.cur_len += WireVarInt::sint32_byte_len(value);
+        self.parent.cur_len += WireVarInt::sint32_byte_len(value) as u64;
         self
     }
 
     #[inline]
     fn add_sint64(&mut self, value: i64) -> &mut Self {
-        self.parent.cur_len += WireVarInt::sint64_byte_len(value);
+        self.parent.cur_len += WireVarInt::sint64_byte_len(value) as u64;
         self
     }
 
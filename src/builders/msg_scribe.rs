@@ -66,22 +66,51 @@ pub trait MsgScribe {
     fn add_bytes(&mut self, field_number: FieldNumber, value: &[u8]) -> &mut Self;
 
     /// Adds a protobuf `string` field by formatting the given value using its
-    /// [`std::fmt::Display`] implementation directly into the serialization
+    /// [`core::fmt::Display`] implementation directly into the serialization
     /// buffer, avoiding intermediate [`String`] allocations.
     fn add_display_str(
         &mut self,
         field_number: FieldNumber,
-        value: &dyn std::fmt::Display,
-    ) -> Result<&mut Self, std::fmt::Error>;
+        value: &dyn core::fmt::Display,
+    ) -> Result<&mut Self, core::fmt::Error>;
 
     /// Adds a protobuf `string` field by formatting the given value using its
-    /// [`std::fmt::Debug`] implementation directly into the serialization
+    /// [`core::fmt::Debug`] implementation directly into the serialization
     /// buffer, avoiding intermediate [`String`] allocations.
     fn add_debug_str(
         &mut self,
         field_number: FieldNumber,
-        value: &dyn std::fmt::Debug,
-    ) -> Result<&mut Self, std::fmt::Error>;
+        value: &dyn core::fmt::Debug,
+    ) -> Result<&mut Self, core::fmt::Error>;
+
+    /// Adds an already serialized message as a sub-message field (LEN wire
+    /// type: tag + varint length + encoded message bytes).
+    ///
+    /// Produces the same bytes as serializing the message content between
+    /// [`start_msg`](Self::start_msg) & [`end_msg`](Self::end_msg). The
+    /// encoding is the same as for a `bytes` field, so by default the data
+    /// is written via [`add_bytes`](Self::add_bytes).
+    #[inline]
+    fn add_encoded_msg(&mut self, field_number: FieldNumber, encoded_msg: &[u8]) -> &mut Self {
+        self.add_bytes(field_number, encoded_msg)
+    }
+
+    /// Adds already encoded data (zero or more complete records) verbatim.
+    ///
+    /// The caller is responsible for passing valid encoded records. The
+    /// data is written via [`add_raw_record`](Self::add_raw_record).
+    #[inline]
+    fn add_raw(&mut self, data: &[u8]) -> &mut Self {
+        self.add_raw_record(data)
+    }
+
+    /// Adds an already encoded record (tag + value) verbatim.
+    ///
+    /// This can be used to re-emit records that were not interpreted while
+    /// decoding (e.g. unknown fields of a newer schema version), see
+    /// [`MsgDecoder::next_with_raw`](crate::decode::MsgDecoder::next_with_raw).
+    /// The caller is responsible for passing a valid encoded record.
+    fn add_raw_record(&mut self, record: &[u8]) -> &mut Self;
 
     /// Begins a nested sub-message field. All fields added after this call
     /// (until the matching [`end_msg`](Self::end_msg)) belong to the
@@ -92,6 +121,63 @@ pub trait MsgScribe {
     /// [`start_msg`](Self::start_msg).
     fn end_msg(&mut self, field_number: FieldNumber) -> &mut Self;
 
+    /// Adds a single entry of a protobuf `map<K, V>` field.
+    ///
+    /// Map entries are encoded as sub-messages with the key as field
+    /// [`FieldNumber::MAP_KEY`] and the value as field
+    /// [`FieldNumber::MAP_VALUE`]. `add_key` & `add_value` are the methods
+    /// used to add the key & value (e.g. `S::add_string` for a `string` key).
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")]
+    /// # fn main() {
+    /// use protobin::{builders::*, FieldNumber};
+    ///
+    /// // map<string, int32> counts = 3;
+    /// fn ser<S: MsgScribe>(mut s: S) -> S::End {
+    ///     let field = FieldNumber::try_from(3).unwrap();
+    ///     s.add_map_entry(field, "a", 1, S::add_string, S::add_int32);
+    ///     s.add_map_entry(field, "b", 2, S::add_string, S::add_int32);
+    ///     s.end()
+    /// }
+    ///
+    /// let mut builder = MsgBuilder::new();
+    /// let step2 = ser(builder.start(None));
+    /// let bytes = ser(step2);
+    /// assert_eq!(bytes, &[0x1a, 0x05, 0x0a, 0x01, b'a', 0x10, 0x01, 0x1a, 0x05, 0x0a, 0x01, b'b', 0x10, 0x02]);
+    /// # }
+    /// # #[cfg(not(feature = "alloc"))]
+    /// # fn main() {}
+    /// ```
+    fn add_map_entry<K, V>(
+        &mut self,
+        field_number: FieldNumber,
+        key: K,
+        value: V,
+        add_key: impl FnOnce(&mut Self, FieldNumber, K) -> &mut Self,
+        add_value: impl FnOnce(&mut Self, FieldNumber, V) -> &mut Self,
+    ) -> &mut Self {
+        self.start_msg(field_number);
+        add_key(self, FieldNumber::MAP_KEY, key);
+        add_value(self, FieldNumber::MAP_VALUE, value);
+        self.end_msg(field_number)
+    }
+
+    /// Adds all entries of a protobuf `map<K, V>` field (see
+    /// [`add_map_entry`](Self::add_map_entry) for details).
+    fn add_map<K, V>(
+        &mut self,
+        field_number: FieldNumber,
+        entries: impl IntoIterator<Item = (K, V)>,
+        mut add_key: impl FnMut(&mut Self, FieldNumber, K) -> &mut Self,
+        mut add_value: impl FnMut(&mut Self, FieldNumber, V) -> &mut Self,
+    ) -> &mut Self {
+        for (key, value) in entries {
+            self.add_map_entry(field_number, key, value, &mut add_key, &mut add_value);
+        }
+        self
+    }
+
     /// Begins a packed repeated field and returns a [`PackedScribe`] that can
     /// be used to add the packed elements. Must be paired with
     /// [`end_packed`](Self::end_packed) using the same `field_number`.
@@ -110,3 +196,14 @@ pub trait MsgScribe {
 
     // TODO add packed method with slice passing
 }
+
+/// A [`core::fmt::Write`] implementation that only counts the number of
+/// bytes written without storing them.
+pub(crate) struct FmtByteCounter(pub(crate) usize);
+
+impl core::fmt::Write for FmtByteCounter {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
@@ -1,45 +1,68 @@
-use crate::{builders::*, *};
+use crate::{builders::*, wire::*, *};
+use alloc::vec::Vec;
 
 /// Helper to determine serialize a message after all
 /// lengths have been determined.
+///
+/// By default the message is written to the internal buffer of the
+/// [`MsgBuilder`]. With the `bytes` feature it can also be appended to a
+/// `BytesMut` instead (see `MsgLenBuilder::end_bytes_mut`).
 #[derive(Debug, Eq, PartialEq, Hash)]
-pub struct MsgSerBuilder<'a> {
-    pub(crate) buf: &'a mut MsgBuilder,
+pub struct MsgSerBuilder<'a, O = &'a mut WireEncoder> {
+    pub(crate) lens: &'a [(FieldNumber, u64)],
+    pub(crate) ser_stack: &'a mut Vec<SerStackEntry>,
+    pub(crate) out: O,
+    /// Length of `out` when the serialization pass started.
+    pub(crate) start: usize,
     pub(crate) next_len_index: usize,
 }
 
 impl<'a> MsgSerBuilder<'a> {
+    /// Creates a serialization pass writing to the internal buffer of the
+    /// [`MsgBuilder`].
+    pub(crate) fn new(buf: &'a mut MsgBuilder) -> MsgSerBuilder<'a> {
+        MsgSerBuilder {
+            lens: &buf.lens,
+            ser_stack: &mut buf.ser_stack,
+            out: &mut buf.encoder,
+            start: 0,
+            next_len_index: 0,
+        }
+    }
+}
+
+impl<'a, O: SerOut> MsgSerBuilder<'a, O> {
     /// Writes the VARINT wire-type tag for the given field number.
     #[inline]
     fn add_varint_tag(&mut self, field_number: FieldNumber) {
-        self.buf.encoder.add_var_uint32(field_number.0 << 3);
+        self.out.put_tag(field_number, 0);
     }
 
     /// Serializes a protobuf `int32` field (tag + VARINT value) into the buffer.
     pub fn add_int32_field(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
         self.add_varint_tag(field_number);
-        self.buf.encoder.add_var_int32(value);
+        self.out.put_var_uint64(WireVarInt::from_int32(value).raw());
         self
     }
 
     /// Serializes a protobuf `int64` field (tag + VARINT value) into the buffer.
     pub fn add_int64_field(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
         self.add_varint_tag(field_number);
-        self.buf.encoder.add_var_int64(value);
+        self.out.put_var_uint64(WireVarInt::from_int64(value).raw());
         self
     }
 
     /// Serializes a protobuf `uint32` field (tag + VARINT value) into the buffer.
     pub fn add_uint32_field(&mut self, field_number: FieldNumber, value: u32) -> &mut Self {
         self.add_varint_tag(field_number);
-        self.buf.encoder.add_var_uint32(value);
+        self.out.put_var_uint64(u64::from(value));
         self
     }
 
     /// Serializes a protobuf `uint64` field (tag + VARINT value) into the buffer.
     pub fn add_uint64_field(&mut self, field_number: FieldNumber, value: u64) -> &mut Self {
         self.add_varint_tag(field_number);
-        self.buf.encoder.add_var_uint64(value);
+        self.out.put_var_uint64(value);
         self
     }
 
@@ -47,16 +70,14 @@ impl<'a> MsgSerBuilder<'a> {
     /// buffer.
     pub fn add_bool_field(&mut self, field_number: FieldNumber, value: bool) -> &mut Self {
         self.add_varint_tag(field_number);
-        self.buf.encoder.add_bool(value);
+        self.out.put_slice(&[u8::from(value)]);
         self
     }
 
     /// Serializes a protobuf `enum` field (tag + VARINT value) into the buffer.
     pub fn add_enum_field(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
         self.add_varint_tag(field_number);
-        self.buf
-            .encoder
-            .add_var_uint32(u32::from_ne_bytes(value.to_ne_bytes()));
+        self.out.put_var_uint64(WireVarInt::from_int32(value).raw());
         self
     }
 
@@ -64,7 +85,8 @@ impl<'a> MsgSerBuilder<'a> {
     /// the buffer.
     pub fn add_sint32_field(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
         self.add_varint_tag(field_number);
-        self.buf.encoder.add_var_sint32(value);
+        self.out
+            .put_var_uint64(WireVarInt::from_sint32(value).raw());
         self
     }
 
@@ -72,82 +94,83 @@ impl<'a> MsgSerBuilder<'a> {
     /// the buffer.
     pub fn add_sint64_field(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
         self.add_varint_tag(field_number);
-        self.buf.encoder.add_var_sint64(value);
+        self.out
+            .put_var_uint64(WireVarInt::from_sint64(value).raw());
         self
     }
 
     /// Serializes a protobuf `fixed32` field (I32 wire-type tag + 4 bytes
     /// little-endian) into the buffer.
     pub fn add_fixed32_field(&mut self, field_number: FieldNumber, value: u32) -> &mut Self {
-        self.buf.encoder.add_var_uint32((field_number.0 << 3) | 5);
-        self.buf.encoder.add_fixed32(value);
+        self.out.put_tag(field_number, 5);
+        self.out.put_fixed32(value);
         self
     }
 
     /// Serializes a protobuf `sfixed32` field (I32 wire-type tag + 4 bytes
     /// little-endian) into the buffer.
     pub fn add_sfixed32_field(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
-        self.buf.encoder.add_var_uint32((field_number.0 << 3) | 5);
-        self.buf.encoder.add_sfixed32(value);
+        self.out.put_tag(field_number, 5);
+        self.out
+            .put_fixed32(u32::from_ne_bytes(value.to_ne_bytes()));
         self
     }
 
     /// Serializes a protobuf `float` field (I32 wire-type tag + 4 bytes
     /// little-endian IEEE 754) into the buffer.
     pub fn add_float_field(&mut self, field_number: FieldNumber, value: f32) -> &mut Self {
-        self.buf.encoder.add_var_uint32((field_number.0 << 3) | 5);
-        self.buf
-            .encoder
-            .add_fixed32(u32::from_ne_bytes(value.to_ne_bytes()));
+        self.out.put_tag(field_number, 5);
+        self.out
+            .put_fixed32(u32::from_ne_bytes(value.to_ne_bytes()));
         self
     }
 
     /// Serializes a protobuf `fixed64` field (I64 wire-type tag + 8 bytes
     /// little-endian) into the buffer.
     pub fn add_fixed64_field(&mut self, field_number: FieldNumber, value: u64) -> &mut Self {
-        self.buf.encoder.add_var_uint32((field_number.0 << 3) | 1);
-        self.buf.encoder.add_fixed64(value);
+        self.out.put_tag(field_number, 1);
+        self.out.put_fixed64(value);
         self
     }
 
     /// Serializes a protobuf `sfixed64` field (I64 wire-type tag + 8 bytes
     /// little-endian) into the buffer.
     pub fn add_sfixed64_field(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
-        self.buf.encoder.add_var_uint32((field_number.0 << 3) | 1);
-        self.buf.encoder.add_sfixed64(value);
+        self.out.put_tag(field_number, 1);
+        self.out
+            .put_fixed64(u64::from_ne_bytes(value.to_ne_bytes()));
         self
     }
 
     /// Serializes a protobuf `double` field (I64 wire-type tag + 8 bytes
     /// little-endian IEEE 754) into the buffer.
     pub fn add_double_field(&mut self, field_number: FieldNumber, value: f64) -> &mut Self {
-        self.buf.encoder.add_var_uint32((field_number.0 << 3) | 1);
-        self.buf
-            .encoder
-            .add_fixed64(u64::from_ne_bytes(value.to_ne_bytes()));
+        self.out.put_tag(field_number, 1);
+        self.out
+            .put_fixed64(u64::from_ne_bytes(value.to_ne_bytes()));
         self
     }
 
     /// Serializes a protobuf `string` field (LEN wire-type tag + varint length
     /// + UTF-8 bytes) into the buffer.
     pub fn add_string_field(&mut self, field_number: FieldNumber, value: &str) -> &mut Self {
-        self.buf.encoder.add_var_uint32((field_number.0 << 3) | 2);
-        self.buf.encoder.add_var_uint32(value.len() as u32);
-        self.buf.encoder.buf.extend_from_slice(value.as_bytes());
+        self.out.put_tag(field_number, 2);
+        self.out.put_var_uint64(value.len() as u64);
+        self.out.put_slice(value.as_bytes());
         self
     }
 
     /// Serializes a protobuf `bytes` field (LEN wire-type tag + varint length
     /// + raw bytes) into the buffer.
     pub fn add_bytes_field(&mut self, field_number: FieldNumber, value: &[u8]) -> &mut Self {
-        self.buf.encoder.add_var_uint32((field_number.0 << 3) | 2);
-        self.buf.encoder.add_var_uint32(value.len() as u32);
-        self.buf.encoder.buf.extend_from_slice(value);
+        self.out.put_tag(field_number, 2);
+        self.out.put_var_uint64(value.len() as u64);
+        self.out.put_slice(value);
         self
     }
 
     /// Serializes a protobuf `string` field whose content is produced by the
-    /// [`std::fmt::Display`] implementation of `value`.
+    /// [`core::fmt::Display`] implementation of `value`.
     ///
     /// The formatted output is written directly into the encoding buffer
     /// without allocating an intermediate [`String`]. The byte length is
@@ -156,9 +179,9 @@ impl<'a> MsgSerBuilder<'a> {
     pub fn add_display_str_field(
         &mut self,
         field_number: FieldNumber,
-        value: &dyn std::fmt::Display,
-    ) -> Result<&mut Self, std::fmt::Error> {
-        use std::fmt::Write;
+        value: &dyn core::fmt::Display,
+    ) -> Result<&mut Self, core::fmt::Error> {
+        use core::fmt::Write;
 
         // count byte length of the formatted output
         let mut counter = FmtByteCounter(0);
@@ -166,17 +189,17 @@ impl<'a> MsgSerBuilder<'a> {
         let len = counter.0;
 
         // write tag + length
-        self.buf.encoder.add_var_uint32((field_number.0 << 3) | 2);
-        self.buf.encoder.add_var_uint32(len as u32);
+        self.out.put_tag(field_number, 2);
+        self.out.put_var_uint64(len as u64);
 
         // write data directly into the buffer
-        write!(FmtVecWriter(&mut self.buf.encoder.buf), "{}", value)?;
+        write!(FmtSerOutWriter(&mut self.out), "{}", value)?;
 
         Ok(self)
     }
 
     /// Serializes a protobuf `string` field whose content is produced by the
-    /// [`std::fmt::Debug`] implementation of `value`.
+    /// [`core::fmt::Debug`] implementation of `value`.
     ///
     /// The formatted output is written directly into the encoding buffer
     /// without allocating an intermediate [`String`]. The byte length is
@@ -185,9 +208,9 @@ impl<'a> MsgSerBuilder<'a> {
     pub fn add_debug_str_field(
         &mut self,
         field_number: FieldNumber,
-        value: &dyn std::fmt::Debug,
-    ) -> Result<&mut Self, std::fmt::Error> {
-        use std::fmt::Write;
+        value: &dyn core::fmt::Debug,
+    ) -> Result<&mut Self, core::fmt::Error> {
+        use core::fmt::Write;
 
         // count byte length of the formatted output
         let mut counter = FmtByteCounter(0);
@@ -195,26 +218,50 @@ impl<'a> MsgSerBuilder<'a> {
         let len = counter.0;
 
         // write tag + length
-        self.buf.encoder.add_var_uint32((field_number.0 << 3) | 2);
-        self.buf.encoder.add_var_uint32(len as u32);
+        self.out.put_tag(field_number, 2);
+        self.out.put_var_uint64(len as u64);
 
         // write data directly into the buffer
-        write!(FmtVecWriter(&mut self.buf.encoder.buf), "{:?}", value)?;
+        write!(FmtSerOutWriter(&mut self.out), "{:?}", value)?;
 
         Ok(self)
     }
 
+    /// Serializes an already serialized message as a sub-message field (LEN
+    /// wire-type tag + varint length + encoded message bytes) into the buffer.
+    pub fn add_encoded_msg_field(
+        &mut self,
+        field_number: FieldNumber,
+        encoded_msg: &[u8],
+    ) -> &mut Self {
+        // same encoding as for bytes
+        self.add_bytes_field(field_number, encoded_msg)
+    }
+
+    /// Writes already encoded data (zero or more complete records) verbatim
+    /// into the buffer.
+    pub fn add_raw(&mut self, data: &[u8]) -> &mut Self {
+        self.add_raw_record(data)
+    }
+
+    /// Writes an already encoded record (tag + value) verbatim into the
+    /// buffer.
+    pub fn add_raw_record(&mut self, record: &[u8]) -> &mut Self {
+        self.out.put_slice(record);
+        self
+    }
+
     fn start_len_area(&mut self, field_number: FieldNumber) -> &mut Self {
         // get length value
-        let len = self.buf.lens[self.next_len_index];
+        let len = self.lens[self.next_len_index];
         self.next_len_index += 1;
 
         // check that the field number is matching
         assert_eq!(len.0, field_number, "Field number in serialisation does not match the one from the length pass (expected {}, actual {})", len.0.0, field_number.0);
 
         // write tag and length value
-        self.buf.encoder.add_var_uint32((field_number.0 << 3) | 2);
-        self.buf.encoder.add_var_int32(len.1);
+        self.out.put_tag(field_number, 2);
+        self.out.put_var_uint64(len.1);
 
         self
     }
@@ -234,19 +281,144 @@ impl<'a> MsgSerBuilder<'a> {
     pub fn start_packed_field<'b>(
         &'b mut self,
         field_number: FieldNumber,
-    ) -> MsgSerPackedScribe<'a, 'b> {
+    ) -> MsgSerPackedScribe<'a, 'b, O> {
         MsgSerPackedScribe {
             parent: self.start_len_area(field_number),
         }
     }
+
+    pub(crate) fn try_start_len_area(
+        &mut self,
+        field_number: FieldNumber,
+        t: LenAreaType,
+    ) -> Result<&mut Self, EncodeError> {
+        // check that the field number is matching
+        let expected = self.lens.get(self.next_len_index).copied();
+        let Some(len) = expected.filter(|len| len.0 == field_number) else {
+            return Err(EncodeError::LenPassFieldMismatch {
+                expected: expected.map(|len| len.0),
+                actual: field_number,
+            });
+        };
+        self.next_len_index += 1;
+
+        // write tag and length value
+        self.out.put_tag(field_number, 2);
+        self.out.put_var_uint64(len.1);
+
+        // remember where the area starts to check the length at the end
+        self.ser_stack.push(SerStackEntry {
+            start: self.out.written_len(),
+            len: len.1,
+            t,
+            field_number,
+        });
+        Ok(self)
+    }
+
+    fn try_end_len_area(
+        &mut self,
+        field_number: FieldNumber,
+        t: LenAreaType,
+    ) -> Result<&mut Self, EncodeError> {
+        let Some(ended) = self.ser_stack.last() else {
+            return Err(EncodeError::EndWithoutStart { field_number, t });
+        };
+        if (ended.field_number, ended.t) != (field_number, t) {
+            return Err(EncodeError::EndMismatch {
+                expected_field_number: ended.field_number,
+                expected_t: ended.t,
+                actual_field_number: field_number,
+                actual_t: t,
+            });
+        }
+        let expected = ended.len as usize;
+        let actual = self.out.written_len() - ended.start;
+        self.ser_stack.pop();
+        if expected != actual {
+            return Err(EncodeError::LenPassLenMismatch {
+                field_number,
+                expected,
+                actual,
+            });
+        }
+        Ok(self)
+    }
+
+    /// Fallible version of [`start_msg_field`](Self::start_msg_field) that
+    /// returns an error instead of panicking if the field number does not
+    /// match the one from the length pass.
+    ///
+    /// Must be paired with [`try_end_msg_field`](Self::try_end_msg_field)
+    /// (the non fallible methods do not track the started sub-messages).
+    pub fn try_start_msg_field(
+        &mut self,
+        field_number: FieldNumber,
+    ) -> Result<&mut Self, EncodeError> {
+        self.try_start_len_area(field_number, LenAreaType::Msg)
+    }
+
+    /// Fallible version of [`start_packed_field`](Self::start_packed_field)
+    /// that returns an error instead of panicking if the field number does
+    /// not match the one from the length pass.
+    ///
+    /// Must be paired with [`try_end_packed_field`](Self::try_end_packed_field)
+    /// (the non fallible methods do not track the started packed fields).
+    pub fn try_start_packed_field<'b>(
+        &'b mut self,
+        field_number: FieldNumber,
+    ) -> Result<MsgSerPackedScribe<'a, 'b, O>, EncodeError> {
+        Ok(MsgSerPackedScribe {
+            parent: self.try_start_len_area(field_number, LenAreaType::Packed)?,
+        })
+    }
+
+    /// Ends a sub-message started with [`try_start_msg_field`](Self::try_start_msg_field)
+    /// & checks that the serialized content has the length determined in
+    /// the length pass.
+    pub fn try_end_msg_field(
+        &mut self,
+        field_number: FieldNumber,
+    ) -> Result<&mut Self, EncodeError> {
+        self.try_end_len_area(field_number, LenAreaType::Msg)
+    }
+
+    /// Ends a packed field started with [`try_start_packed_field`](Self::try_start_packed_field)
+    /// & checks that the serialized content has the length determined in
+    /// the length pass.
+    pub fn try_end_packed_field(
+        &mut self,
+        field_number: FieldNumber,
+    ) -> Result<&mut Self, EncodeError> {
+        self.try_end_len_area(field_number, LenAreaType::Packed)
+    }
+
+    /// Fallible version of [`MsgScribe::end`] that returns an error if not
+    /// all sub-messages or packed fields started with the `try_start_*`
+    /// methods were ended or if sub-messages or packed fields of the length
+    /// pass were not serialized.
+    pub fn try_end(self) -> Result<O::End, EncodeError> {
+        if let Some(open) = self.ser_stack.last() {
+            return Err(EncodeError::Unclosed {
+                field_number: open.field_number,
+                t: open.t,
+            });
+        }
+        if let Some(missing) = self.lens.get(self.next_len_index) {
+            return Err(EncodeError::LenPassFieldMissing {
+                expected: missing.0,
+            });
+        }
+        Ok(self.out.end(self.start))
+    }
 }
 
-impl<'a> MsgScribe for MsgSerBuilder<'a> {
+impl<'a, O: SerOut> MsgScribe for MsgSerBuilder<'a, O> {
     type Packed<'b>
-        = MsgSerPackedScribe<'a, 'b>
+        = MsgSerPackedScribe<'a, 'b, O>
     where
         Self: 'b;
-    type End = &'a [u8];
+    type End = O::End;
 
     #[inline]
     fn add_int32(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
@@ -332,8 +504,8 @@ impl<'a> MsgScribe for MsgSerBuilder<'a> {
     fn add_display_str(
         &mut self,
         field_number: FieldNumber,
-        value: &dyn std::fmt::Display,
-    ) -> Result<&mut Self, std::fmt::Error> {
+        value: &dyn core::fmt::Display,
+    ) -> Result<&mut Self, core::fmt::Error> {
         self.add_display_str_field(field_number, value)
     }
 
@@ -341,11 +513,21 @@ impl<'a> MsgScribe for MsgSerBuilder<'a> {
     fn add_debug_str(
         &mut self,
         field_number: FieldNumber,
-        value: &dyn std::fmt::Debug,
-    ) -> Result<&mut Self, std::fmt::Error> {
+        value: &dyn core::fmt::Debug,
+    ) -> Result<&mut Self, core::fmt::Error> {
         self.add_debug_str_field(field_number, value)
     }
 
+    #[inline]
+    fn add_encoded_msg(&mut self, field_number: FieldNumber, encoded_msg: &[u8]) -> &mut Self {
+        self.add_encoded_msg_field(field_number, encoded_msg)
+    }
+
+    #[inline]
+    fn add_raw_record(&mut self, record: &[u8]) -> &mut Self {
+        MsgSerBuilder::add_raw_record(self, record)
+    }
+
     #[inline]
     fn start_msg(&mut self, field_number: FieldNumber) -> &mut Self {
         self.start_msg_field(field_number)
@@ -357,7 +539,7 @@ impl<'a> MsgScribe for MsgSerBuilder<'a> {
     }
 
     #[inline]
-    fn start_packed<'b>(&'b mut self, field_number: FieldNumber) -> MsgSerPackedScribe<'a, 'b> {
+    fn start_packed<'b>(&'b mut self, field_number: FieldNumber) -> MsgSerPackedScribe<'a, 'b, O> {
         self.start_packed_field(field_number)
     }
 
@@ -368,29 +550,7 @@ impl<'a> MsgScribe for MsgSerBuilder<'a> {
 
     #[inline]
     fn end(self) -> Self::End {
-        &self.buf.encoder.buf
-    }
-}
-
-/// A [`std::fmt::Write`] implementation that only counts the number of
-/// bytes written without storing them.
-struct FmtByteCounter(usize);
-
-impl std::fmt::Write for FmtByteCounter {
-    fn write_str(&mut self, s: &str) -> std::fmt::Result {
-        self.0 += s.len();
-        Ok(())
-    }
-}
-
-/// A [`std::fmt::Write`] implementation that writes UTF-8 bytes directly
-/// into a [`Vec<u8>`].
-struct FmtVecWriter<'a>(&'a mut Vec<u8>);
-
-impl std::fmt::Write for FmtVecWriter<'_> {
-    fn write_str(&mut self, s: &str) -> std::fmt::Result {
-        self.0.extend_from_slice(s.as_bytes());
-        Ok(())
+        self.out.end(self.start)
     }
 }
 
@@ -399,10 +559,10 @@ mod tests {
     use super::*;
     use crate::builders::MsgBuilder;
     use crate::decode::MsgDecoder;
-    use crate::wire::WireValueRef;
+    use crate::wire::{WireLenRef, WireValueRef};
 
     /// Helper: run a two-phase encode using `add_display_str_field` on both phases.
-    fn encode_display(field_number: FieldNumber, value: &dyn std::fmt::Display) -> Vec<u8> {
+    fn encode_display(field_number: FieldNumber, value: &dyn core::fmt::Display) -> Vec<u8> {
         let mut builder = MsgBuilder::new();
 
         let mut len = builder.start(None);
@@ -413,7 +573,7 @@ mod tests {
     }
 
     /// Helper: run a two-phase encode using `add_debug_str_field` on both phases.
-    fn encode_debug(field_number: FieldNumber, value: &dyn std::fmt::Debug) -> Vec<u8> {
+    fn encode_debug(field_number: FieldNumber, value: &dyn core::fmt::Debug) -> Vec<u8> {
         let mut builder = MsgBuilder::new();
 
         let mut len = builder.start(None);
@@ -448,6 +608,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant, clippy::useless_format)]
     fn add_display_str_field() {
         // integer Display produces the same bytes as add_string_field
         {
@@ -468,8 +629,8 @@ mod tests {
         // empty Display output matches empty string field
         {
             struct Empty;
-            impl std::fmt::Display for Empty {
-                fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            impl core::fmt::Display for Empty {
+                fn fmt(&self, _f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
                     Ok(())
                 }
             }
@@ -488,8 +649,8 @@ mod tests {
                 x: i32,
                 y: i32,
             }
-            impl std::fmt::Display for Point {
-                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            impl core::fmt::Display for Point {
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
                     write!(f, "({}, {})", self.x, self.y)
                 }
             }
@@ -547,9 +708,9 @@ mod tests {
         // failing Display impl propagates the error
         {
             struct Failing;
-            impl std::fmt::Display for Failing {
-                fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                    Err(std::fmt::Error)
+            impl core::fmt::Display for Failing {
+                fn fmt(&self, _f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    Err(core::fmt::Error)
                 }
             }
             let mut builder = MsgBuilder::new();
@@ -612,9 +773,9 @@ mod tests {
         // failing Debug impl propagates the error
         {
             struct Failing;
-            impl std::fmt::Debug for Failing {
-                fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                    Err(std::fmt::Error)
+            impl core::fmt::Debug for Failing {
+                fn fmt(&self, _f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    Err(core::fmt::Error)
                 }
             }
             let mut builder = MsgBuilder::new();
@@ -622,4 +783,136 @@ mod tests {
             assert!(len.add_debug_str_field(FieldNumber(1), &Failing).is_err());
         }
     }
+
+    #[test]
+    fn add_raw_record() {
+        /// Re-encodes only the fields 1 & 3 (the "known" ones) and passes
+        /// all other records through via `add_raw_record`.
+        fn ser_known<S: MsgScribe>(data: &[u8], mut s: S) -> S::End {
+            let mut decoder = MsgDecoder::new(data);
+            while let Some(record) = decoder.next_with_raw() {
+                let (record, raw) = record.unwrap();
+                match (record.field_number.value(), record.value) {
+                    (1, WireValueRef::Len(v)) => {
+                        s.add_string(record.field_number, v.try_as_string().unwrap());
+                    }
+                    (3, v) => {
+                        s.add_int64(record.field_number, v.try_as_int64().unwrap());
+                    }
+                    _ => {
+                        s.add_raw_record(raw);
+                    }
+                }
+            }
+            s.end()
+        }
+
+        /// Original message containing fields unknown to `ser_known`.
+        fn ser_original<S: MsgScribe>(mut s: S) -> S::End {
+            s.add_string(FieldNumber(1), "known");
+            s.add_fixed64(FieldNumber(2), 1234);
+            s.add_int64(FieldNumber(3), -5);
+            s.start_msg(FieldNumber(4));
+            s.add_string(FieldNumber(1), "unknown sub message");
+            s.end_msg(FieldNumber(4));
+            s.add_bytes(FieldNumber(1000), &[0xff; 200]);
+            s.end()
+        }
+
+        let mut builder = MsgBuilder::new();
+        let step2 = ser_original(builder.start(None));
+        let original = ser_original(step2).to_vec();
+
+        // unknown fields in top level message
+        {
+            let step2 = ser_known(&original, builder.start(None));
+            let result = ser_known(&original, step2);
+            assert_eq!(original, result);
+        }
+
+        // unknown fields inside of a sub message (length has to be
+        // accounted for in the length phase)
+        {
+            fn ser_outer<S: MsgScribe>(inner: &[u8], mut s: S) -> S::End {
+                s.start_msg(FieldNumber(7));
+                let mut decoder = MsgDecoder::new(inner);
+                while let Some(record) = decoder.next_with_raw() {
+                    s.add_raw_record(record.unwrap().1);
+                }
+                s.end_msg(FieldNumber(7));
+                s.end()
+            }
+            let step2 = ser_outer(&original, builder.start(None));
+            let result = ser_outer(&original, step2).to_vec();
+
+            let mut decoder = MsgDecoder::new(&result);
+            let record = decoder.next().unwrap().unwrap();
+            assert_eq!(FieldNumber(7), record.field_number);
+            assert_eq!(
+                WireValueRef::Len(WireLenRef { data: &original }),
+                record.value
+            );
+            assert!(decoder.next().is_none());
+        }
+    }
+
+    #[test]
+    fn add_encoded_msg_and_add_raw() {
+        fn ser_inner<S: MsgScribe>(mut s: S) -> S {
+            s.add_string(FieldNumber(1), &"cached ".repeat(30));
+            s.add_uint32(FieldNumber(2), 42);
+            s
+        }
+
+        /// Envelope serializing the inner message via start_msg & end_msg.
+        fn ser_envelope<S: MsgScribe>(mut s: S) -> S::End {
+            s.add_uint64(FieldNumber(1), 7);
+            s.start_msg(FieldNumber(2));
+            s = ser_inner(s);
+            s.end_msg(FieldNumber(2));
+            s.add_bool(FieldNumber(3), true);
+            s.end()
+        }
+
+        /// Envelope re-using an already encoded inner message.
+        fn ser_cached_envelope<S: MsgScribe>(inner: &[u8], mut s: S) -> S::End {
+            s.add_uint64(FieldNumber(1), 7);
+            s.add_encoded_msg(FieldNumber(2), inner);
+            s.add_bool(FieldNumber(3), true);
+            s.end()
+        }
+
+        /// Envelope splicing in pre-encoded fields.
+        fn ser_raw_envelope<S: MsgScribe>(fields: &[u8], mut s: S) -> S::End {
+            s.add_raw(fields);
+            s.add_bool(FieldNumber(3), true);
+            s.end()
+        }
+
+        let mut builder = MsgBuilder::new();
+        let step2 = ser_envelope(builder.start(None));
+        let expected = ser_envelope(step2).to_vec();
+
+        let step2 = ser_inner(builder.start(None)).end();
+        let inner = ser_inner(step2).end().to_vec();
+
+        // add_encoded_msg
+        {
+            let step2 = ser_cached_envelope(&inner, builder.start(None));
+            assert_eq!(expected, ser_cached_envelope(&inner, step2));
+        }
+
+        // add_raw with multiple records
+        {
+            let fields = &expected[..expected.len() - 2];
+            let step2 = ser_raw_envelope(fields, builder.start(None));
+            assert_eq!(expected, ser_raw_envelope(fields, step2));
+        }
+
+        // add_raw with an empty slice
+        {
+            let step2 = ser_raw_envelope(&[], builder.start(None));
+            assert_eq!(&[0x18, 0x01], ser_raw_envelope(&[], step2));
+        }
+    }
 }
@@ -0,0 +1,11 @@
+use crate::FieldNumber;
+
+/// Error if more than one member of a protobuf `oneof` was written in
+/// the same message (see [`OneofScribe`](super::OneofScribe)).
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct OneofError {
+    /// Field number of the member that was written first.
+    pub first: FieldNumber,
+    /// Field number of the member that was written afterwards.
+    pub second: FieldNumber,
+}
@@ -0,0 +1,370 @@
+use crate::{builders::*, FieldNumber};
+use alloc::{vec, vec::Vec};
+
+/// [`MsgScribe`] wrapper that checks that at most one member of every
+/// declared protobuf `oneof` is written in a message.
+///
+/// Only the fields of the message the wrapper was created for are checked
+/// (fields of sub-messages are passed through unchecked). The first
+/// violation is recorded & returned by [`end`](MsgScribe::end) (the wrapped
+/// scribe still receives all calls, so both encoding phases stay in sync).
+///
+/// Records added via [`add_raw`](MsgScribe::add_raw) &
+/// [`add_raw_record`](MsgScribe::add_raw_record) are not checked.
+///
+/// ```
+/// use protobin::{builders::*, FieldNumber};
+///
+/// // message Event {
+/// //   uint64 id = 1;
+/// //   oneof payload {
+/// //     string text = 2;
+/// //     bytes data = 3;
+/// //   }
+/// // }
+/// fn ser_event<S: MsgScribe>(text: Option<&str>, data: Option<&[u8]>, mut s: S) -> S::End {
+///     s.add_uint64(1.try_into().unwrap(), 1234);
+///     if let Some(text) = text {
+///         s.add_string(2.try_into().unwrap(), text);
+///     }
+///     if let Some(data) = data {
+///         s.add_bytes(3.try_into().unwrap(), data);
+///     }
+///     s.end()
+/// }
+///
+/// let payload: [FieldNumber; 2] = [2.try_into().unwrap(), 3.try_into().unwrap()];
+/// let oneofs: [&[FieldNumber]; 1] = [&payload];
+/// let mut builder = MsgBuilder::new();
+///
+/// // ok
+/// let step2 = ser_event(Some("a"), None, OneofScribe::new(builder.start(None), &oneofs)).unwrap();
+/// let bytes = ser_event(Some("a"), None, OneofScribe::new(step2, &oneofs)).unwrap();
+/// assert_eq!(bytes, &[0x08, 0xd2, 0x09, 0x12, 0x01, b'a']);
+///
+/// // two members of the same oneof
+/// let result = ser_event(Some("a"), Some(b"b"), OneofScribe::new(builder.start(None), &oneofs));
+/// assert!(result.is_err());
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct OneofScribe<'o, S: MsgScribe> {
+    scribe: S,
+    oneofs: &'o [&'o [FieldNumber]],
+    /// Member written per oneof (same index as in `oneofs`).
+    written: Vec<Option<FieldNumber>>,
+    /// Sub-message nesting depth (only depth 0 is checked).
+    depth: usize,
+    /// First detected error.
+    error: Option<OneofError>,
+}
+
+impl<'o, S: MsgScribe> OneofScribe<'o, S> {
+    /// Wraps the given scribe. `oneofs` contains the member field numbers
+    /// of every `oneof` in the message.
+    pub fn new(scribe: S, oneofs: &'o [&'o [FieldNumber]]) -> OneofScribe<'o, S> {
+        OneofScribe {
+            scribe,
+            oneofs,
+            written: vec![None; oneofs.len()],
+            depth: 0,
+            error: None,
+        }
+    }
+
+    /// Returns a reference to the wrapped scribe.
+    pub fn inner(&self) -> &S {
+        &self.scribe
+    }
+
+    /// Returns the first detected error (if any).
+    pub fn error(&self) -> Option<&OneofError> {
+        self.error.as_ref()
+    }
+
+    /// Destroys the wrapper & returns the wrapped scribe together with
+    /// the first detected error (if any).
+    pub fn into_inner(self) -> (S, Option<OneofError>) {
+        (self.scribe, self.error)
+    }
+
+    /// Records that the given field is written & checks that no other
+    /// member of the same oneof was written before.
+    fn check(&mut self, field_number: FieldNumber) {
+        if self.depth != 0 {
+            return;
+        }
+        for (members, written) in self.oneofs.iter().zip(self.written.iter_mut()) {
+            if members.contains(&field_number) {
+                match written {
+                    Some(first) => {
+                        if self.error.is_none() {
+                            self.error = Some(OneofError {
+                                first: *first,
+                                second: field_number,
+                            });
+                        }
+                    }
+                    None => *written = Some(field_number),
+                }
+            }
+        }
+    }
+}
+
+impl<'o, S: MsgScribe> MsgScribe for OneofScribe<'o, S> {
+    type Packed<'a>
+        = S::Packed<'a>
+    where
+        Self: 'a;
+    type End = Result<S::End, OneofError>;
+
+    #[inline]
+    fn add_int32(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.check(field_number);
+        self.scribe.add_int32(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_int64(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
+        self.check(field_number);
+        self.scribe.add_int64(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_uint32(&mut self, field_number: FieldNumber, value: u32) -> &mut Self {
+        self.check(field_number);
+        self.scribe.add_uint32(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_uint64(&mut self, field_number: FieldNumber, value: u64) -> &mut Self {
+        self.check(field_number);
+        self.scribe.add_uint64(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_bool(&mut self, field_number: FieldNumber, value: bool) -> &mut Self {
+        self.check(field_number);
+        self.scribe.add_bool(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_enum(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.check(field_number);
+        self.scribe.add_enum(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_sint32(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.check(field_number);
+        self.scribe.add_sint32(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_sint64(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
+        self.check(field_number);
+        self.scribe.add_sint64(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_fixed32(&mut self, field_number: FieldNumber, value: u32) -> &mut Self {
+        self.check(field_number);
+        self.scribe.add_fixed32(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_sfixed32(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.check(field_number);
+        self.scribe.add_sfixed32(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_float(&mut self, field_number: FieldNumber, value: f32) -> &mut Self {
+        self.check(field_number);
+        self.scribe.add_float(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_fixed64(&mut self, field_number: FieldNumber, value: u64) -> &mut Self {
+        self.check(field_number);
+        self.scribe.add_fixed64(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_sfixed64(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
+        self.check(field_number);
+        self.scribe.add_sfixed64(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_double(&mut self, field_number: FieldNumber, value: f64) -> &mut Self {
+        self.check(field_number);
+        self.scribe.add_double(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_string(&mut self, field_number: FieldNumber, value: &str) -> &mut Self {
+        self.check(field_number);
+        self.scribe.add_string(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_bytes(&mut self, field_number: FieldNumber, value: &[u8]) -> &mut Self {
+        self.check(field_number);
+        self.scribe.add_bytes(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_display_str(
+        &mut self,
+        field_number: FieldNumber,
+        value: &dyn core::fmt::Display,
+    ) -> Result<&mut Self, core::fmt::Error> {
+        self.check(field_number);
+        self.scribe.add_display_str(field_number, value)?;
+        Ok(self)
+    }
+
+    #[inline]
+    fn add_debug_str(
+        &mut self,
+        field_number: FieldNumber,
+        value: &dyn core::fmt::Debug,
+    ) -> Result<&mut Self, core::fmt::Error> {
+        self.check(field_number);
+        self.scribe.add_debug_str(field_number, value)?;
+        Ok(self)
+    }
+
+    #[inline]
+    fn add_encoded_msg(&mut self, field_number: FieldNumber, encoded_msg: &[u8]) -> &mut Self {
+        self.check(field_number);
+        self.scribe.add_encoded_msg(field_number, encoded_msg);
+        self
+    }
+
+    #[inline]
+    fn add_raw_record(&mut self, record: &[u8]) -> &mut Self {
+        self.scribe.add_raw_record(record);
+        self
+    }
+
+    #[inline]
+    fn start_msg(&mut self, field_number: FieldNumber) -> &mut Self {
+        self.check(field_number);
+        self.depth += 1;
+        self.scribe.start_msg(field_number);
+        self
+    }
+
+    #[inline]
+    fn end_msg(&mut self, field_number: FieldNumber) -> &mut Self {
+        self.depth = self.depth.saturating_sub(1);
+        self.scribe.end_msg(field_number);
+        self
+    }
+
+    #[inline]
+    fn start_packed<'a>(&'a mut self, field_number: FieldNumber) -> S::Packed<'a> {
+        self.check(field_number);
+        self.scribe.start_packed(field_number)
+    }
+
+    #[inline]
+    fn end_packed(&mut self, field_number: FieldNumber) -> &mut Self {
+        self.scribe.end_packed(field_number);
+        self
+    }
+
+    #[inline]
+    fn end(self) -> Self::End {
+        match self.error {
+            Some(err) => Err(err),
+            None => Ok(self.scribe.end()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{builders::*, FieldNumber};
+
+    const A: [FieldNumber; 2] = [FieldNumber(2), FieldNumber(3)];
+    const B: [FieldNumber; 3] = [FieldNumber(4), FieldNumber(5), FieldNumber(6)];
+    const ONEOFS: [&[FieldNumber]; 2] = [&A, &B];
+
+    fn ser<S: MsgScribe>(fields: &[u32], mut s: S) -> S::End {
+        for f in fields {
+            let f = FieldNumber(*f);
+            if f == FieldNumber(6) {
+                // sub message with fields that have the same field number
+                // as oneof members in the outer message
+                s.start_msg(f);
+                s.add_uint32(FieldNumber(2), 1);
+                s.add_uint32(FieldNumber(3), 1);
+                s.end_msg(f);
+            } else if f == FieldNumber(5) {
+                s.start_packed(f).add_uint32(1).add_uint32(2);
+                s.end_packed(f);
+            } else {
+                s.add_uint32(f, 1);
+            }
+        }
+        s.end()
+    }
+
+    fn check(fields: &[u32]) -> Result<Vec<u8>, OneofError> {
+        let mut builder = MsgBuilder::new();
+        let step2 = ser(fields, OneofScribe::new(builder.start(None), &ONEOFS))?;
+        Ok(ser(fields, OneofScribe::new(step2, &ONEOFS))?.to_vec())
+    }
+
+    fn unchecked(fields: &[u32]) -> Vec<u8> {
+        let mut builder = MsgBuilder::new();
+        let step2 = ser(fields, builder.start(None));
+        ser(fields, step2).to_vec()
+    }
+
+    #[test]
+    fn ok() {
+        for fields in [&[][..], &[1, 1, 1], &[1, 2, 4], &[3, 1, 5], &[6, 1, 2]] {
+            assert_eq!(Ok(unchecked(fields)), check(fields));
+        }
+    }
+
+    #[test]
+    fn error() {
+        let tests = [
+            (&[2, 3][..], (2, 3)),
+            (&[3, 3], (3, 3)),
+            (&[1, 4, 2, 6], (4, 6)),
+            (&[5, 6, 4], (5, 6)),
+            (&[2, 4, 3, 5], (2, 3)),
+        ];
+        for (fields, (first, second)) in tests {
+            assert_eq!(
+                Err(OneofError {
+                    first: FieldNumber(first),
+                    second: FieldNumber(second)
+                }),
+                check(fields)
+            );
+        }
+    }
+}
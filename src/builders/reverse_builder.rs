@@ -0,0 +1,167 @@
+use crate::{builders::*, wire::VarIntBytes, FieldNumber};
+use alloc::{vec, vec::Vec};
+
+/// Buffers to serialize a message back to front in a single pass (should
+/// be reused as much as possible to avoid allocations).
+///
+/// The [`ReverseScribe`] returned by [`ReverseBuilder::start`] writes every
+/// field in front of the previously written ones, starting at the end of
+/// the buffer. When a sub-message or packed field is closed its content
+/// is already written & the length is known, so the length prefix can be
+/// written directly in its minimal form. In contrast to [`MsgBuilder`] the
+/// serialization function is only called once & no length values have to
+/// be stored, in contrast to [`SinglePassBuilder`] no data has to be moved.
+///
+/// # Reversed field order
+///
+/// As every field is written in front of the previous ones, the fields of
+/// a message end up in the reverse order of the calls. To get the same
+/// bytes as [`MsgBuilder`] the serialization function has to add the
+/// fields in reverse order (last field first, repeated & packed values
+/// from back to front). `start_msg` & `end_msg` (as well as `start_packed`
+/// & `end_packed`) are still called as a pair around the content of the
+/// sub-message. Map entries added via [`MsgScribe::add_map_entry`] are
+/// written with the key in front of the value.
+///
+/// Note that the order of repeated fields & packed values is significant
+/// in protobuf, so serialization functions written for the other builders
+/// can not be reused without reversing them.
+///
+/// ```
+/// use protobin::builders::*;
+///
+/// // message { uint32 a = 1; repeated string b = 2; }
+/// let a = 1.try_into().unwrap();
+/// let b = 2.try_into().unwrap();
+///
+/// let mut builder = ReverseBuilder::new();
+/// let mut s = builder.start();
+/// // add the fields from back to front
+/// for v in ["x", "y"].iter().rev() {
+///     s.add_string(b, v);
+/// }
+/// s.add_uint32(a, 3);
+/// let bytes = s.end().unwrap();
+/// assert_eq!(bytes, &[0x08, 0x03, 0x12, 0x01, b'x', 0x12, 0x01, b'y']);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ReverseBuilder {
+    /// Buffer containing the encoded bytes at its end (starting at `pos`).
+    pub(crate) buf: Vec<u8>,
+    /// Start of the encoded bytes in `buf`.
+    pub(crate) pos: usize,
+    /// Open sub-messages & packed fields.
+    pub(crate) stack: Vec<ReverseStackEntry>,
+    /// Buffer used to format `add_display_str` & `add_debug_str` values.
+    pub(crate) fmt_buf: Vec<u8>,
+    /// Maximum allowed byte length.
+    pub(crate) max_len: u64,
+}
+
+impl Default for ReverseBuilder {
+    fn default() -> Self {
+        ReverseBuilder {
+            buf: Vec::new(),
+            pos: 0,
+            stack: Vec::new(),
+            fmt_buf: Vec::new(),
+            max_len: MsgBuilder::MAX_LEN_PROTOBUF,
+        }
+    }
+}
+
+impl ReverseBuilder {
+    /// Minimum buffer size allocated when the buffer grows.
+    const MIN_CAPACITY: usize = 64;
+
+    /// Setup a new [`ReverseBuilder`].
+    pub fn new() -> ReverseBuilder {
+        Default::default()
+    }
+
+    /// Setup a new [`ReverseBuilder`] with a buffer of the given size
+    /// (messages up to this size are encoded without reallocation).
+    pub fn with_capacity(capacity: usize) -> ReverseBuilder {
+        ReverseBuilder {
+            buf: vec![0; capacity],
+            pos: capacity,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the maximum allowed byte length of messages, sub-messages,
+    /// packed fields, strings & bytes.
+    pub fn max_len(&self) -> u64 {
+        self.max_len
+    }
+
+    /// Sets the maximum allowed byte length of messages, sub-messages,
+    /// packed fields, strings & bytes (see [`MsgBuilder::set_max_len`]).
+    pub fn set_max_len(&mut self, max_len: u64) {
+        self.max_len = max_len;
+    }
+
+    /// Start encoding a new message.
+    pub fn start<'a>(&'a mut self) -> ReverseScribe<'a> {
+        // clear all previous data
+        self.pos = self.buf.len();
+        self.stack.clear();
+
+        ReverseScribe {
+            buf: self,
+            error: None,
+        }
+    }
+
+    /// Number of bytes written so far.
+    #[inline]
+    pub(crate) fn written_len(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    /// Writes the given bytes in front of the already written bytes.
+    #[inline]
+    pub(crate) fn prepend(&mut self, data: &[u8]) {
+        if self.pos < data.len() {
+            self.grow(data.len());
+        }
+        let start = self.pos - data.len();
+        self.buf[start..self.pos].copy_from_slice(data);
+        self.pos = start;
+    }
+
+    /// Writes the given value as VARINT in front of the already written
+    /// bytes.
+    #[inline]
+    pub(crate) fn prepend_var_uint64(&mut self, value: u64) {
+        self.prepend(VarIntBytes::new(value).as_slice());
+    }
+
+    /// Writes the tag in front of the already written bytes.
+    #[inline]
+    pub(crate) fn prepend_tag(&mut self, field_number: FieldNumber, wire_type: u32) {
+        self.prepend(VarIntBytes::tag(field_number, wire_type).as_slice());
+    }
+
+    /// Reallocates the buffer so at least `additional` bytes can be
+    /// prepended (the written bytes are moved to the end of the new buffer).
+    #[cold]
+    fn grow(&mut self, additional: usize) {
+        let written = self.written_len();
+        let new_len = (self.buf.len() * 2)
+            .max(written + additional)
+            .max(Self::MIN_CAPACITY);
+        let mut new_buf = vec![0; new_len];
+        new_buf[new_len - written..].copy_from_slice(&self.buf[self.pos..]);
+        self.buf = new_buf;
+        self.pos = new_len - written;
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub(crate) struct ReverseStackEntry {
+    /// Number of bytes written when the area was started.
+    pub(crate) end: usize,
+    pub(crate) t: LenAreaType,
+    pub(crate) field_number: FieldNumber,
+}
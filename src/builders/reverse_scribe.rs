@@ -0,0 +1,521 @@
+use crate::{builders::*, wire::WireVarInt, FieldNumber};
+
+/// Serializes a message back to front in a single pass (use
+/// [`ReverseBuilder`] to create).
+///
+/// Fields are written in front of the previously added fields, so they
+/// have to be added in reverse order (see [`ReverseBuilder`]).
+///
+/// Instead of panicking on mismatched `start_*` & `end_*` calls or on too
+/// long data the first error is recorded & returned by
+/// [`end`](MsgScribe::end).
+#[derive(Debug, Eq, PartialEq, Hash)]
+pub struct ReverseScribe<'a> {
+    pub(crate) buf: &'a mut ReverseBuilder,
+    /// First detected error.
+    pub(crate) error: Option<EncodeError>,
+}
+
+impl<'a> ReverseScribe<'a> {
+    /// Returns the first detected error (if any).
+    pub fn error(&self) -> Option<&EncodeError> {
+        self.error.as_ref()
+    }
+
+    /// Records the error if it is the first one.
+    fn record(&mut self, err: EncodeError) {
+        if self.error.is_none() {
+            self.error = Some(err);
+        }
+    }
+
+    /// Records an error if the length of a LEN field is bigger than the
+    /// maximum length.
+    #[inline]
+    fn check_len(&mut self, field_number: FieldNumber, len: u64) {
+        if len > self.buf.max_len {
+            self.record(EncodeError::LenTooBig {
+                field_number: Some(field_number),
+                len,
+                max_len: self.buf.max_len,
+            });
+        }
+    }
+
+    /// Writes the tag & length of a LEN field in front of its (already
+    /// written) content.
+    #[inline]
+    fn prepend_len_prefix(&mut self, field_number: FieldNumber, len: u64) {
+        self.check_len(field_number, len);
+        self.buf.prepend_var_uint64(len);
+        self.buf.prepend_tag(field_number, 2);
+    }
+
+    /// Formats the value with the given function & writes it as `string`
+    /// field.
+    fn add_fmt_str(
+        &mut self,
+        field_number: FieldNumber,
+        f: impl FnOnce(&mut FmtVecWriter<'_>) -> core::fmt::Result,
+    ) -> Result<&mut Self, core::fmt::Error> {
+        // the formatted length is only known afterwards, so format into
+        // a separate buffer first
+        let mut fmt_buf = core::mem::take(&mut self.buf.fmt_buf);
+        fmt_buf.clear();
+        let result = f(&mut FmtVecWriter(&mut fmt_buf));
+        if result.is_ok() {
+            self.add_bytes(field_number, &fmt_buf);
+        }
+        self.buf.fmt_buf = fmt_buf;
+        result.map(|_| self)
+    }
+
+    fn start_len_area(&mut self, field_number: FieldNumber, t: LenAreaType) -> &mut Self {
+        let end = self.buf.written_len();
+        self.buf.stack.push(ReverseStackEntry {
+            end,
+            t,
+            field_number,
+        });
+        self
+    }
+
+    fn end_len_area(&mut self, field_number: FieldNumber, t: LenAreaType) -> &mut Self {
+        let Some(ended) = self.buf.stack.last() else {
+            self.record(EncodeError::EndWithoutStart { field_number, t });
+            return self;
+        };
+        if (ended.field_number, ended.t) != (field_number, t) {
+            let err = EncodeError::EndMismatch {
+                expected_field_number: ended.field_number,
+                expected_t: ended.t,
+                actual_field_number: field_number,
+                actual_t: t,
+            };
+            self.record(err);
+            return self;
+        }
+        let len = (self.buf.written_len() - ended.end) as u64;
+        self.buf.stack.pop();
+        self.prepend_len_prefix(field_number, len);
+        self
+    }
+}
+
+impl<'a> MsgScribe for ReverseScribe<'a> {
+    type Packed<'b>
+        = ReversePackedScribe<'a, 'b>
+    where
+        Self: 'b;
+    type End = Result<&'a [u8], EncodeError>;
+
+    #[inline]
+    fn add_int32(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.buf
+            .prepend_var_uint64(WireVarInt::from_int32(value).raw());
+        self.buf.prepend_tag(field_number, 0);
+        self
+    }
+
+    #[inline]
+    fn add_int64(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
+        self.buf
+            .prepend_var_uint64(WireVarInt::from_int64(value).raw());
+        self.buf.prepend_tag(field_number, 0);
+        self
+    }
+
+    #[inline]
+    fn add_uint32(&mut self, field_number: FieldNumber, value: u32) -> &mut Self {
+        self.buf.prepend_var_uint64(u64::from(value));
+        self.buf.prepend_tag(field_number, 0);
+        self
+    }
+
+    #[inline]
+    fn add_uint64(&mut self, field_number: FieldNumber, value: u64) -> &mut Self {
+        self.buf.prepend_var_uint64(value);
+        self.buf.prepend_tag(field_number, 0);
+        self
+    }
+
+    #[inline]
+    fn add_bool(&mut self, field_number: FieldNumber, value: bool) -> &mut Self {
+        self.buf.prepend(&[u8::from(value)]);
+        self.buf.prepend_tag(field_number, 0);
+        self
+    }
+
+    #[inline]
+    fn add_enum(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.buf
+            .prepend_var_uint64(WireVarInt::from_int32(value).raw());
+        self.buf.prepend_tag(field_number, 0);
+        self
+    }
+
+    #[inline]
+    fn add_sint32(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.buf
+            .prepend_var_uint64(WireVarInt::from_sint32(value).raw());
+        self.buf.prepend_tag(field_number, 0);
+        self
+    }
+
+    #[inline]
+    fn add_sint64(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
+        self.buf
+            .prepend_var_uint64(WireVarInt::from_sint64(value).raw());
+        self.buf.prepend_tag(field_number, 0);
+        self
+    }
+
+    #[inline]
+    fn add_fixed32(&mut self, field_number: FieldNumber, value: u32) -> &mut Self {
+        self.buf.prepend(&value.to_le_bytes());
+        self.buf.prepend_tag(field_number, 5);
+        self
+    }
+
+    #[inline]
+    fn add_sfixed32(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.buf.prepend(&value.to_le_bytes());
+        self.buf.prepend_tag(field_number, 5);
+        self
+    }
+
+    #[inline]
+    fn add_float(&mut self, field_number: FieldNumber, value: f32) -> &mut Self {
+        self.buf.prepend(&value.to_le_bytes());
+        self.buf.prepend_tag(field_number, 5);
+        self
+    }
+
+    #[inline]
+    fn add_fixed64(&mut self, field_number: FieldNumber, value: u64) -> &mut Self {
+        self.buf.prepend(&value.to_le_bytes());
+        self.buf.prepend_tag(field_number, 1);
+        self
+    }
+
+    #[inline]
+    fn add_sfixed64(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
+        self.buf.prepend(&value.to_le_bytes());
+        self.buf.prepend_tag(field_number, 1);
+        self
+    }
+
+    #[inline]
+    fn add_double(&mut self, field_number: FieldNumber, value: f64) -> &mut Self {
+        self.buf.prepend(&value.to_le_bytes());
+        self.buf.prepend_tag(field_number, 1);
+        self
+    }
+
+    #[inline]
+    fn add_string(&mut self, field_number: FieldNumber, value: &str) -> &mut Self {
+        self.add_bytes(field_number, value.as_bytes())
+    }
+
+    #[inline]
+    fn add_bytes(&mut self, field_number: FieldNumber, value: &[u8]) -> &mut Self {
+        self.buf.prepend(value);
+        self.prepend_len_prefix(field_number, value.len() as u64);
+        self
+    }
+
+    fn add_display_str(
+        &mut self,
+        field_number: FieldNumber,
+        value: &dyn core::fmt::Display,
+    ) -> Result<&mut Self, core::fmt::Error> {
+        use core::fmt::Write;
+        self.add_fmt_str(field_number, |w| write!(w, "{}", value))
+    }
+
+    fn add_debug_str(
+        &mut self,
+        field_number: FieldNumber,
+        value: &dyn core::fmt::Debug,
+    ) -> Result<&mut Self, core::fmt::Error> {
+        use core::fmt::Write;
+        self.add_fmt_str(field_number, |w| write!(w, "{:?}", value))
+    }
+
+    #[inline]
+    fn add_encoded_msg(&mut self, field_number: FieldNumber, encoded_msg: &[u8]) -> &mut Self {
+        self.add_bytes(field_number, encoded_msg)
+    }
+
+    #[inline]
+    fn add_raw_record(&mut self, record: &[u8]) -> &mut Self {
+        self.buf.prepend(record);
+        self
+    }
+
+    #[inline]
+    fn start_msg(&mut self, field_number: FieldNumber) -> &mut Self {
+        self.start_len_area(field_number, LenAreaType::Msg)
+    }
+
+    #[inline]
+    fn end_msg(&mut self, field_number: FieldNumber) -> &mut Self {
+        self.end_len_area(field_number, LenAreaType::Msg)
+    }
+
+    /// Adds a single entry of a protobuf `map<K, V>` field (the value is
+    /// added before the key, so the key is written in front of the value).
+    fn add_map_entry<K, V>(
+        &mut self,
+        field_number: FieldNumber,
+        key: K,
+        value: V,
+        add_key: impl FnOnce(&mut Self, FieldNumber, K) -> &mut Self,
+        add_value: impl FnOnce(&mut Self, FieldNumber, V) -> &mut Self,
+    ) -> &mut Self {
+        self.start_msg(field_number);
+        add_value(self, FieldNumber::MAP_VALUE, value);
+        add_key(self, FieldNumber::MAP_KEY, key);
+        self.end_msg(field_number)
+    }
+
+    #[inline]
+    fn start_packed<'b>(&'b mut self, field_number: FieldNumber) -> ReversePackedScribe<'a, 'b> {
+        ReversePackedScribe {
+            parent: self.start_len_area(field_number, LenAreaType::Packed),
+        }
+    }
+
+    #[inline]
+    fn end_packed(&mut self, field_number: FieldNumber) -> &mut Self {
+        self.end_len_area(field_number, LenAreaType::Packed)
+    }
+
+    fn end(mut self) -> Self::End {
+        if let Some(open) = self.buf.stack.last() {
+            let err = EncodeError::Unclosed {
+                field_number: open.field_number,
+                t: open.t,
+            };
+            self.record(err);
+        }
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+        let len = self.buf.written_len() as u64;
+        if len > self.buf.max_len {
+            return Err(EncodeError::LenTooBig {
+                field_number: None,
+                len,
+                max_len: self.buf.max_len,
+            });
+        }
+        Ok(&self.buf.buf[self.buf.pos..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{builders::*, FieldNumber};
+
+    const MSG: FieldNumber = FieldNumber(1);
+    const STR: FieldNumber = FieldNumber(2);
+    const PACKED: FieldNumber = FieldNumber(3);
+    const MAP: FieldNumber = FieldNumber(4);
+    const SCALAR: FieldNumber = FieldNumber(5);
+
+    /// Serializes nested sub-messages with strings of the given lengths in
+    /// each level (in the regular order).
+    fn ser<S: MsgScribe>(depth: usize, str_lens: &[usize], s: &mut S) {
+        for (i, len) in str_lens.iter().enumerate() {
+            s.add_string(STR, &"x".repeat(*len));
+            if i == 1 {
+                s.add_display_str(STR, &len).unwrap();
+                s.add_debug_str(STR, &"y").unwrap();
+            }
+        }
+        s.start_packed(PACKED)
+            .add_uint32(300)
+            .add_sint32(-1)
+            .add_int32(-2)
+            .add_double(1.5);
+        s.end_packed(PACKED);
+        s.add_map_entry(MAP, "k", -3, S::add_string, S::add_int32);
+        s.add_int64(SCALAR, -4)
+            .add_sint64(SCALAR, -5)
+            .add_bool(SCALAR, true)
+            .add_enum(SCALAR, 6)
+            .add_fixed32(SCALAR, 7)
+            .add_sfixed64(SCALAR, -8)
+            .add_float(SCALAR, 9.5);
+        if depth > 0 {
+            s.start_msg(MSG);
+            ser(depth - 1, str_lens, s);
+            s.end_msg(MSG);
+            s.start_msg(MSG);
+            s.end_msg(MSG);
+        }
+    }
+
+    /// Same as `ser` but with all fields in reversed order.
+    fn ser_rev<S: MsgScribe>(depth: usize, str_lens: &[usize], s: &mut S) {
+        if depth > 0 {
+            s.start_msg(MSG);
+            s.end_msg(MSG);
+            s.start_msg(MSG);
+            ser_rev(depth - 1, str_lens, s);
+            s.end_msg(MSG);
+        }
+        s.add_float(SCALAR, 9.5)
+            .add_sfixed64(SCALAR, -8)
+            .add_fixed32(SCALAR, 7)
+            .add_enum(SCALAR, 6)
+            .add_bool(SCALAR, true)
+            .add_sint64(SCALAR, -5)
+            .add_int64(SCALAR, -4);
+        s.add_map_entry(MAP, "k", -3, S::add_string, S::add_int32);
+        s.start_packed(PACKED)
+            .add_double(1.5)
+            .add_int32(-2)
+            .add_sint32(-1)
+            .add_uint32(300);
+        s.end_packed(PACKED);
+        for (i, len) in str_lens.iter().enumerate().rev() {
+            if i == 1 {
+                s.add_debug_str(STR, &"y").unwrap();
+                s.add_display_str(STR, &len).unwrap();
+            }
+            s.add_string(STR, &"x".repeat(*len));
+        }
+    }
+
+    fn two_pass(depth: usize, str_lens: &[usize]) -> Vec<u8> {
+        let mut builder = MsgBuilder::new();
+        let mut len = builder.start(None);
+        ser(depth, str_lens, &mut len);
+        let mut s = len.end();
+        ser(depth, str_lens, &mut s);
+        s.end().to_vec()
+    }
+
+    #[test]
+    fn same_as_two_pass() {
+        let str_lens = [
+            &[][..],
+            &[0],
+            &[1, 100],
+            &[120, 7],
+            &[127, 128],
+            &[16_383, 0, 1],
+            &[16_384, 16_300],
+        ];
+        // reused builders (with & without preallocated buffer)
+        let mut builders = [ReverseBuilder::new(), ReverseBuilder::with_capacity(100)];
+        for str_lens in str_lens {
+            for depth in 0..4 {
+                let expected = two_pass(depth, str_lens);
+                for builder in builders.iter_mut() {
+                    let mut s = builder.start();
+                    ser_rev(depth, str_lens, &mut s);
+                    assert_eq!(expected, s.end().unwrap());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn error() {
+        use LenAreaType::*;
+
+        fn check(
+            builder: &mut ReverseBuilder,
+            f: impl FnOnce(&mut ReverseScribe<'_>),
+        ) -> Result<Vec<u8>, EncodeError> {
+            let mut s = builder.start();
+            f(&mut s);
+            s.end().map(|bytes| bytes.to_vec())
+        }
+
+        let mut builder = ReverseBuilder::new();
+        assert_eq!(
+            Err(EncodeError::EndWithoutStart {
+                field_number: MSG,
+                t: Msg
+            }),
+            check(&mut builder, |s| {
+                s.end_msg(MSG).end_msg(MSG);
+            })
+        );
+        assert_eq!(
+            Err(EncodeError::EndMismatch {
+                expected_field_number: MSG,
+                expected_t: Msg,
+                actual_field_number: MSG,
+                actual_t: Packed,
+            }),
+            check(&mut builder, |s| {
+                s.start_msg(MSG).end_packed(MSG);
+            })
+        );
+        assert_eq!(
+            Err(EncodeError::Unclosed {
+                field_number: STR,
+                t: Msg
+            }),
+            check(&mut builder, |s| {
+                s.start_msg(MSG).start_msg(STR);
+            })
+        );
+
+        builder.set_max_len(3);
+        assert_eq!(
+            Err(EncodeError::LenTooBig {
+                field_number: Some(STR),
+                len: 4,
+                max_len: 3
+            }),
+            check(&mut builder, |s| {
+                s.add_string(STR, "abcd");
+            })
+        );
+        assert_eq!(
+            Err(EncodeError::LenTooBig {
+                field_number: Some(STR),
+                len: 4,
+                max_len: 3
+            }),
+            check(&mut builder, |s| {
+                s.add_display_str(STR, &"abcd").unwrap();
+            })
+        );
+        assert_eq!(
+            Err(EncodeError::LenTooBig {
+                field_number: Some(MSG),
+                len: 4,
+                max_len: 3
+            }),
+            check(&mut builder, |s| {
+                s.start_msg(MSG).add_uint32(STR, 1 << 14).end_msg(MSG);
+            })
+        );
+        assert_eq!(
+            Err(EncodeError::LenTooBig {
+                field_number: None,
+                len: 4,
+                max_len: 3
+            }),
+            check(&mut builder, |s| {
+                s.add_uint32(STR, 1).add_uint32(STR, 1);
+            })
+        );
+
+        // builder can be reused after an error
+        builder.set_max_len(MsgBuilder::MAX_LEN_PROTOBUF);
+        assert_eq!(
+            Ok(vec![0x10, 0x01]),
+            check(&mut builder, |s| {
+                s.add_uint32(STR, 1);
+            })
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{wire::*, FieldNumber};
+
+/// Output buffer of a [`crate::builders::MsgSerBuilder`].
+///
+/// Implemented for the internal buffer of the [`crate::builders::MsgBuilder`]
+/// (`&mut WireEncoder`) and for `&mut bytes::BytesMut` (`bytes` feature).
+/// The trait is not nameable outside of the crate, so no other outputs can
+/// be added.
+pub trait SerOut {
+    /// Value returned by the `end` of the serialization pass.
+    type End;
+
+    /// Number of bytes in the buffer (including bytes that were already
+    /// present before the serialization pass started).
+    fn written_len(&self) -> usize;
+
+    /// Appends the bytes.
+    fn put_slice(&mut self, data: &[u8]);
+
+    /// Appends the value as VARINT.
+    #[inline]
+    fn put_var_uint64(&mut self, value: u64) {
+        self.put_slice(VarIntBytes::new(value).as_slice());
+    }
+
+    /// Appends the value as 4 byte little endian.
+    #[inline]
+    fn put_fixed32(&mut self, value: u32) {
+        self.put_slice(&value.to_le_bytes());
+    }
+
+    /// Appends the value as 8 byte little endian.
+    #[inline]
+    fn put_fixed64(&mut self, value: u64) {
+        self.put_slice(&value.to_le_bytes());
+    }
+
+    /// Appends the tag of a record.
+    #[inline]
+    fn put_tag(&mut self, field_number: FieldNumber, wire_type: u32) {
+        self.put_var_uint64(u64::from((field_number.0 << 3) | wire_type));
+    }
+
+    /// Ends the serialization pass. `start` is the [`SerOut::written_len`]
+    /// at the start of the pass.
+    fn end(self, start: usize) -> Self::End;
+}
+
+impl<'a> SerOut for &'a mut WireEncoder {
+    type End = &'a [u8];
+
+    #[inline]
+    fn written_len(&self) -> usize {
+        self.buf.len()
+    }
+
+    #[inline]
+    fn put_slice(&mut self, data: &[u8]) {
+        self.buf.extend_from_slice(data);
+    }
+
+    #[inline]
+    fn put_var_uint64(&mut self, value: u64) {
+        self.add_var_uint64(value);
+    }
+
+    #[inline]
+    fn put_fixed32(&mut self, value: u32) {
+        self.add_fixed32(value);
+    }
+
+    #[inline]
+    fn put_fixed64(&mut self, value: u64) {
+        self.add_fixed64(value);
+    }
+
+    /// Returns the whole buffer (the internal buffer is cleared when the
+    /// length pass starts).
+    #[inline]
+    fn end(self, _: usize) -> &'a [u8] {
+        let encoder: &'a WireEncoder = self;
+        &encoder.buf
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl SerOut for &mut bytes::BytesMut {
+    type End = bytes::Bytes;
+
+    #[inline]
+    fn written_len(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn put_slice(&mut self, data: &[u8]) {
+        self.extend_from_slice(data);
+    }
+
+    /// Splits the bytes written by the serialization pass off without
+    /// copying. Bytes that were present before stay in the `BytesMut`.
+    #[inline]
+    fn end(self, start: usize) -> bytes::Bytes {
+        if start == 0 {
+            // keeps the remaining capacity for the next message
+            self.split().freeze()
+        } else {
+            self.split_off(start).freeze()
+        }
+    }
+}
+
+/// A [`core::fmt::Write`] implementation that writes UTF-8 bytes directly
+/// into a [`SerOut`].
+pub(crate) struct FmtSerOutWriter<'s, O: SerOut>(pub(crate) &'s mut O);
+
+impl<O: SerOut> core::fmt::Write for FmtSerOutWriter<'_, O> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.0.put_slice(s.as_bytes());
+        Ok(())
+    }
+}
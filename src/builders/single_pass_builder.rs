@@ -0,0 +1,125 @@
+use crate::{builders::*, wire::*, FieldNumber};
+use alloc::vec::Vec;
+
+/// Buffers to serialize a message in a single pass (should be reused as
+/// much as possible to avoid allocations).
+///
+/// In contrast to [`MsgBuilder`] the serialization function is only called
+/// once. Instead of determining the lengths of sub-messages & packed fields
+/// upfront, a fixed 5 byte (non-minimal) varint is reserved for every length
+/// and filled in ("backpatched") when the sub-message or packed field ends.
+/// This is useful for producers that can not replay their data twice (e.g.
+/// iterators or database cursors) & avoids running the serialization code
+/// twice for small messages.
+///
+/// Non-minimal varints are valid protobuf, but waste up to 4 bytes per
+/// sub-message. If [compaction](SinglePassBuilder::set_compact) is enabled
+/// the lengths are shrunk to their minimal form at the end (producing the
+/// same bytes as [`MsgBuilder`]).
+///
+/// As a 5 byte varint can only hold 35 bits, sub-messages & packed fields
+/// are additionally limited to `2^35 - 1` bytes (independent of the
+/// [maximum length](SinglePassBuilder::set_max_len)). The lengths of
+/// sub-messages & packed fields are checked before compaction.
+///
+/// ```
+/// use protobin::builders::*;
+///
+/// fn ser<S: MsgScribe>(values: impl Iterator<Item = u32>, mut s: S) -> S::End {
+///     let f = 1.try_into().unwrap();
+///     s.start_msg(f);
+///     for v in values {
+///         s.add_uint32(1.try_into().unwrap(), v);
+///     }
+///     s.end_msg(f);
+///     s.end()
+/// }
+///
+/// let mut builder = SinglePassBuilder::new();
+/// let bytes = ser([1, 2].into_iter(), builder.start()).unwrap();
+/// assert_eq!(bytes, &[0x0a, 0x84, 0x80, 0x80, 0x80, 0x00, 0x08, 0x01, 0x08, 0x02]);
+///
+/// builder.set_compact(true);
+/// let bytes = ser([1, 2].into_iter(), builder.start()).unwrap();
+/// assert_eq!(bytes, &[0x0a, 0x04, 0x08, 0x01, 0x08, 0x02]);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct SinglePassBuilder {
+    pub(crate) encoder: WireEncoder,
+    /// Open sub-messages & packed fields.
+    pub(crate) stack: Vec<SlotStackEntry>,
+    /// Position & length of all reserved length slots in order of their
+    /// position (only recorded if compaction is enabled).
+    pub(crate) slots: Vec<(usize, u64)>,
+    /// Maximum allowed byte length.
+    pub(crate) max_len: u64,
+    /// Shrink the lengths to their minimal form at the end.
+    pub(crate) compact: bool,
+}
+
+impl Default for SinglePassBuilder {
+    fn default() -> Self {
+        SinglePassBuilder {
+            encoder: WireEncoder::default(),
+            stack: Vec::new(),
+            slots: Vec::new(),
+            max_len: MsgBuilder::MAX_LEN_PROTOBUF,
+            compact: false,
+        }
+    }
+}
+
+impl SinglePassBuilder {
+    /// Setup a new [`SinglePassBuilder`] (compaction disabled).
+    pub fn new() -> SinglePassBuilder {
+        Default::default()
+    }
+
+    /// Returns true if the lengths are shrunk to their minimal form at
+    /// the end.
+    pub fn compact(&self) -> bool {
+        self.compact
+    }
+
+    /// Enables or disables shrinking the reserved 5 byte lengths to their
+    /// minimal form at the end (costs an additional pass over the encoded
+    /// data).
+    pub fn set_compact(&mut self, compact: bool) {
+        self.compact = compact;
+    }
+
+    /// Returns the maximum allowed byte length of messages, sub-messages,
+    /// packed fields, strings & bytes.
+    pub fn max_len(&self) -> u64 {
+        self.max_len
+    }
+
+    /// Sets the maximum allowed byte length of messages, sub-messages,
+    /// packed fields, strings & bytes (see [`MsgBuilder::set_max_len`]).
+    pub fn set_max_len(&mut self, max_len: u64) {
+        self.max_len = max_len;
+    }
+
+    /// Start encoding a new message.
+    pub fn start<'a>(&'a mut self) -> SinglePassScribe<'a> {
+        // clear all previous data
+        self.encoder.buf.clear();
+        self.stack.clear();
+        self.slots.clear();
+
+        SinglePassScribe {
+            buf: self,
+            error: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub(crate) struct SlotStackEntry {
+    /// Position of the reserved length slot in the buffer.
+    pub(crate) pos: usize,
+    /// Index in `slots` (only valid if compaction is enabled).
+    pub(crate) slot_index: usize,
+    pub(crate) t: LenAreaType,
+    pub(crate) field_number: FieldNumber,
+}
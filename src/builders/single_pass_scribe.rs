@@ -0,0 +1,564 @@
+use crate::{builders::*, wire::*, FieldNumber};
+use alloc::vec::Vec;
+
+/// Serializes a message in a single pass (use [`SinglePassBuilder`] to
+/// create).
+///
+/// Instead of panicking on mismatched `start_*` & `end_*` calls or on too
+/// long data the first error is recorded & returned by
+/// [`end`](MsgScribe::end).
+#[derive(Debug, Eq, PartialEq, Hash)]
+pub struct SinglePassScribe<'a> {
+    pub(crate) buf: &'a mut SinglePassBuilder,
+    /// First detected error.
+    pub(crate) error: Option<EncodeError>,
+}
+
+/// Byte length of a reserved length slot.
+const SLOT_LEN: usize = 5;
+
+/// Maximum length that can be stored in a reserved length slot.
+const SLOT_MAX_LEN: u64 = (1 << (7 * SLOT_LEN)) - 1;
+
+impl<'a> SinglePassScribe<'a> {
+    /// Returns the first detected error (if any).
+    pub fn error(&self) -> Option<&EncodeError> {
+        self.error.as_ref()
+    }
+
+    /// Records the error if it is the first one.
+    fn record(&mut self, err: EncodeError) {
+        if self.error.is_none() {
+            self.error = Some(err);
+        }
+    }
+
+    /// Writes the tag with the given wire type.
+    #[inline]
+    fn add_tag(&mut self, field_number: FieldNumber, wire_type: u32) {
+        self.buf
+            .encoder
+            .add_var_uint32((field_number.0 << 3) | wire_type);
+    }
+
+    /// Records an error if the length of a LEN field is bigger than the
+    /// maximum length.
+    #[inline]
+    fn check_len(&mut self, field_number: FieldNumber, len: u64) {
+        if len > self.buf.max_len {
+            self.record(EncodeError::LenTooBig {
+                field_number: Some(field_number),
+                len,
+                max_len: self.buf.max_len,
+            });
+        }
+    }
+
+    /// Writes the tag & length of a LEN field.
+    #[inline]
+    fn add_len_prefix(&mut self, field_number: FieldNumber, len: usize) {
+        self.check_len(field_number, len as u64);
+        self.add_tag(field_number, 2);
+        self.buf.encoder.add_var_uint64(len as u64);
+    }
+
+    /// Inserts the tag & length of a LEN field in front of the content
+    /// that was written starting at `start`.
+    fn insert_len_prefix(&mut self, field_number: FieldNumber, start: usize) {
+        let len = (self.buf.encoder.buf.len() - start) as u64;
+        self.check_len(field_number, len);
+        let tag = VarIntBytes::tag(field_number, 2);
+        let len = VarIntBytes::new(len);
+        let prefix = tag.as_slice().iter().chain(len.as_slice());
+        self.buf.encoder.buf.splice(start..start, prefix.copied());
+    }
+
+    fn start_len_area(&mut self, field_number: FieldNumber, t: LenAreaType) -> &mut Self {
+        self.add_tag(field_number, 2);
+
+        // reserve the length slot (filled in when the area ends)
+        let pos = self.buf.encoder.buf.len();
+        self.buf
+            .encoder
+            .buf
+            .extend_from_slice(&[0x80, 0x80, 0x80, 0x80, 0x00]);
+        let slot_index = self.buf.slots.len();
+        if self.buf.compact {
+            self.buf.slots.push((pos, 0));
+        }
+        self.buf.stack.push(SlotStackEntry {
+            pos,
+            slot_index,
+            t,
+            field_number,
+        });
+        self
+    }
+
+    fn end_len_area(&mut self, field_number: FieldNumber, t: LenAreaType) -> &mut Self {
+        let Some(ended) = self.buf.stack.last() else {
+            self.record(EncodeError::EndWithoutStart { field_number, t });
+            return self;
+        };
+        if (ended.field_number, ended.t) != (field_number, t) {
+            let err = EncodeError::EndMismatch {
+                expected_field_number: ended.field_number,
+                expected_t: ended.t,
+                actual_field_number: field_number,
+                actual_t: t,
+            };
+            self.record(err);
+            return self;
+        }
+        let (pos, slot_index) = (ended.pos, ended.slot_index);
+        self.buf.stack.pop();
+
+        // check the length
+        let len = (self.buf.encoder.buf.len() - pos - SLOT_LEN) as u64;
+        let max_len = self.buf.max_len.min(SLOT_MAX_LEN);
+        if len > max_len {
+            self.record(EncodeError::LenTooBig {
+                field_number: Some(field_number),
+                len,
+                max_len,
+            });
+        }
+
+        // backpatch the length (as non-minimal 5 byte varint)
+        let slot = &mut self.buf.encoder.buf[pos..pos + SLOT_LEN];
+        for (i, b) in slot.iter_mut().enumerate() {
+            *b = ((len >> (7 * i)) & 0x7f) as u8 | if i + 1 < SLOT_LEN { 0x80 } else { 0 };
+        }
+        if self.buf.compact {
+            self.buf.slots[slot_index].1 = len;
+        }
+        self
+    }
+
+    /// Shrinks all reserved length slots to minimal varints.
+    fn compact(&mut self) {
+        let slots = &mut self.buf.slots;
+
+        // determine the new lengths (inner areas shrink first, so process
+        // the slots from back to front & keep a stack of already processed
+        // areas that are not yet known to be enclosed by another area)
+        let mut processed: Vec<(usize, u64)> = Vec::new(); // (position, saved bytes)
+        for (pos, len) in slots.iter_mut().rev() {
+            let end = *pos + SLOT_LEN + *len as usize;
+            let mut inner_saved = 0;
+            while let Some(&(inner_pos, saved)) = processed.last() {
+                if inner_pos >= end {
+                    break;
+                }
+                inner_saved += saved;
+                processed.pop();
+            }
+            *len -= inner_saved;
+            let saved = (SLOT_LEN - WireVarInt::uint64_byte_len(*len) as usize) as u64;
+            processed.push((*pos, inner_saved + saved));
+        }
+
+        // move the data & write the minimal lengths
+        let buf = &mut self.buf.encoder.buf;
+        let mut read = 0;
+        let mut write = 0;
+        for &(pos, len) in slots.iter() {
+            buf.copy_within(read..pos, write);
+            write += pos - read;
+            write += write_var_uint64(&mut buf[write..], len);
+            read = pos + SLOT_LEN;
+        }
+        let end = buf.len();
+        buf.copy_within(read..end, write);
+        buf.truncate(write + end - read);
+    }
+}
+
+impl<'a> MsgScribe for SinglePassScribe<'a> {
+    type Packed<'b>
+        = SinglePassPackedScribe<'a, 'b>
+    where
+        Self: 'b;
+    type End = Result<&'a [u8], EncodeError>;
+
+    #[inline]
+    fn add_int32(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.add_tag(field_number, 0);
+        self.buf.encoder.add_var_int32(value);
+        self
+    }
+
+    #[inline]
+    fn add_int64(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
+        self.add_tag(field_number, 0);
+        self.buf.encoder.add_var_int64(value);
+        self
+    }
+
+    #[inline]
+    fn add_uint32(&mut self, field_number: FieldNumber, value: u32) -> &mut Self {
+        self.add_tag(field_number, 0);
+        self.buf.encoder.add_var_uint32(value);
+        self
+    }
+
+    #[inline]
+    fn add_uint64(&mut self, field_number: FieldNumber, value: u64) -> &mut Self {
+        self.add_tag(field_number, 0);
+        self.buf.encoder.add_var_uint64(value);
+        self
+    }
+
+    #[inline]
+    fn add_bool(&mut self, field_number: FieldNumber, value: bool) -> &mut Self {
+        self.add_tag(field_number, 0);
+        self.buf.encoder.add_bool(value);
+        self
+    }
+
+    #[inline]
+    fn add_enum(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.add_tag(field_number, 0);
+        self.buf.encoder.add_var_int32(value);
+        self
+    }
+
+    #[inline]
+    fn add_sint32(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.add_tag(field_number, 0);
+        self.buf.encoder.add_var_sint32(value);
+        self
+    }
+
+    #[inline]
+    fn add_sint64(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
+        self.add_tag(field_number, 0);
+        self.buf.encoder.add_var_sint64(value);
+        self
+    }
+
+    #[inline]
+    fn add_fixed32(&mut self, field_number: FieldNumber, value: u32) -> &mut Self {
+        self.add_tag(field_number, 5);
+        self.buf.encoder.add_fixed32(value);
+        self
+    }
+
+    #[inline]
+    fn add_sfixed32(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.add_tag(field_number, 5);
+        self.buf.encoder.add_sfixed32(value);
+        self
+    }
+
+    #[inline]
+    fn add_float(&mut self, field_number: FieldNumber, value: f32) -> &mut Self {
+        self.add_tag(field_number, 5);
+        self.buf.encoder.add_float(value);
+        self
+    }
+
+    #[inline]
+    fn add_fixed64(&mut self, field_number: FieldNumber, value: u64) -> &mut Self {
+        self.add_tag(field_number, 1);
+        self.buf.encoder.add_fixed64(value);
+        self
+    }
+
+    #[inline]
+    fn add_sfixed64(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
+        self.add_tag(field_number, 1);
+        self.buf.encoder.add_sfixed64(value);
+        self
+    }
+
+    #[inline]
+    fn add_double(&mut self, field_number: FieldNumber, value: f64) -> &mut Self {
+        self.add_tag(field_number, 1);
+        self.buf.encoder.add_double(value);
+        self
+    }
+
+    #[inline]
+    fn add_string(&mut self, field_number: FieldNumber, value: &str) -> &mut Self {
+        self.add_bytes(field_number, value.as_bytes())
+    }
+
+    #[inline]
+    fn add_bytes(&mut self, field_number: FieldNumber, value: &[u8]) -> &mut Self {
+        self.add_len_prefix(field_number, value.len());
+        self.buf.encoder.buf.extend_from_slice(value);
+        self
+    }
+
+    fn add_display_str(
+        &mut self,
+        field_number: FieldNumber,
+        value: &dyn core::fmt::Display,
+    ) -> Result<&mut Self, core::fmt::Error> {
+        use core::fmt::Write;
+
+        // format into the buffer & insert the length afterwards
+        let start = self.buf.encoder.buf.len();
+        write!(FmtVecWriter(&mut self.buf.encoder.buf), "{}", value)?;
+        self.insert_len_prefix(field_number, start);
+        Ok(self)
+    }
+
+    fn add_debug_str(
+        &mut self,
+        field_number: FieldNumber,
+        value: &dyn core::fmt::Debug,
+    ) -> Result<&mut Self, core::fmt::Error> {
+        use core::fmt::Write;
+
+        // format into the buffer & insert the length afterwards
+        let start = self.buf.encoder.buf.len();
+        write!(FmtVecWriter(&mut self.buf.encoder.buf), "{:?}", value)?;
+        self.insert_len_prefix(field_number, start);
+        Ok(self)
+    }
+
+    #[inline]
+    fn add_encoded_msg(&mut self, field_number: FieldNumber, encoded_msg: &[u8]) -> &mut Self {
+        self.add_bytes(field_number, encoded_msg)
+    }
+
+    #[inline]
+    fn add_raw_record(&mut self, record: &[u8]) -> &mut Self {
+        self.buf.encoder.buf.extend_from_slice(record);
+        self
+    }
+
+    #[inline]
+    fn start_msg(&mut self, field_number: FieldNumber) -> &mut Self {
+        self.start_len_area(field_number, LenAreaType::Msg)
+    }
+
+    #[inline]
+    fn end_msg(&mut self, field_number: FieldNumber) -> &mut Self {
+        self.end_len_area(field_number, LenAreaType::Msg)
+    }
+
+    #[inline]
+    fn start_packed<'b>(&'b mut self, field_number: FieldNumber) -> SinglePassPackedScribe<'a, 'b> {
+        SinglePassPackedScribe {
+            parent: self.start_len_area(field_number, LenAreaType::Packed),
+        }
+    }
+
+    #[inline]
+    fn end_packed(&mut self, field_number: FieldNumber) -> &mut Self {
+        self.end_len_area(field_number, LenAreaType::Packed)
+    }
+
+    fn end(mut self) -> Self::End {
+        if let Some(open) = self.buf.stack.last() {
+            let err = EncodeError::Unclosed {
+                field_number: open.field_number,
+                t: open.t,
+            };
+            self.record(err);
+        }
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+        if self.buf.compact && !self.buf.slots.is_empty() {
+            self.compact();
+        }
+        let len = self.buf.encoder.buf.len() as u64;
+        if len > self.buf.max_len {
+            return Err(EncodeError::LenTooBig {
+                field_number: None,
+                len,
+                max_len: self.buf.max_len,
+            });
+        }
+        Ok(&self.buf.encoder.buf)
+    }
+}
+
+/// A [`core::fmt::Write`] implementation that writes UTF-8 bytes directly
+/// into a [`Vec<u8>`].
+pub(crate) struct FmtVecWriter<'a>(pub(crate) &'a mut Vec<u8>);
+
+impl core::fmt::Write for FmtVecWriter<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.0.extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{builders::*, decode::MsgDecoder, FieldNumber};
+
+    const MSG: FieldNumber = FieldNumber(1);
+    const STR: FieldNumber = FieldNumber(2);
+    const PACKED: FieldNumber = FieldNumber(3);
+
+    /// Serializes nested sub-messages with strings of the given lengths in
+    /// each level.
+    fn ser<S: MsgScribe>(depth: usize, str_lens: &[usize], s: &mut S) {
+        for (i, len) in str_lens.iter().enumerate() {
+            s.add_string(STR, &"x".repeat(*len));
+            if i == 1 {
+                s.add_display_str(STR, &len).unwrap();
+            }
+        }
+        s.start_packed(PACKED).add_uint32(300).add_sint32(-1);
+        s.end_packed(PACKED);
+        if depth > 0 {
+            s.start_msg(MSG);
+            ser(depth - 1, str_lens, s);
+            s.end_msg(MSG);
+            s.start_msg(MSG);
+            s.end_msg(MSG);
+        }
+    }
+
+    fn two_pass(depth: usize, str_lens: &[usize]) -> Vec<u8> {
+        let mut builder = MsgBuilder::new();
+        let mut len = builder.start(None);
+        ser(depth, str_lens, &mut len);
+        let mut s = len.end();
+        ser(depth, str_lens, &mut s);
+        s.end().to_vec()
+    }
+
+    fn single_pass(depth: usize, str_lens: &[usize], compact: bool) -> Vec<u8> {
+        let mut builder = SinglePassBuilder::new();
+        builder.set_compact(compact);
+        let mut s = builder.start();
+        ser(depth, str_lens, &mut s);
+        s.end().unwrap().to_vec()
+    }
+
+    /// Decodes the message recursively into (depth, field number, value) tuples.
+    fn records(data: &[u8], depth: usize, result: &mut Vec<(usize, FieldNumber, String)>) {
+        for record in MsgDecoder::new(data) {
+            let record = record.unwrap();
+            if record.field_number == MSG {
+                result.push((depth, MSG, String::new()));
+                records(record.value.try_as_len().unwrap().data, depth + 1, result);
+            } else {
+                result.push((depth, record.field_number, format!("{:?}", record.value)));
+            }
+        }
+    }
+
+    #[test]
+    fn same_as_two_pass() {
+        let str_lens = [
+            &[][..],
+            &[0],
+            &[1, 100],
+            &[120, 7],
+            &[127, 128],
+            &[16_383, 0, 1],
+            &[16_384, 16_300],
+        ];
+        for str_lens in str_lens {
+            for depth in 0..4 {
+                let expected = two_pass(depth, str_lens);
+
+                // compacted output is identical
+                assert_eq!(expected, single_pass(depth, str_lens, true));
+
+                // non compacted output has the same content
+                let bytes = single_pass(depth, str_lens, false);
+                let (mut a, mut b) = (Vec::new(), Vec::new());
+                records(&expected, 0, &mut a);
+                records(&bytes, 0, &mut b);
+                assert_eq!(a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn error() {
+        use LenAreaType::*;
+
+        fn check(
+            builder: &mut SinglePassBuilder,
+            f: impl FnOnce(&mut SinglePassScribe<'_>),
+        ) -> Result<Vec<u8>, EncodeError> {
+            let mut s = builder.start();
+            f(&mut s);
+            s.end().map(|bytes| bytes.to_vec())
+        }
+
+        let mut builder = SinglePassBuilder::new();
+        assert_eq!(
+            Err(EncodeError::EndWithoutStart {
+                field_number: MSG,
+                t: Msg
+            }),
+            check(&mut builder, |s| {
+                s.end_msg(MSG).end_msg(MSG);
+            })
+        );
+        assert_eq!(
+            Err(EncodeError::EndMismatch {
+                expected_field_number: MSG,
+                expected_t: Msg,
+                actual_field_number: MSG,
+                actual_t: Packed,
+            }),
+            check(&mut builder, |s| {
+                s.start_msg(MSG).end_packed(MSG);
+            })
+        );
+        assert_eq!(
+            Err(EncodeError::Unclosed {
+                field_number: STR,
+                t: Msg
+            }),
+            check(&mut builder, |s| {
+                s.start_msg(MSG).start_msg(STR);
+            })
+        );
+
+        builder.set_max_len(3);
+        assert_eq!(
+            Err(EncodeError::LenTooBig {
+                field_number: Some(STR),
+                len: 4,
+                max_len: 3
+            }),
+            check(&mut builder, |s| {
+                s.add_string(STR, "abcd");
+            })
+        );
+        assert_eq!(
+            Err(EncodeError::LenTooBig {
+                field_number: Some(STR),
+                len: 4,
+                max_len: 3
+            }),
+            check(&mut builder, |s| {
+                s.add_display_str(STR, &"abcd").unwrap();
+            })
+        );
+        assert_eq!(
+            Err(EncodeError::LenTooBig {
+                field_number: Some(MSG),
+                len: 4,
+                max_len: 3
+            }),
+            check(&mut builder, |s| {
+                s.start_msg(MSG).add_uint32(STR, 1 << 14).end_msg(MSG);
+            })
+        );
+        assert_eq!(
+            Err(EncodeError::LenTooBig {
+                field_number: None,
+                len: 4,
+                max_len: 3
+            }),
+            check(&mut builder, |s| {
+                s.add_uint32(STR, 1).add_uint32(STR, 1);
+            })
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use crate::{builders::*, FieldNumber};
+
+/// Fallible version of [`MsgLenBuilder`] (use [`MsgBuilder::try_start`] to
+/// create).
+///
+/// Instead of panicking on mismatched `start_*` & `end_*` calls the first
+/// error is recorded & returned by [`end`](MsgScribe::end). Calls after an
+/// error are still accepted, but their result is discarded.
+#[derive(Debug, Eq, PartialEq, Hash)]
+pub struct TryMsgLenBuilder<'a> {
+    builder: MsgLenBuilder<'a>,
+    /// First detected error.
+    error: Option<EncodeError>,
+}
+
+impl<'a> TryMsgLenBuilder<'a> {
+    pub(crate) fn new(builder: MsgLenBuilder<'a>) -> TryMsgLenBuilder<'a> {
+        TryMsgLenBuilder {
+            builder,
+            error: None,
+        }
+    }
+
+    /// Returns the first detected error (if any).
+    pub fn error(&self) -> Option<&EncodeError> {
+        self.error.as_ref()
+    }
+
+    /// Records the error if it is the first one.
+    fn record<T>(&mut self, result: Result<T, EncodeError>) {
+        if let Err(err) = result {
+            if self.error.is_none() {
+                self.error = Some(err);
+            }
+        }
+    }
+}
+
+impl<'a> MsgScribe for TryMsgLenBuilder<'a> {
+    type Packed<'b>
+        = MsgLenPackedScribe<'a, 'b>
+    where
+        Self: 'b;
+    type End = Result<TryMsgSerBuilder<'a>, EncodeError>;
+
+    #[inline]
+    fn add_int32(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.builder.add_int32(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_int64(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
+        self.builder.add_int64(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_uint32(&mut self, field_number: FieldNumber, value: u32) -> &mut Self {
+        self.builder.add_uint32(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_uint64(&mut self, field_number: FieldNumber, value: u64) -> &mut Self {
+        self.builder.add_uint64(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_bool(&mut self, field_number: FieldNumber, value: bool) -> &mut Self {
+        self.builder.add_bool(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_enum(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.builder.add_enum(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_sint32(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.builder.add_sint32(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_sint64(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
+        self.builder.add_sint64(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_fixed32(&mut self, field_number: FieldNumber, value: u32) -> &mut Self {
+        self.builder.add_fixed32(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_sfixed32(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.builder.add_sfixed32(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_float(&mut self, field_number: FieldNumber, value: f32) -> &mut Self {
+        self.builder.add_float(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_fixed64(&mut self, field_number: FieldNumber, value: u64) -> &mut Self {
+        self.builder.add_fixed64(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_sfixed64(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
+        self.builder.add_sfixed64(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_double(&mut self, field_number: FieldNumber, value: f64) -> &mut Self {
+        self.builder.add_double(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_string(&mut self, field_number: FieldNumber, value: &str) -> &mut Self {
+        self.builder.add_string(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_bytes(&mut self, field_number: FieldNumber, value: &[u8]) -> &mut Self {
+        self.builder.add_bytes(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_display_str(
+        &mut self,
+        field_number: FieldNumber,
+        value: &dyn core::fmt::Display,
+    ) -> Result<&mut Self, core::fmt::Error> {
+        self.builder.add_display_str(field_number, value)?;
+        Ok(self)
+    }
+
+    #[inline]
+    fn add_debug_str(
+        &mut self,
+        field_number: FieldNumber,
+        value: &dyn core::fmt::Debug,
+    ) -> Result<&mut Self, core::fmt::Error> {
+        self.builder.add_debug_str(field_number, value)?;
+        Ok(self)
+    }
+
+    #[inline]
+    fn add_encoded_msg(&mut self, field_number: FieldNumber, encoded_msg: &[u8]) -> &mut Self {
+        self.builder.add_encoded_msg(field_number, encoded_msg);
+        self
+    }
+
+    #[inline]
+    fn add_raw_record(&mut self, record: &[u8]) -> &mut Self {
+        self.builder.add_raw_record(record);
+        self
+    }
+
+    #[inline]
+    fn start_msg(&mut self, field_number: FieldNumber) -> &mut Self {
+        self.builder.start_msg_field(field_number);
+        self
+    }
+
+    #[inline]
+    fn end_msg(&mut self, field_number: FieldNumber) -> &mut Self {
+        let result = self.builder.try_end_msg_field(field_number).map(|_| ());
+        self.record(result);
+        self
+    }
+
+    #[inline]
+    fn start_packed<'b>(&'b mut self, field_number: FieldNumber) -> MsgLenPackedScribe<'a, 'b> {
+        self.builder.start_packed_field(field_number)
+    }
+
+    #[inline]
+    fn end_packed(&mut self, field_number: FieldNumber) -> &mut Self {
+        let result = self.builder.try_end_packed_field(field_number).map(|_| ());
+        self.record(result);
+        self
+    }
+
+    #[inline]
+    fn end(self) -> Self::End {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+        Ok(TryMsgSerBuilder::new(self.builder.try_end()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{builders::*, FieldNumber};
+
+    const F1: FieldNumber = FieldNumber(1);
+    const F2: FieldNumber = FieldNumber(2);
+
+    #[test]
+    fn ok() {
+        fn ser<S: MsgScribe>(mut s: S) -> S::End {
+            s.add_uint32(F1, 1);
+            s.start_msg(F2);
+            s.start_packed(F1).add_uint32(1).add_uint32(2);
+            s.end_packed(F1);
+            s.add_string(F2, "a");
+            s.end_msg(F2);
+            s.end()
+        }
+        let mut builder = MsgBuilder::new();
+        let step2 = ser(builder.start(None));
+        let expected = ser(step2).to_vec();
+
+        let step2 = ser(builder.try_start(None)).unwrap();
+        assert_eq!(Ok(&expected[..]), ser(step2));
+    }
+
+    #[test]
+    fn error() {
+        use LenAreaType::*;
+
+        let mut builder = MsgBuilder::new();
+
+        // end without start
+        {
+            let mut s = builder.try_start(None);
+            s.end_msg(F1);
+            assert_eq!(
+                Some(&EncodeError::EndWithoutStart {
+                    field_number: F1,
+                    t: Msg
+                }),
+                s.error()
+            );
+            assert!(s.end().is_err());
+        }
+        // field number mismatch
+        {
+            let mut s = builder.try_start(None);
+            s.start_msg(F1).end_msg(F2);
+            assert_eq!(
+                Err(EncodeError::EndMismatch {
+                    expected_field_number: F1,
+                    expected_t: Msg,
+                    actual_field_number: F2,
+                    actual_t: Msg,
+                }),
+                s.end().map(|_| ())
+            );
+        }
+        // type mismatch (first error is kept)
+        {
+            let mut s = builder.try_start(None);
+            s.start_packed(F1);
+            s.end_msg(F1).end_msg(F2);
+            assert_eq!(
+                Err(EncodeError::EndMismatch {
+                    expected_field_number: F1,
+                    expected_t: Packed,
+                    actual_field_number: F1,
+                    actual_t: Msg,
+                }),
+                s.end().map(|_| ())
+            );
+        }
+        // unclosed
+        {
+            let mut s = builder.try_start(None);
+            s.start_msg(F1).start_msg(F2).end_msg(F2);
+            assert_eq!(
+                Err(EncodeError::Unclosed {
+                    field_number: F1,
+                    t: Msg
+                }),
+                s.end().map(|_| ())
+            );
+        }
+    }
+}
@@ -0,0 +1,347 @@
+use crate::{builders::*, FieldNumber};
+
+/// Fallible version of [`MsgSerBuilder`] (returned by the
+/// [`end`](MsgScribe::end) of [`TryMsgLenBuilder`]).
+///
+/// Instead of panicking if the sub-messages & packed fields do not match
+/// the length pass the first error is recorded & returned by
+/// [`end`](MsgScribe::end). Additionally it is checked that the
+/// serialized content of every sub-message & packed field has the length
+/// determined in the length pass. Calls after an error are still accepted,
+/// but their result is discarded.
+#[derive(Debug, Eq, PartialEq, Hash)]
+pub struct TryMsgSerBuilder<'a> {
+    builder: MsgSerBuilder<'a>,
+    /// First detected error.
+    error: Option<EncodeError>,
+}
+
+impl<'a> TryMsgSerBuilder<'a> {
+    pub(crate) fn new(builder: MsgSerBuilder<'a>) -> TryMsgSerBuilder<'a> {
+        TryMsgSerBuilder {
+            builder,
+            error: None,
+        }
+    }
+
+    /// Returns the first detected error (if any).
+    pub fn error(&self) -> Option<&EncodeError> {
+        self.error.as_ref()
+    }
+
+    /// Records the error if it is the first one.
+    fn record<T>(&mut self, result: Result<T, EncodeError>) {
+        if let Err(err) = result {
+            if self.error.is_none() {
+                self.error = Some(err);
+            }
+        }
+    }
+}
+
+impl<'a> MsgScribe for TryMsgSerBuilder<'a> {
+    type Packed<'b>
+        = MsgSerPackedScribe<'a, 'b>
+    where
+        Self: 'b;
+    type End = Result<&'a [u8], EncodeError>;
+
+    #[inline]
+    fn add_int32(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.builder.add_int32(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_int64(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
+        self.builder.add_int64(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_uint32(&mut self, field_number: FieldNumber, value: u32) -> &mut Self {
+        self.builder.add_uint32(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_uint64(&mut self, field_number: FieldNumber, value: u64) -> &mut Self {
+        self.builder.add_uint64(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_bool(&mut self, field_number: FieldNumber, value: bool) -> &mut Self {
+        self.builder.add_bool(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_enum(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.builder.add_enum(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_sint32(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.builder.add_sint32(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_sint64(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
+        self.builder.add_sint64(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_fixed32(&mut self, field_number: FieldNumber, value: u32) -> &mut Self {
+        self.builder.add_fixed32(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_sfixed32(&mut self, field_number: FieldNumber, value: i32) -> &mut Self {
+        self.builder.add_sfixed32(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_float(&mut self, field_number: FieldNumber, value: f32) -> &mut Self {
+        self.builder.add_float(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_fixed64(&mut self, field_number: FieldNumber, value: u64) -> &mut Self {
+        self.builder.add_fixed64(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_sfixed64(&mut self, field_number: FieldNumber, value: i64) -> &mut Self {
+        self.builder.add_sfixed64(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_double(&mut self, field_number: FieldNumber, value: f64) -> &mut Self {
+        self.builder.add_double(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_string(&mut self, field_number: FieldNumber, value: &str) -> &mut Self {
+        self.builder.add_string(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_bytes(&mut self, field_number: FieldNumber, value: &[u8]) -> &mut Self {
+        self.builder.add_bytes(field_number, value);
+        self
+    }
+
+    #[inline]
+    fn add_display_str(
+        &mut self,
+        field_number: FieldNumber,
+        value: &dyn core::fmt::Display,
+    ) -> Result<&mut Self, core::fmt::Error> {
+        self.builder.add_display_str(field_number, value)?;
+        Ok(self)
+    }
+
+    #[inline]
+    fn add_debug_str(
+        &mut self,
+        field_number: FieldNumber,
+        value: &dyn core::fmt::Debug,
+    ) -> Result<&mut Self, core::fmt::Error> {
+        self.builder.add_debug_str(field_number, value)?;
+        Ok(self)
+    }
+
+    #[inline]
+    fn add_encoded_msg(&mut self, field_number: FieldNumber, encoded_msg: &[u8]) -> &mut Self {
+        self.builder.add_encoded_msg(field_number, encoded_msg);
+        self
+    }
+
+    #[inline]
+    fn add_raw_record(&mut self, record: &[u8]) -> &mut Self {
+        self.builder.add_raw_record(record);
+        self
+    }
+
+    #[inline]
+    fn start_msg(&mut self, field_number: FieldNumber) -> &mut Self {
+        let result = self.builder.try_start_msg_field(field_number).map(|_| ());
+        self.record(result);
+        self
+    }
+
+    #[inline]
+    fn end_msg(&mut self, field_number: FieldNumber) -> &mut Self {
+        let result = self.builder.try_end_msg_field(field_number).map(|_| ());
+        self.record(result);
+        self
+    }
+
+    #[inline]
+    fn start_packed<'b>(&'b mut self, field_number: FieldNumber) -> MsgSerPackedScribe<'a, 'b> {
+        let result = self
+            .builder
+            .try_start_len_area(field_number, LenAreaType::Packed)
+            .map(|_| ());
+        self.record(result);
+        // on errors the packed values are still written (the result is
+        // discarded anyways)
+        MsgSerPackedScribe {
+            parent: &mut self.builder,
+        }
+    }
+
+    #[inline]
+    fn end_packed(&mut self, field_number: FieldNumber) -> &mut Self {
+        let result = self.builder.try_end_packed_field(field_number).map(|_| ());
+        self.record(result);
+        self
+    }
+
+    #[inline]
+    fn end(self) -> Self::End {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+        self.builder.try_end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{builders::*, FieldNumber};
+
+    const F1: FieldNumber = FieldNumber(1);
+    const F2: FieldNumber = FieldNumber(2);
+
+    /// Runs the length pass with `len_pass` & the serialization pass with
+    /// `ser_pass`.
+    fn check(
+        len_pass: fn(&mut TryMsgLenBuilder<'_>),
+        ser_pass: fn(&mut TryMsgSerBuilder<'_>),
+    ) -> Result<Vec<u8>, EncodeError> {
+        let mut builder = MsgBuilder::new();
+        let mut s = builder.try_start(None);
+        len_pass(&mut s);
+        let mut s = s.end()?;
+        ser_pass(&mut s);
+        s.end().map(|bytes| bytes.to_vec())
+    }
+
+    #[test]
+    fn ok() {
+        assert_eq!(
+            Ok(vec![0x0a, 0x02, 0x12, 0x00]),
+            check(
+                |s| {
+                    s.start_msg(F1).start_msg(F2).end_msg(F2).end_msg(F1);
+                },
+                |s| {
+                    s.start_msg(F1).start_msg(F2).end_msg(F2).end_msg(F1);
+                },
+            )
+        );
+    }
+
+    #[test]
+    fn error() {
+        // different field number than in the length pass
+        assert_eq!(
+            Err(EncodeError::LenPassFieldMismatch {
+                expected: Some(F1),
+                actual: F2
+            }),
+            check(
+                |s| {
+                    s.start_msg(F1).end_msg(F1);
+                },
+                |s| {
+                    s.start_msg(F2).end_msg(F2);
+                },
+            )
+        );
+        // more sub-messages than in the length pass
+        assert_eq!(
+            Err(EncodeError::LenPassFieldMismatch {
+                expected: None,
+                actual: F1
+            }),
+            check(
+                |_| {},
+                |s| {
+                    s.start_packed(F1).add_uint32(1);
+                    s.end_packed(F1);
+                },
+            )
+        );
+        // fewer sub-messages than in the length pass
+        assert_eq!(
+            Err(EncodeError::LenPassFieldMissing { expected: F1 }),
+            check(
+                |s| {
+                    s.start_msg(F1).end_msg(F1);
+                },
+                |_| {},
+            )
+        );
+        // different content length
+        assert_eq!(
+            Err(EncodeError::LenPassLenMismatch {
+                field_number: F1,
+                expected: 2,
+                actual: 3
+            }),
+            check(
+                |s| {
+                    s.start_msg(F1).add_string(F2, "").end_msg(F1);
+                },
+                |s| {
+                    s.start_msg(F1).add_string(F2, "a").end_msg(F1);
+                },
+            )
+        );
+        // mismatched end call
+        assert_eq!(
+            Err(EncodeError::EndMismatch {
+                expected_field_number: F1,
+                expected_t: LenAreaType::Msg,
+                actual_field_number: F1,
+                actual_t: LenAreaType::Packed,
+            }),
+            check(
+                |s| {
+                    s.start_msg(F1).end_msg(F1);
+                },
+                |s| {
+                    s.start_msg(F1).end_packed(F1);
+                },
+            )
+        );
+        // missing end call
+        assert_eq!(
+            Err(EncodeError::Unclosed {
+                field_number: F1,
+                t: LenAreaType::Msg,
+            }),
+            check(
+                |s| {
+                    s.start_msg(F1).end_msg(F1);
+                },
+                |s| {
+                    s.start_msg(F1);
+                },
+            )
+        );
+    }
+}
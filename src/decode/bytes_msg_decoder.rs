@@ -0,0 +1,120 @@
+use crate::{decode::*, wire::*};
+use bytes::{Buf, Bytes};
+
+/// Decoder for messages stored in [`Bytes`].
+///
+/// Same as [`MsgDecoder`] but the data of LEN records is returned as
+/// [`Bytes`] slices of the decoded data instead of references. The slices
+/// share the memory of the decoded data (no copying), but are not bound
+/// to the lifetime of a borrow, so they can e.g. be moved to other tasks.
+///
+/// ```
+/// use bytes::Bytes;
+/// use protobin::{decode::*, wire::*};
+///
+/// // field 1: string "ab", field 2: uint32 3
+/// let data = Bytes::from_static(&[0x0a, 0x02, b'a', b'b', 0x10, 0x03]);
+///
+/// let mut decoder = BytesMsgDecoder::new(data.clone());
+/// let record = decoder.next().unwrap().unwrap();
+/// assert_eq!(1, record.field_number.value());
+/// let name: Bytes = record.value.try_into_len().unwrap();
+/// assert_eq!(&b"ab"[..], &name[..]);
+///
+/// let record = decoder.next().unwrap().unwrap();
+/// assert_eq!(Ok(3), record.value.as_ref().try_as_uint32());
+/// assert!(decoder.next().is_none());
+/// ```
+///
+/// Sub-messages can be decoded by creating a new [`BytesMsgDecoder`] with
+/// the data of the LEN value.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct BytesMsgDecoder {
+    /// Data that is not yet decoded.
+    data: Bytes,
+}
+
+impl BytesMsgDecoder {
+    /// Setup a decoder for the records of the given message.
+    pub fn new(data: Bytes) -> BytesMsgDecoder {
+        BytesMsgDecoder { data }
+    }
+
+    /// Returns the data that is not yet decoded.
+    pub fn remaining(&self) -> &Bytes {
+        &self.data
+    }
+}
+
+impl Iterator for BytesMsgDecoder {
+    type Item = Result<BytesMsgRecord, DecodeError>;
+
+    /// Returns the next message record until an error is encountered or
+    /// no more data is present.
+    ///
+    /// In case an error is encountered the error is returned and in the
+    /// following call `None`.
+    fn next(&mut self) -> Option<Result<BytesMsgRecord, DecodeError>> {
+        let mut decoder = MsgDecoder::new(&self.data);
+        let result = match decoder.next()? {
+            Ok(record) => Ok(BytesMsgRecord {
+                field_number: record.field_number,
+                value: WireValueBytes::from_ref(&self.data, record.value),
+            }),
+            Err(err) => Err(err),
+        };
+        // MsgDecoder clears the data after an error, so the error is only
+        // returned once
+        let consumed = self.data.len() - decoder.wire_decoder.data.len();
+        self.data.advance(consumed);
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{builders::*, decode::*, wire::*, *};
+    use bytes::Bytes;
+
+    #[test]
+    fn same_as_msg_decoder() {
+        fn ser<S: MsgScribe>(s: &mut S) {
+            s.add_uint64(FieldNumber(1), 1 << 40)
+                .add_fixed64(FieldNumber(2), 3)
+                .add_string(FieldNumber(3), "abc")
+                .add_fixed32(FieldNumber(4), 5)
+                .add_bytes(FieldNumber(5), &[]);
+        }
+        let mut builder = MsgBuilder::new();
+        let mut len = builder.start(None);
+        ser(&mut len);
+        let mut s = len.end();
+        ser(&mut s);
+        let data = Bytes::copy_from_slice(s.end());
+
+        let expected: Vec<_> = MsgDecoder::new(&data).collect();
+        let actual: Vec<_> = BytesMsgDecoder::new(data.clone()).collect();
+        assert_eq!(expected.len(), actual.len());
+        for (e, a) in expected.iter().zip(actual.iter()) {
+            assert_eq!(e.as_ref().unwrap(), &a.as_ref().unwrap().as_ref());
+        }
+
+        // LEN values share the memory of the data
+        let WireValueBytes::Len(value) = &actual[2].as_ref().unwrap().value else {
+            panic!("expected LEN value");
+        };
+        let offset = value.as_ptr() as usize - data.as_ptr() as usize;
+        assert_eq!(&data[offset..offset + 3], b"abc");
+    }
+
+    #[test]
+    fn error() {
+        // truncated LEN value followed by valid data
+        let data = Bytes::from_static(&[0x08, 0x01, 0x12, 0x05, b'a']);
+        let mut decoder = BytesMsgDecoder::new(data);
+        assert!(decoder.next().unwrap().is_ok());
+        assert!(decoder.next().unwrap().is_err());
+        assert!(decoder.next().is_none());
+        assert!(decoder.remaining().is_empty());
+    }
+}
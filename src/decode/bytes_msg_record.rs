@@ -0,0 +1,19 @@
+use crate::{decode::*, wire::*, *};
+
+/// Message record decoded by the [`BytesMsgDecoder`] (LEN values are
+/// [`bytes::Bytes`] slices of the decoded data).
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct BytesMsgRecord {
+    pub field_number: FieldNumber,
+    pub value: WireValueBytes,
+}
+
+impl BytesMsgRecord {
+    /// Returns a [`MsgRecordRef`] referencing the record.
+    pub fn as_ref(&self) -> MsgRecordRef<'_> {
+        MsgRecordRef {
+            field_number: self.field_number,
+            value: self.value.as_ref(),
+        }
+    }
+}
@@ -1,6 +1,14 @@
+use crate::wire::WireType;
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum DecodeError {
     Len,
     UnexpectedContinuationBit,
     UnknownWireType(u32),
+    /// A record has a different wire type than the one expected
+    /// (e.g. a non LEN record for a map field).
+    UnexpectedWireType {
+        expected: WireType,
+        actual: WireType,
+    },
 }
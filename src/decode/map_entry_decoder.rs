@@ -0,0 +1,163 @@
+use crate::{decode::*, wire::*, *};
+
+/// Iterator over the entries of a protobuf `map<K, V>` field in a message.
+///
+/// All records of the message with other field numbers are skipped.
+///
+/// ```
+/// use protobin::{decode::*, FieldNumber};
+///
+/// // map<string, int32> counts = 3; containing {"a": 1, "b": 2}
+/// let data = [0x1a, 0x05, 0x0a, 0x01, b'a', 0x10, 0x01, 0x1a, 0x05, 0x0a, 0x01, b'b', 0x10, 0x02];
+///
+/// let field = FieldNumber::try_from(3).unwrap();
+/// for entry in MapEntryDecoder::new(MsgDecoder::new(&data), field) {
+///     let entry = entry.unwrap();
+///     let key = entry.key.unwrap();
+///     let value = entry.value.unwrap();
+///     println!("{:?} => {:?}", key, value.try_as_int32());
+/// }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct MapEntryDecoder<'a> {
+    pub msg_decoder: MsgDecoder<'a>,
+    pub field_number: FieldNumber,
+}
+
+impl<'a> MapEntryDecoder<'a> {
+    /// Setup a decoder for the entries of the map field with the given
+    /// field number in the records of `msg_decoder`.
+    pub fn new(msg_decoder: MsgDecoder<'a>, field_number: FieldNumber) -> MapEntryDecoder<'a> {
+        MapEntryDecoder {
+            msg_decoder,
+            field_number,
+        }
+    }
+}
+
+impl<'a> Iterator for MapEntryDecoder<'a> {
+    type Item = Result<MapEntryRef<'a>, DecodeError>;
+
+    /// Returns the next map entry until an error is encountered or no
+    /// more data is present.
+    ///
+    /// In case an error is encountered the error is returned and in the
+    /// following call `None`.
+    fn next(&mut self) -> Option<Result<MapEntryRef<'a>, DecodeError>> {
+        for record in self.msg_decoder.by_ref() {
+            let result = match record {
+                Ok(record) if record.field_number != self.field_number => continue,
+                Ok(MsgRecordRef {
+                    value: WireValueRef::Len(len_ref),
+                    ..
+                }) => len_ref.try_as_map_entry(),
+                Ok(record) => Err(DecodeError::UnexpectedWireType {
+                    expected: WireType::Len,
+                    actual: record.value.write_type(),
+                }),
+                Err(err) => Err(err),
+            };
+            if result.is_err() {
+                // stop iterating after the first error
+                self.msg_decoder.wire_decoder.data = &[];
+            }
+            return Some(result);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "alloc")]
+    use crate::builders::*;
+    use crate::{decode::*, wire::*, *};
+
+    #[cfg(feature = "alloc")]
+    fn ser<S: MsgScribe>(mut s: S) -> S::End {
+        s.add_uint32(FieldNumber(1), 10);
+        s.add_map(
+            FieldNumber(2),
+            [("a", 1), ("bb", -2)],
+            S::add_string,
+            S::add_sint32,
+        );
+        s.add_uint32(FieldNumber(3), 30);
+        s.add_map_entry(FieldNumber(2), "ccc", 3, S::add_string, S::add_sint32);
+        // entry without key & value
+        s.start_msg(FieldNumber(2));
+        s.end_msg(FieldNumber(2));
+        s.end()
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn map_entries() {
+        let mut builder = MsgBuilder::new();
+        let step2 = ser(builder.start(None));
+        let data = ser(step2);
+
+        let entries: Vec<_> = MapEntryDecoder::new(MsgDecoder::new(data), FieldNumber(2))
+            .map(|e| e.unwrap())
+            .collect();
+        let as_tuple = |e: &MapEntryRef<'_>| {
+            (
+                e.key.map(|k| {
+                    let WireValueRef::Len(k) = k else {
+                        panic!("expected LEN wire type");
+                    };
+                    k.try_as_string().unwrap().to_owned()
+                }),
+                e.value.map(|v| v.try_as_sint32().unwrap()),
+            )
+        };
+        assert_eq!(
+            vec![
+                (Some("a".to_owned()), Some(1)),
+                (Some("bb".to_owned()), Some(-2)),
+                (Some("ccc".to_owned()), Some(3)),
+                (None, None),
+            ],
+            entries.iter().map(as_tuple).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn map_entry_last_wins() {
+        // key = 1, value = 2, key = 3, unknown field 3
+        let data = [0x08, 0x01, 0x10, 0x02, 0x08, 0x03, 0x18, 0x04];
+        let entry = WireLenRef { data: &data }.try_as_map_entry().unwrap();
+        assert_eq!(
+            Some(WireValueRef::VarInt(WireVarInt::from_raw(3))),
+            entry.key
+        );
+        assert_eq!(
+            Some(WireValueRef::VarInt(WireVarInt::from_raw(2))),
+            entry.value
+        );
+    }
+
+    #[test]
+    fn map_entry_errors() {
+        // non LEN record with the map field number
+        {
+            let data = [0x10, 0x01, 0x12, 0x00];
+            let mut decoder = MapEntryDecoder::new(MsgDecoder::new(&data), FieldNumber(2));
+            assert_eq!(
+                Some(Err(DecodeError::UnexpectedWireType {
+                    expected: WireType::Len,
+                    actual: WireType::VarInt
+                })),
+                decoder.next()
+            );
+            assert_eq!(None, decoder.next());
+        }
+        // broken entry content
+        {
+            let data = [0x12, 0x01, 0x08];
+            let mut decoder = MapEntryDecoder::new(MsgDecoder::new(&data), FieldNumber(2));
+            assert_eq!(Some(Err(DecodeError::Len)), decoder.next());
+            assert_eq!(None, decoder.next());
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use crate::{decode::*, wire::*, *};
+
+/// Decoded entry of a protobuf `map<K, V>` field.
+///
+/// `key` & `value` are `None` if the entry did not contain them (in
+/// which case the default value of the key or value type applies).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct MapEntryRef<'a> {
+    pub key: Option<WireValueRef<'a>>,
+    pub value: Option<WireValueRef<'a>>,
+}
+
+impl<'a> MapEntryRef<'a> {
+    /// Decodes a map entry from the data of a LEN record.
+    ///
+    /// Unknown fields in the entry are ignored and if the key or value is
+    /// present multiple times the last one wins.
+    pub fn decode(data: &'a [u8]) -> Result<MapEntryRef<'a>, DecodeError> {
+        let mut result = MapEntryRef {
+            key: None,
+            value: None,
+        };
+        for record in MsgDecoder::new(data) {
+            let record = record?;
+            if record.field_number == FieldNumber::MAP_KEY {
+                result.key = Some(record.value);
+            } else if record.field_number == FieldNumber::MAP_VALUE {
+                result.value = Some(record.value);
+            }
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,225 @@
+use crate::{builders::*, decode::*, wire::*, *};
+use alloc::vec::Vec;
+
+/// Owned & editable message consisting of the decoded records (in the
+/// order they were decoded).
+///
+/// In contrast to [`MsgDecoder`] & [`MsgRecordRef`] a [`Message`] does not
+/// reference the decoded data, so it can be kept or sent to other threads
+/// after the decoded buffer was reused. Sub-messages are stored as LEN
+/// values & can be decoded into their own [`Message`] if needed.
+///
+/// A message can be re-serialized via any [`MsgScribe`] (see
+/// [`Message::ser`] & [`Message::encode`]). Records are encoded from
+/// their values, so tags, VARINTs & lengths are always written in their
+/// minimal form, even if the decoded data used a longer encoding.
+///
+/// ```
+/// use protobin::{decode::*, wire::*, FieldNumber};
+///
+/// // field 1: uint32 150, field 2: string "ab"
+/// let data = [0x08, 0x96, 0x01, 0x12, 0x02, b'a', b'b'];
+/// let mut msg = Message::decode(&data).unwrap();
+/// assert_eq!(
+///     Ok(150),
+///     msg.last(FieldNumber::try_from(1).unwrap()).unwrap().as_ref().try_as_uint32()
+/// );
+///
+/// // replace field 1 with the value 1
+/// let f1 = FieldNumber::try_from(1).unwrap();
+/// msg.remove(f1);
+/// msg.push(f1, WireValue::VarInt(WireVarInt::from_uint32(1)));
+/// assert_eq!(msg.encode(), &[0x12, 0x02, b'a', b'b', 0x08, 0x01]);
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Message {
+    pub records: Vec<MsgRecord>,
+}
+
+impl Message {
+    /// Setup a new empty [`Message`].
+    pub fn new() -> Message {
+        Default::default()
+    }
+
+    /// Decodes all records of a message (LEN values are copied).
+    pub fn decode(data: &[u8]) -> Result<Message, DecodeError> {
+        let records = MsgDecoder::new(data)
+            .map(|record| record.map(MsgRecord::from))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Message { records })
+    }
+
+    /// Adds a record at the end of the message.
+    pub fn push(&mut self, field_number: FieldNumber, value: WireValue) -> &mut Self {
+        self.records.push(MsgRecord {
+            field_number,
+            value,
+        });
+        self
+    }
+
+    /// Returns the values of all records with the given field number (e.g.
+    /// the values of a repeated field).
+    pub fn get(&self, field_number: FieldNumber) -> impl Iterator<Item = &WireValue> + '_ {
+        self.records
+            .iter()
+            .filter(move |r| r.field_number == field_number)
+            .map(|r| &r.value)
+    }
+
+    /// Returns the value of the last record with the given field number
+    /// (for non repeated fields the last value wins).
+    pub fn last(&self, field_number: FieldNumber) -> Option<&WireValue> {
+        self.get(field_number).last()
+    }
+
+    /// Removes all records with the given field number & returns the
+    /// number of removed records.
+    pub fn remove(&mut self, field_number: FieldNumber) -> usize {
+        let len = self.records.len();
+        self.records.retain(|r| r.field_number != field_number);
+        len - self.records.len()
+    }
+
+    /// Serializes all records (has to be called for both passes of the
+    /// [`MsgBuilder`]).
+    pub fn ser<S: MsgScribe>(&self, s: &mut S) {
+        for record in &self.records {
+            let field_number = record.field_number;
+            match &record.value {
+                WireValue::VarInt(v) => {
+                    s.add_uint64(field_number, v.as_uint64());
+                }
+                WireValue::I64(v) => {
+                    s.add_fixed64(field_number, v.0);
+                }
+                WireValue::Len(data) => {
+                    s.add_bytes(field_number, data);
+                }
+                WireValue::SGroup => {
+                    s.add_raw_record(VarIntBytes::tag(field_number, 3).as_slice());
+                }
+                WireValue::EGroup => {
+                    s.add_raw_record(VarIntBytes::tag(field_number, 4).as_slice());
+                }
+                WireValue::I32(v) => {
+                    s.add_fixed32(field_number, v.0);
+                }
+            }
+        }
+    }
+
+    /// Encodes the message.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut builder = MsgBuilder::new();
+        let mut s = builder.start(None);
+        self.ser(&mut s);
+        let mut s = s.end();
+        self.ser(&mut s);
+        s.end().to_vec()
+    }
+}
+
+impl<'a> TryFrom<MsgDecoder<'a>> for Message {
+    type Error = DecodeError;
+
+    /// Decodes all remaining records of the decoder.
+    fn try_from(decoder: MsgDecoder<'a>) -> Result<Self, Self::Error> {
+        let records = decoder
+            .map(|record| record.map(MsgRecord::from))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Message { records })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{builders::*, decode::*, wire::*, *};
+
+    const F1: FieldNumber = FieldNumber(1);
+    const F2: FieldNumber = FieldNumber(2);
+    const BIG: FieldNumber = FieldNumber(FieldNumber::MAX_ALLOWED_U32);
+
+    #[test]
+    fn decode_encode() {
+        fn ser<S: MsgScribe>(s: &mut S) {
+            s.add_int32(F1, -1)
+                .add_fixed64(F2, 2)
+                .add_string(BIG, "abc")
+                .add_float(F1, 1.5);
+            s.start_msg(F2);
+            s.add_uint32(F1, 300);
+            s.end_msg(F2);
+            // group (field 2 with one field 1 inside)
+            s.add_raw_record(&[0x13, 0x08, 0x01, 0x14]);
+        }
+        let mut builder = MsgBuilder::new();
+        let mut s = builder.start(None);
+        ser(&mut s);
+        let mut s = s.end();
+        ser(&mut s);
+        let data = s.end().to_vec();
+
+        let msg = Message::decode(&data).unwrap();
+        assert_eq!(
+            msg.records.iter().map(|r| r.as_ref()).collect::<Vec<_>>(),
+            MsgDecoder::new(&data)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        );
+        assert_eq!(msg, Message::try_from(MsgDecoder::new(&data)).unwrap());
+
+        // re-encoding results in the same bytes
+        assert_eq!(data, msg.encode());
+
+        // errors are forwarded
+        assert_eq!(Err(DecodeError::Len), Message::decode(&[0x0a, 0x02, 0x01]));
+    }
+
+    #[test]
+    fn edit() {
+        let mut msg = Message::new();
+        msg.push(F1, WireValue::VarInt(WireVarInt::from_uint32(1)))
+            .push(F2, WireValue::Len(b"a".to_vec()))
+            .push(F1, WireValue::VarInt(WireVarInt::from_uint32(2)));
+
+        assert_eq!(2, msg.get(F1).count());
+        assert_eq!(
+            Some(&WireValue::VarInt(WireVarInt::from_uint32(2))),
+            msg.last(F1)
+        );
+        assert_eq!(None, msg.last(BIG));
+
+        // edit a sub-message
+        let mut sub = Message::new();
+        sub.push(F1, WireValue::I32(WireI32(7)));
+        msg.push(F2, WireValue::Len(sub.encode()));
+        assert_eq!(
+            &[0x08, 0x01, 0x12, 0x01, b'a', 0x08, 0x02, 0x12, 0x05, 0x0d, 7, 0, 0, 0][..],
+            &msg.encode()[..]
+        );
+
+        assert_eq!(2, msg.remove(F1));
+        assert_eq!(0, msg.remove(F1));
+        assert_eq!(2, msg.records.len());
+    }
+
+    #[test]
+    fn encode_minimal() {
+        // tag of field 1 & the value 1 with redundant continuation bytes
+        let data = [0x88, 0x00, 0x81, 0x80, 0x00];
+        let msg = Message::decode(&data).unwrap();
+        assert_eq!(
+            Some(&WireValue::VarInt(WireVarInt::from_uint32(1))),
+            msg.last(F1)
+        );
+        assert_eq!(&[0x08, 0x01][..], &msg.encode()[..]);
+    }
+
+    #[test]
+    fn send_sync() {
+        fn check<T: Send + Sync + 'static>() {}
+        check::<Message>();
+    }
+}
@@ -1,8 +1,45 @@
+#[cfg(feature = "bytes")]
+mod bytes_msg_decoder;
+#[cfg(feature = "bytes")]
+pub use bytes_msg_decoder::*;
+
+#[cfg(feature = "bytes")]
+mod bytes_msg_record;
+#[cfg(feature = "bytes")]
+pub use bytes_msg_record::*;
+
 mod decode_error;
 pub use decode_error::*;
 
+mod map_entry_decoder;
+pub use map_entry_decoder::*;
+
+mod map_entry_ref;
+pub use map_entry_ref::*;
+
+#[cfg(feature = "alloc")]
+mod message;
+#[cfg(feature = "alloc")]
+pub use message::*;
+
 mod msg_decoder;
 pub use msg_decoder::*;
 
+#[cfg(feature = "alloc")]
+mod msg_record;
+#[cfg(feature = "alloc")]
+pub use msg_record::*;
+
 mod msg_record_ref;
 pub use msg_record_ref::*;
+
+mod msg_span_decoder;
+pub use msg_span_decoder::*;
+
+#[cfg(feature = "alloc")]
+mod msg_span_tree;
+#[cfg(feature = "alloc")]
+pub use msg_span_tree::*;
+
+mod record_span;
+pub use record_span::*;
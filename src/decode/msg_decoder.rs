@@ -12,6 +12,49 @@ impl<'a> MsgDecoder<'a> {
         }
     }
 
+    /// Returns the next message record together with the raw encoded bytes
+    /// of the record (tag, length & value).
+    ///
+    /// The raw bytes can be re-emitted unchanged via
+    /// [`MsgScribe::add_raw_record`](crate::builders::MsgScribe::add_raw_record),
+    /// e.g. to preserve fields that are unknown to the decoding code when
+    /// re-encoding a message.
+    ///
+    /// Same as for [`Iterator::next`] `None` is returned after the first
+    /// error or when no more data is present.
+    pub fn next_with_raw(&mut self) -> Option<Result<(MsgRecordRef<'a>, &'a [u8]), DecodeError>> {
+        let start = self.wire_decoder.data;
+        let record = self.next()?;
+        Some(record.map(|record| {
+            let consumed = start.len() - self.wire_decoder.data.len();
+            (record, &start[..consumed])
+        }))
+    }
+
+    /// Returns the active member of a protobuf `oneof` by decoding all
+    /// remaining records (without advancing `self`).
+    ///
+    /// `members` contains the field numbers of all members of the `oneof`.
+    /// If multiple members are present the last one wins (same as in the
+    /// protobuf reference implementation). `None` is returned if no member
+    /// is present.
+    ///
+    /// Note that a sub-message member that is present multiple times has
+    /// to be merged by the caller, only the last record is returned.
+    pub fn find_oneof(
+        &self,
+        members: &[FieldNumber],
+    ) -> Result<Option<MsgRecordRef<'a>>, DecodeError> {
+        let mut result = None;
+        for record in self.clone() {
+            let record = record?;
+            if members.contains(&record.field_number) {
+                result = Some(record);
+            }
+        }
+        Ok(result)
+    }
+
     fn next_inner(&mut self) -> Result<MsgRecordRef<'a>, DecodeError> {
         // read field number & tag
         let tag = self.wire_decoder.read_var_uint32()?;
@@ -68,3 +111,78 @@ impl<'a> Iterator for MsgDecoder<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{decode::*, wire::*, *};
+
+    #[test]
+    fn next_with_raw() {
+        // ok case
+        {
+            let data = [
+                0x08, 0x96, 0x01, // field 1: varint 150
+                0x12, 0x02, b'a', b'b', // field 2: string "ab"
+                0x1d, 0x01, 0x02, 0x03, 0x04, // field 3: fixed32
+            ];
+            let mut decoder = MsgDecoder::new(&data);
+
+            let (record, raw) = decoder.next_with_raw().unwrap().unwrap();
+            assert_eq!(FieldNumber(1), record.field_number);
+            assert_eq!(Ok(150), record.value.try_as_uint32());
+            assert_eq!(&data[..3], raw);
+
+            let (record, raw) = decoder.next_with_raw().unwrap().unwrap();
+            assert_eq!(FieldNumber(2), record.field_number);
+            assert_eq!(&data[3..7], raw);
+
+            let (record, raw) = decoder.next_with_raw().unwrap().unwrap();
+            assert_eq!(WireValueRef::I32(WireI32(0x0403_0201)), record.value);
+            assert_eq!(&data[7..], raw);
+
+            assert!(decoder.next_with_raw().is_none());
+        }
+        // error case
+        {
+            let data = [0x12, 0x02, b'a'];
+            let mut decoder = MsgDecoder::new(&data);
+            assert_eq!(Some(Err(DecodeError::Len)), decoder.next_with_raw());
+            assert!(decoder.next_with_raw().is_none());
+        }
+    }
+
+    #[test]
+    fn find_oneof() {
+        let members = [FieldNumber(2), FieldNumber(3)];
+        // no member present
+        assert_eq!(
+            Ok(None),
+            MsgDecoder::new(&[0x08, 0x01]).find_oneof(&members)
+        );
+        // single member
+        {
+            let data = [0x08, 0x01, 0x10, 0x05, 0x20, 0x01];
+            let decoder = MsgDecoder::new(&data);
+            let record = decoder.find_oneof(&members).unwrap().unwrap();
+            assert_eq!(FieldNumber(2), record.field_number);
+            assert_eq!(Ok(5), record.value.try_as_uint32());
+            // decoder was not advanced
+            assert_eq!(3, decoder.count());
+        }
+        // last member wins
+        {
+            let data = [0x18, 0x01, 0x10, 0x05, 0x18, 0x07];
+            let record = MsgDecoder::new(&data)
+                .find_oneof(&members)
+                .unwrap()
+                .unwrap();
+            assert_eq!(FieldNumber(3), record.field_number);
+            assert_eq!(Ok(7), record.value.try_as_uint32());
+        }
+        // decode error
+        assert_eq!(
+            Err(DecodeError::Len),
+            MsgDecoder::new(&[0x10, 0x05, 0x18]).find_oneof(&members)
+        );
+    }
+}
@@ -0,0 +1,27 @@
+use crate::{decode::*, wire::*, *};
+
+/// Owned version of [`MsgRecordRef`] (see [`Message`]).
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct MsgRecord {
+    pub field_number: FieldNumber,
+    pub value: WireValue,
+}
+
+impl MsgRecord {
+    /// Returns a [`MsgRecordRef`] referencing the record.
+    pub fn as_ref(&self) -> MsgRecordRef<'_> {
+        MsgRecordRef {
+            field_number: self.field_number,
+            value: self.value.as_ref(),
+        }
+    }
+}
+
+impl<'a> From<MsgRecordRef<'a>> for MsgRecord {
+    fn from(record: MsgRecordRef<'a>) -> Self {
+        MsgRecord {
+            field_number: record.field_number,
+            value: record.value.into(),
+        }
+    }
+}
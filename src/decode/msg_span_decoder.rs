@@ -0,0 +1,155 @@
+use crate::{decode::*, wire::*};
+
+/// Decoder returning the records of a message together with the byte
+/// ranges of their tag, length prefix & value (e.g. for hex dumps).
+///
+/// The ranges are relative to the start of the buffer passed to
+/// [`MsgSpanDecoder::new`], also for the records of sub-messages decoded
+/// via [`MsgSpanDecoder::sub_msg`]. Use [`MsgSpanTree`] to decode the
+/// sub-messages recursively.
+///
+/// ```
+/// use protobin::decode::*;
+///
+/// // message { uint32 a = 1; message b = 2 { string c = 1; } }
+/// let bytes = [0x08, 0x03, 0x12, 0x03, 0x0a, 0x01, b'x'];
+///
+/// let mut decoder = MsgSpanDecoder::new(&bytes);
+/// let a = decoder.next().unwrap().unwrap();
+/// assert_eq!((0..1, None, 1..2), (a.tag, a.len, a.value));
+///
+/// let b = decoder.next().unwrap().unwrap();
+/// assert_eq!((2..3, Some(3..4), 4..7), (b.tag.clone(), b.len.clone(), b.value.clone()));
+///
+/// let c = decoder.sub_msg(&b).unwrap().next().unwrap().unwrap();
+/// assert_eq!((1, 4..5, Some(5..6), 6..7), (c.depth, c.tag, c.len, c.value));
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct MsgSpanDecoder<'a> {
+    decoder: MsgDecoder<'a>,
+    /// Position of the next record in the buffer.
+    offset: usize,
+    /// Nesting depth of the decoded records.
+    depth: usize,
+}
+
+impl<'a> MsgSpanDecoder<'a> {
+    /// Setup a decoder for the records of the given message.
+    pub fn new(data: &'a [u8]) -> MsgSpanDecoder<'a> {
+        MsgSpanDecoder {
+            decoder: MsgDecoder::new(data),
+            offset: 0,
+            depth: 0,
+        }
+    }
+
+    /// Position of the next record in the buffer (on error the position of
+    /// the record that could not be decoded).
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Nesting depth of the decoded records.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns a decoder for the records of a sub-message contained in the
+    /// value of the given span (`None` if it is not a LEN record).
+    pub fn sub_msg(&self, span: &RecordSpan<'a>) -> Option<MsgSpanDecoder<'a>> {
+        if let WireValueRef::Len(l) = span.record.value {
+            Some(MsgSpanDecoder {
+                decoder: l.as_sub_msg(),
+                offset: span.value.start,
+                depth: span.depth + 1,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> Iterator for MsgSpanDecoder<'a> {
+    type Item = Result<RecordSpan<'a>, DecodeError>;
+
+    /// Returns the next record & its byte ranges until an error is
+    /// encountered or no more data is present (same as
+    /// [`MsgDecoder::next`]).
+    fn next(&mut self) -> Option<Result<RecordSpan<'a>, DecodeError>> {
+        let (record, raw) = match self.decoder.next_with_raw()? {
+            Ok(v) => v,
+            Err(err) => return Some(Err(err)),
+        };
+        let start = self.offset;
+        let end = start + raw.len();
+        self.offset = end;
+
+        // the tag is not necessarily minimally encoded, so decode it again
+        let mut tag_decoder = WireDecoder::new(raw);
+        // already successfully decoded by next_with_raw
+        let _ = tag_decoder.read_var_uint32();
+        let tag_end = end - tag_decoder.data.len();
+
+        let (len, value) = match record.value {
+            WireValueRef::Len(l) => {
+                let value_start = end - l.data.len();
+                (Some(tag_end..value_start), value_start..end)
+            }
+            _ => (None, tag_end..end),
+        };
+        Some(Ok(RecordSpan {
+            record,
+            depth: self.depth,
+            tag: start..tag_end,
+            len,
+            value,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::decode::*;
+
+    #[test]
+    fn spans() {
+        let data = [
+            0x88, 0x80, 0x00, 0xac, 0x02, // 1: 300 (tag not minimally encoded)
+            0x11, 1, 0, 0, 0, 0, 0, 0, 0, // 2: fixed64 1
+            0x1d, 2, 0, 0, 0, // 3: fixed32 2
+            0x22, 0x80, 0x00, // 4: "" (length not minimally encoded)
+            0x2b, 0x2c, // 5: empty group
+        ];
+        let spans: Vec<_> = MsgSpanDecoder::new(&data)
+            .map(|s| {
+                let s = s.unwrap();
+                (s.record.field_number.value(), s.tag, s.len, s.value)
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (1, 0..3, None, 3..5),
+                (2, 5..6, None, 6..14),
+                (3, 14..15, None, 15..19),
+                (4, 19..20, Some(20..22), 22..22),
+                (5, 22..23, None, 23..23),
+                (5, 23..24, None, 24..24),
+            ],
+            spans
+        );
+
+        // error
+        let mut decoder = MsgSpanDecoder::new(&[0x08, 0x01, 0x0a, 0x05]);
+        assert!(decoder.next().unwrap().is_ok());
+        assert_eq!(Some(Err(DecodeError::Len)), decoder.next());
+        assert_eq!(2, decoder.offset());
+        assert_eq!(None, decoder.next());
+
+        // not a LEN record
+        let mut decoder = MsgSpanDecoder::new(&[0x08, 0x01]);
+        let span = decoder.next().unwrap().unwrap();
+        assert_eq!(None, decoder.sub_msg(&span));
+    }
+}
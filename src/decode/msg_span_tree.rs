@@ -0,0 +1,121 @@
+use crate::decode::*;
+use alloc::{vec, vec::Vec};
+
+/// Decoder returning the records of a message & of its sub-messages
+/// (depth first) together with their byte ranges (see [`MsgSpanDecoder`]).
+///
+/// As the wire format does not indicate which LEN values contain
+/// sub-messages, `is_sub_msg` is called for every LEN record to decide if
+/// its value is decoded as sub-message. The records of a sub-message are
+/// returned directly after the record of the sub-message.
+///
+/// ```
+/// use protobin::decode::*;
+///
+/// // message { uint32 a = 1; message b = 2 { string c = 1; } }
+/// let bytes = [0x08, 0x03, 0x12, 0x03, 0x0a, 0x01, b'x'];
+///
+/// let spans: Vec<_> = MsgSpanTree::new(&bytes, |s| s.record.field_number.value() == 2)
+///     .map(|s| s.map(|s| (s.depth, s.range())))
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+/// assert_eq!(vec![(0, 0..2), (0, 2..7), (1, 4..7)], spans);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MsgSpanTree<'a, F> {
+    /// Decoders of the open (sub-)messages (innermost last).
+    stack: Vec<MsgSpanDecoder<'a>>,
+    is_sub_msg: F,
+    /// Position of the record that could not be decoded.
+    error_offset: Option<usize>,
+}
+
+impl<'a, F: FnMut(&RecordSpan<'a>) -> bool> MsgSpanTree<'a, F> {
+    /// Setup a decoder for the records of the given message.
+    pub fn new(data: &'a [u8], is_sub_msg: F) -> MsgSpanTree<'a, F> {
+        MsgSpanTree {
+            stack: vec![MsgSpanDecoder::new(data)],
+            is_sub_msg,
+            error_offset: None,
+        }
+    }
+
+    /// Position of the record that could not be decoded (`None` if no
+    /// error was encountered).
+    #[inline]
+    pub fn error_offset(&self) -> Option<usize> {
+        self.error_offset
+    }
+}
+
+impl<'a, F: FnMut(&RecordSpan<'a>) -> bool> Iterator for MsgSpanTree<'a, F> {
+    type Item = Result<RecordSpan<'a>, DecodeError>;
+
+    /// Returns the next record until an error is encountered (also in a
+    /// sub-message) or no more data is present.
+    fn next(&mut self) -> Option<Result<RecordSpan<'a>, DecodeError>> {
+        loop {
+            let decoder = self.stack.last_mut()?;
+            match decoder.next() {
+                None => {
+                    self.stack.pop();
+                }
+                Some(Err(err)) => {
+                    self.error_offset = Some(decoder.offset());
+                    self.stack.clear();
+                    return Some(Err(err));
+                }
+                Some(Ok(span)) => {
+                    if span.len.is_some() && (self.is_sub_msg)(&span) {
+                        let sub = decoder.sub_msg(&span);
+                        self.stack.extend(sub);
+                    }
+                    return Some(Ok(span));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::decode::*;
+
+    #[test]
+    fn tree() {
+        let data = [
+            0x0a, 0x06, // 1 {
+            0x12, 0x02, 0x08, 0x01, //   2 { 1: 1 }
+            0x18, 0x02, //   3: 2 }
+            0x22, 0x01, b'x', // 4: "x"
+        ];
+        let spans: Vec<_> = MsgSpanTree::new(&data, |s| s.record.field_number.value() < 3)
+            .map(|s| {
+                let s = s.unwrap();
+                (s.record.field_number.value(), s.depth, s.range())
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (1, 0, 0..8),
+                (2, 1, 2..6),
+                (1, 2, 4..6),
+                (3, 1, 6..8),
+                (4, 0, 8..11),
+            ],
+            spans
+        );
+
+        // error in a sub-message stops the decoding
+        let data = [0x0a, 0x02, 0x08, 0x80, 0x10, 0x01];
+        let mut tree = MsgSpanTree::new(&data, |_| true);
+        assert!(tree.next().unwrap().is_ok());
+        assert_eq!(None, tree.error_offset());
+        assert_eq!(
+            Some(Err(DecodeError::Len)),
+            tree.next().map(|r| r.map(|_| ()))
+        );
+        assert_eq!(Some(2), tree.error_offset());
+        assert_eq!(None, tree.next().map(|r| r.map(|_| ())));
+    }
+}
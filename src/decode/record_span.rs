@@ -0,0 +1,27 @@
+use crate::decode::*;
+use core::ops::Range;
+
+/// A decoded record together with the byte ranges of its parts in the
+/// decoded buffer (see [`MsgSpanDecoder`]).
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct RecordSpan<'a> {
+    pub record: MsgRecordRef<'a>,
+    /// Nesting depth of the record (0 for records of the top level
+    /// message).
+    pub depth: usize,
+    /// Byte range of the tag (field number & wire type).
+    pub tag: Range<usize>,
+    /// Byte range of the length prefix (`None` if the record is not a LEN
+    /// record).
+    pub len: Option<Range<usize>>,
+    /// Byte range of the value (empty for group markers).
+    pub value: Range<usize>,
+}
+
+impl<'a> RecordSpan<'a> {
+    /// Byte range of the whole record (tag, length & value).
+    #[inline]
+    pub fn range(&self) -> Range<usize> {
+        self.tag.start..self.value.end
+    }
+}
@@ -1,4 +1,4 @@
-use std::ops::RangeInclusive;
+use core::ops::RangeInclusive;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct FieldNumber(pub(crate) u32);
@@ -17,6 +17,14 @@ impl FieldNumber {
     /// Number one field number.
     pub const ONE: FieldNumber = FieldNumber(1);
 
+    /// Field number of the key in the entry messages of a protobuf
+    /// `map<K, V>` field.
+    pub const MAP_KEY: FieldNumber = FieldNumber(1);
+
+    /// Field number of the value in the entry messages of a protobuf
+    /// `map<K, V>` field.
+    pub const MAP_VALUE: FieldNumber = FieldNumber(2);
+
     /// Converts a given [`u32`] to a [`FieldNumber`] as long as it is smaller or
     /// equal `0x1FFFFFFF` ([`FieldNumber::MAX_ALLOWED_U32`]) and at least `1` or
     /// bigger. Otherwise an error is returned.
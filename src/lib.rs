@@ -1,4 +1,9 @@
-#![doc = include_str!("../README.md")]
+// the README examples use the allocating builders
+#![cfg_attr(feature = "alloc", doc = include_str!("../README.md"))]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
 /// Datatypes for message building.
 pub mod builders;
@@ -6,8 +11,48 @@ pub mod builders;
 /// Datatypes for message decoding.
 pub mod decode;
 
+/// Datatypes for patching fields of already encoded messages.
+#[cfg(feature = "alloc")]
+pub mod patch;
+
+/// Description of message types, validation of encoded messages against
+/// them & inference of them from samples.
+#[cfg(feature = "alloc")]
+pub mod schema;
+
+/// Encoding & decoding of serde types as protobuf messages.
+///
+/// Field numbers are assigned by the declaration order of the struct
+/// fields, tuple elements & enum variants (starting at 1). A field or
+/// variant whose serde name is a number (e.g. `#[serde(rename = "5")]`)
+/// uses that number instead. Fields skipped via `skip_serializing_if` keep
+/// their field number, fields that are only skipped in one direction
+/// should be given an explicit number.
+///
+/// The serde data model is mapped to protobuf the following way:
+///
+/// * `bool`, `i8`-`i32`, `i64`, `u8`-`u32`, `u64`, `f32` & `f64` are encoded
+///   as `bool`, `int32`, `int64`, `uint32`, `uint64`, `float` & `double`.
+/// * `char` & `str` are encoded as `string`, serde bytes as `bytes`.
+/// * `None` & unit values are not encoded.
+/// * Sequences are encoded as repeated field (packed fields are accepted
+///   when decoding), maps as `map<K, V>` field.
+/// * Structs & tuples are encoded as sub-message.
+/// * Unit variants are encoded as protobuf `enum` (the variant position or
+///   numeric name is the enum value), variants with data as sub-message
+///   containing one field for the variant (like a `oneof`).
+///
+/// Nested sequences & other values without protobuf representation
+/// result in [`SerdeError::Unsupported`](crate::serde::SerdeError::Unsupported).
+#[cfg(feature = "serde")]
+pub mod serde;
+
 /// Low level "wire" data types for encoding & decoding.
 pub mod wire;
 
+/// Encoding & decoding of the protobuf "well-known types" (e.g.
+/// `google.protobuf.Timestamp`).
+pub mod wkt;
+
 mod field_number;
 pub use field_number::*;
@@ -0,0 +1,5 @@
+mod msg_patcher;
+pub use msg_patcher::*;
+
+mod patch_error;
+pub use patch_error::*;
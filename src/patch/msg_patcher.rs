@@ -0,0 +1,367 @@
+use crate::{decode::*, patch::*, wire::*, FieldNumber};
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// Buffers to patch a single field of an already encoded message (should
+/// be reused as much as possible to avoid allocations).
+///
+/// Instead of decoding & re-encoding the entire message the [`MsgPatcher`]
+/// copies all untouched records verbatim. Only the patched record itself
+/// and the length prefixes of the sub-messages enclosing it are re-written.
+///
+/// Similar to [`MsgBuilder`](crate::builders::MsgBuilder) the patching is
+/// done in two stages:
+///
+/// 1. Locate the records on the field path & determine the new length
+///    of every enclosing sub-message (a changed length can change the
+///    byte length of the length prefix itself, which shifts the length of
+///    the next outer sub-message).
+/// 2. Copy the original data & splice in the new length prefixes and the
+///    new record.
+///
+/// # Example
+///
+/// ```
+/// use protobin::{patch::MsgPatcher, wire::*};
+///
+/// // message with `string request_id = 1` inside `Header header = 2`
+/// let data = [0x12, 0x05, 0x0a, 0x03, b'a', b'b', b'c'];
+///
+/// let mut patcher = MsgPatcher::new();
+/// let patched = patcher
+///     .patch(
+///         &data,
+///         &[2.try_into().unwrap(), 1.try_into().unwrap()],
+///         WireValueRef::Len(WireLenRef { data: b"abcd" }),
+///     )
+///     .unwrap();
+///
+/// assert_eq!(patched, &[0x12, 0x06, 0x0a, 0x04, b'a', b'b', b'c', b'd']);
+/// ```
+#[derive(Default, Debug, Clone, Eq, PartialEq, Hash)]
+pub struct MsgPatcher {
+    len_prefixes: Vec<LenPrefix>,
+    encoder: WireEncoder,
+}
+
+impl MsgPatcher {
+    /// Setup a new [`MsgPatcher`].
+    pub fn new() -> MsgPatcher {
+        Default::default()
+    }
+
+    /// Replaces the field at the given field path with a new value and
+    /// returns the resulting message.
+    ///
+    /// All but the last field number in `field_path` have to refer to
+    /// sub-messages. If a field is present multiple times the last
+    /// occurrence is used (same as protobuf's "last one wins" rule for
+    /// singular fields).
+    ///
+    /// If the patched field (last entry in `field_path`) is not present
+    /// in its message the new record is appended at the end of the message.
+    /// Missing sub-messages on the path are reported as
+    /// [`PatchError::SubMsgNotFound`].
+    pub fn patch<'a>(
+        &'a mut self,
+        data: &[u8],
+        field_path: &[FieldNumber],
+        value: WireValueRef<'_>,
+    ) -> Result<&'a [u8], PatchError> {
+        use PatchError::*;
+
+        let Some((field_number, parents)) = field_path.split_last() else {
+            return Err(EmptyPath);
+        };
+
+        // clear all previous data
+        self.len_prefixes.clear();
+        self.encoder.buf.clear();
+
+        // stage 1: locate the enclosing sub-messages & the patched record
+        let mut decoder = MsgSpanDecoder::new(data);
+        let mut msg_end = data.len();
+        for (depth, parent) in parents.iter().enumerate() {
+            let Some(span) = Self::find_last(decoder.clone(), *parent)? else {
+                return Err(SubMsgNotFound { depth });
+            };
+            let (Some(sub_msg), Some(len)) = (decoder.sub_msg(&span), span.len.clone()) else {
+                return Err(NotASubMsg {
+                    depth,
+                    actual: span.record.value.write_type(),
+                });
+            };
+            decoder = sub_msg;
+            msg_end = span.value.end;
+            self.len_prefixes.push(LenPrefix {
+                len,
+                value: span.value,
+                new_len: 0,
+            });
+        }
+        let target = match Self::find_last(decoder, *field_number)? {
+            Some(span) => span.range(),
+            None => msg_end..msg_end,
+        };
+
+        // determine the length change of every enclosing sub-message
+        // (starting from the innermost one)
+        let new_record_len = Self::record_byte_len(*field_number, &value).ok_or(LenTooBig {
+            depth: parents.len(),
+        })?;
+        let mut delta = new_record_len as i64 - target.len() as i64;
+        for (depth, prefix) in self.len_prefixes.iter_mut().enumerate().rev() {
+            let new_len = prefix.value.len() as i64 + delta;
+            let new_len = u32::try_from(new_len).map_err(|_| LenTooBig { depth })?;
+            let old_prefix_len = prefix.len.len() as i64;
+            delta += i64::from(WireVarInt::uint32_byte_len(new_len)) - old_prefix_len;
+            prefix.new_len = new_len;
+        }
+
+        // stage 2: copy untouched data & write new lengths & record
+        self.encoder
+            .buf
+            .reserve((data.len() as i64 + delta).max(0) as usize);
+        let mut cursor = 0;
+        for prefix in &self.len_prefixes {
+            self.encoder
+                .buf
+                .extend_from_slice(&data[cursor..prefix.len.start]);
+            self.encoder.add_var_uint32(prefix.new_len);
+            cursor = prefix.value.start;
+        }
+        self.encoder
+            .buf
+            .extend_from_slice(&data[cursor..target.start]);
+        self.add_record(*field_number, &value)?;
+        self.encoder.buf.extend_from_slice(&data[target.end..]);
+
+        Ok(&self.encoder.buf)
+    }
+
+    /// Returns the span of the last record with the given field number in
+    /// the message decoded by `decoder`.
+    fn find_last(
+        decoder: MsgSpanDecoder<'_>,
+        field_number: FieldNumber,
+    ) -> Result<Option<RecordSpan<'_>>, DecodeError> {
+        let mut found = None;
+        for span in decoder {
+            let span = span?;
+            if span.record.field_number == field_number {
+                found = Some(span);
+            }
+        }
+        Ok(found)
+    }
+
+    /// Returns the encoded byte length of a record (tag + value) or `None`
+    /// if the value is too long to be encoded.
+    fn record_byte_len(field_number: FieldNumber, value: &WireValueRef<'_>) -> Option<usize> {
+        let value_len = match value {
+            WireValueRef::VarInt(v) => WireVarInt::uint64_byte_len(v.raw()) as usize,
+            WireValueRef::I64(_) => 8,
+            WireValueRef::Len(v) => {
+                let len = u32::try_from(v.data.len()).ok()?;
+                WireVarInt::uint32_byte_len(len) as usize + v.data.len()
+            }
+            WireValueRef::SGroup | WireValueRef::EGroup => 0,
+            WireValueRef::I32(_) => 4,
+        };
+        Some(WireVarInt::tag_byte_len(field_number) as usize + value_len)
+    }
+
+    /// Writes the record (tag + value) to the output buffer.
+    fn add_record(
+        &mut self,
+        field_number: FieldNumber,
+        value: &WireValueRef<'_>,
+    ) -> Result<(), PatchError> {
+        let tag = (field_number.0 << 3) | (value.write_type() as u32);
+        match value {
+            WireValueRef::VarInt(v) => {
+                self.encoder.add_var_uint32(tag);
+                self.encoder.add_var_uint64(v.raw());
+            }
+            WireValueRef::I64(v) => {
+                self.encoder.add_var_uint32(tag);
+                self.encoder.add_fixed64(v.0);
+            }
+            WireValueRef::Len(v) => {
+                self.encoder.add_var_uint32(tag);
+                self.encoder.add_var_uint32(v.data.len() as u32);
+                self.encoder.buf.extend_from_slice(v.data);
+            }
+            WireValueRef::SGroup | WireValueRef::EGroup => {
+                return Err(PatchError::UnsupportedWireType(value.write_type()));
+            }
+            WireValueRef::I32(v) => {
+                self.encoder.add_var_uint32(tag);
+                self.encoder.add_fixed32(v.0);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Length prefix of a sub-message enclosing the patched record.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+struct LenPrefix {
+    /// Byte range of the length prefix in the original message data.
+    len: Range<usize>,
+    /// Byte range of the sub-message in the original message data.
+    value: Range<usize>,
+    /// Length value after patching (set in stage 1).
+    new_len: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builders::{MsgBuilder, MsgScribe};
+
+    fn f(value: u32) -> FieldNumber {
+        FieldNumber(value)
+    }
+
+    /// message Inner { string id = 1; uint64 counter = 2; }
+    /// message Outer { string name = 1; Inner inner = 2; repeated int32 tail = 3; }
+    fn ser_outer<S: MsgScribe>(id: Option<&str>, counter: u64, mut s: S) -> S::End {
+        s.add_string(f(1), "outer name");
+        s.start_msg(f(2));
+        s.start_msg(f(5));
+        if let Some(id) = id {
+            s.add_string(f(1), id);
+        }
+        s.add_uint64(f(2), counter);
+        s.end_msg(f(5));
+        s.end_msg(f(2));
+        s.add_int32(f(3), 1);
+        s.add_int32(f(3), -1);
+        s.end()
+    }
+
+    fn encode_outer(id: Option<&str>, counter: u64) -> Vec<u8> {
+        let mut builder = MsgBuilder::new();
+        let step2 = ser_outer(id, counter, builder.start(None));
+        ser_outer(id, counter, step2).to_vec()
+    }
+
+    #[test]
+    fn patch_nested() {
+        let long_id = "x".repeat(300);
+        let tests = [
+            // same length
+            ("abc", "xyz"),
+            // shrink & grow without a length prefix change
+            ("abc", "a"),
+            ("abc", "abcdef"),
+            // grow & shrink with length prefix changes in all levels
+            ("abc", long_id.as_str()),
+            (long_id.as_str(), "abc"),
+            (long_id.as_str(), ""),
+        ];
+        let mut patcher = MsgPatcher::new();
+        for (old, new) in tests {
+            let data = encode_outer(Some(old), 1234);
+            let patched = patcher
+                .patch(
+                    &data,
+                    &[f(2), f(5), f(1)],
+                    WireValueRef::Len(WireLenRef {
+                        data: new.as_bytes(),
+                    }),
+                )
+                .unwrap();
+            assert_eq!(patched, &encode_outer(Some(new), 1234)[..]);
+        }
+    }
+
+    #[test]
+    fn patch_varint() {
+        let mut patcher = MsgPatcher::new();
+        let data = encode_outer(Some("abc"), 1);
+        let patched = patcher
+            .patch(
+                &data,
+                &[f(2), f(5), f(2)],
+                WireValueRef::VarInt(WireVarInt::from_uint64(u64::MAX)),
+            )
+            .unwrap();
+        assert_eq!(patched, &encode_outer(Some("abc"), u64::MAX)[..]);
+    }
+
+    #[test]
+    fn patch_missing_field_is_appended() {
+        let mut patcher = MsgPatcher::new();
+        let data = encode_outer(None, 1);
+        let patched = patcher
+            .patch(
+                &data,
+                &[f(2), f(5), f(3)],
+                WireValueRef::I32(WireI32(0x1234_5678)),
+            )
+            .unwrap()
+            .to_vec();
+
+        let mut outer = MsgDecoder::new(&patched);
+        outer.next().unwrap().unwrap();
+        let WireValueRef::Len(inner) = outer.next().unwrap().unwrap().value else {
+            panic!("expected Len wire type");
+        };
+        let WireValueRef::Len(inner) = inner.as_sub_msg().next().unwrap().unwrap().value else {
+            panic!("expected Len wire type");
+        };
+        let records: Vec<_> = inner.as_sub_msg().map(|r| r.unwrap()).collect();
+        assert_eq!(2, records.len());
+        assert_eq!(f(3), records[1].field_number);
+        assert_eq!(Ok(0x1234_5678), records[1].value.as_fixed32());
+    }
+
+    #[test]
+    fn patch_last_occurrence() {
+        let mut patcher = MsgPatcher::new();
+        let data = encode_outer(Some("abc"), 1);
+        let patched = patcher
+            .patch(
+                &data,
+                &[f(3)],
+                WireValueRef::VarInt(WireVarInt::from_int32(5)),
+            )
+            .unwrap();
+        let values: Vec<_> = MsgDecoder::new(patched)
+            .map(|r| r.unwrap())
+            .filter(|r| r.field_number == f(3))
+            .map(|r| r.value.try_as_int32().unwrap())
+            .collect();
+        assert_eq!(vec![1, 5], values);
+    }
+
+    #[test]
+    fn patch_errors() {
+        let mut patcher = MsgPatcher::new();
+        let data = encode_outer(Some("abc"), 1);
+        let value = WireValueRef::VarInt(WireVarInt::from_uint32(1));
+
+        assert_eq!(Err(PatchError::EmptyPath), patcher.patch(&data, &[], value));
+        assert_eq!(
+            Err(PatchError::SubMsgNotFound { depth: 1 }),
+            patcher.patch(&data, &[f(2), f(6), f(1)], value)
+        );
+        assert_eq!(
+            Err(PatchError::NotASubMsg {
+                depth: 0,
+                actual: WireType::VarInt
+            }),
+            patcher.patch(&data, &[f(3), f(1)], value)
+        );
+        assert_eq!(
+            Err(PatchError::UnsupportedWireType(WireType::SGroup)),
+            patcher.patch(&data, &[f(1)], WireValueRef::SGroup)
+        );
+        assert_eq!(
+            Err(PatchError::Decode(DecodeError::Len)),
+            patcher.patch(&data[..data.len() - 1], &[f(1)], value)
+        );
+    }
+}
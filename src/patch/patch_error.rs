@@ -0,0 +1,34 @@
+use crate::{decode::DecodeError, wire::WireType};
+
+/// Error when patching a field in an encoded message via
+/// [`MsgPatcher`](super::MsgPatcher).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum PatchError {
+    /// The passed field path contained no field numbers.
+    EmptyPath,
+
+    /// Error while decoding the original message data.
+    Decode(DecodeError),
+
+    /// A sub-message on the field path (not the patched field itself)
+    /// was not present. `depth` is the index in the field path.
+    SubMsgNotFound { depth: usize },
+
+    /// A field on the field path that was expected to be a sub-message
+    /// has a non LEN wire type. `depth` is the index in the field path.
+    NotASubMsg { depth: usize, actual: WireType },
+
+    /// The new value has a wire type that can not be written as a
+    /// single record (`SGROUP` & `EGROUP`).
+    UnsupportedWireType(WireType),
+
+    /// The length of an enclosing sub-message would exceed the maximum
+    /// encodable length ([`u32::MAX`]) after patching.
+    LenTooBig { depth: usize },
+}
+
+impl From<DecodeError> for PatchError {
+    fn from(value: DecodeError) -> Self {
+        PatchError::Decode(value)
+    }
+}
@@ -0,0 +1,19 @@
+use crate::schema::*;
+use alloc::{string::String, vec::Vec};
+
+/// Description of an enum type.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct EnumDescriptor {
+    pub name: String,
+    pub values: Vec<EnumValueDescriptor>,
+    /// Only the declared values are valid (proto2 enums). Open enums (proto3)
+    /// accept any `int32` value.
+    pub closed: bool,
+}
+
+impl EnumDescriptor {
+    /// Returns the declared value with the given number.
+    pub fn value(&self, number: i32) -> Option<&EnumValueDescriptor> {
+        self.values.iter().find(|value| value.number == number)
+    }
+}
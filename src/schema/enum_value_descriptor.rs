@@ -0,0 +1,8 @@
+use alloc::string::String;
+
+/// Description of a declared value of an enum type.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct EnumValueDescriptor {
+    pub name: String,
+    pub number: i32,
+}
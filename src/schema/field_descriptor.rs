@@ -0,0 +1,14 @@
+use crate::{schema::*, FieldNumber};
+use alloc::string::String;
+
+/// Description of a message field.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct FieldDescriptor {
+    pub name: String,
+    pub number: FieldNumber,
+    pub label: FieldLabel,
+    pub field_type: FieldType,
+    /// Values are encoded as packed repeated field (one LEN record
+    /// containing all values).
+    pub packed: bool,
+}
@@ -0,0 +1,22 @@
+/// Cardinality of a message field.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum FieldLabel {
+    /// Field present at most once (if present multiple times the last
+    /// value wins).
+    Optional,
+    /// Field present any number of times.
+    Repeated,
+    /// Field present exactly once (proto2).
+    Required,
+}
+
+impl FieldLabel {
+    /// Keyword of the label in `.proto` files.
+    pub fn proto_name(&self) -> &'static str {
+        match self {
+            FieldLabel::Optional => "optional",
+            FieldLabel::Repeated => "repeated",
+            FieldLabel::Required => "required",
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{schema::*, wire::WireType};
+
+/// Type of a message field.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum FieldType {
+    Int32,
+    Int64,
+    UInt32,
+    UInt64,
+    SInt32,
+    SInt64,
+    Bool,
+    Enum(EnumDescriptor),
+    Fixed32,
+    SFixed32,
+    Float,
+    Fixed64,
+    SFixed64,
+    Double,
+    String,
+    Bytes,
+    /// Embedded message.
+    Message(MsgDescriptor),
+    /// proto2 group (records of the message enclosed by a SGROUP & EGROUP
+    /// record).
+    Group(MsgDescriptor),
+}
+
+impl FieldType {
+    /// Wire type of the records of a (not packed) field with this type.
+    pub fn wire_type(&self) -> WireType {
+        use FieldType::*;
+        match self {
+            Int32 | Int64 | UInt32 | UInt64 | SInt32 | SInt64 | Bool | Enum(_) => WireType::VarInt,
+            Fixed32 | SFixed32 | Float => WireType::I32,
+            Fixed64 | SFixed64 | Double => WireType::I64,
+            String | Bytes | Message(_) => WireType::Len,
+            Group(_) => WireType::SGroup,
+        }
+    }
+
+    /// Returns true if repeated fields of this type can be packed (scalar
+    /// numeric types).
+    pub fn is_packable(&self) -> bool {
+        matches!(
+            self.wire_type(),
+            WireType::VarInt | WireType::I32 | WireType::I64
+        )
+    }
+
+    /// Name of the type in `.proto` files (the type name for enums,
+    /// messages & groups).
+    pub fn proto_name(&self) -> &str {
+        use FieldType::*;
+        match self {
+            Int32 => "int32",
+            Int64 => "int64",
+            UInt32 => "uint32",
+            UInt64 => "uint64",
+            SInt32 => "sint32",
+            SInt64 => "sint64",
+            Bool => "bool",
+            Enum(e) => &e.name,
+            Fixed32 => "fixed32",
+            SFixed32 => "sfixed32",
+            Float => "float",
+            Fixed64 => "fixed64",
+            SFixed64 => "sfixed64",
+            Double => "double",
+            String => "string",
+            Bytes => "bytes",
+            Message(msg) | Group(msg) => &msg.name,
+        }
+    }
+}
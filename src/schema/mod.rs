@@ -0,0 +1,28 @@
+mod enum_descriptor;
+pub use enum_descriptor::*;
+
+mod enum_value_descriptor;
+pub use enum_value_descriptor::*;
+
+mod field_descriptor;
+pub use field_descriptor::*;
+
+mod field_label;
+pub use field_label::*;
+
+mod field_type;
+pub use field_type::*;
+
+mod msg_descriptor;
+pub use msg_descriptor::*;
+
+mod schema_inferrer;
+pub use schema_inferrer::*;
+
+mod schema_validator;
+
+mod schema_violation;
+pub use schema_violation::*;
+
+mod schema_violation_kind;
+pub use schema_violation_kind::*;
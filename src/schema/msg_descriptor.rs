@@ -0,0 +1,162 @@
+use crate::{schema::*, FieldNumber};
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+/// Description of a message type (e.g. inferred via [`SchemaInferrer`]).
+///
+/// The types of embedded messages & groups are contained in the fields
+/// (see [`FieldType::Message`] & [`FieldType::Group`]), recursive message
+/// types can not be described.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct MsgDescriptor {
+    pub name: String,
+    pub fields: Vec<FieldDescriptor>,
+}
+
+impl MsgDescriptor {
+    /// Returns the field with the given field number.
+    pub fn field(&self, number: FieldNumber) -> Option<&FieldDescriptor> {
+        self.fields.iter().find(|field| field.number == number)
+    }
+
+    /// Returns the message as `.proto` file (proto2 syntax, the types of
+    /// embedded messages & enums are declared as nested types).
+    ///
+    /// ```
+    /// use protobin::{schema::*, FieldNumber};
+    ///
+    /// let msg = MsgDescriptor {
+    ///     name: "Point".into(),
+    ///     fields: vec![FieldDescriptor {
+    ///         name: "x".into(),
+    ///         number: FieldNumber::try_from(1).unwrap(),
+    ///         label: FieldLabel::Repeated,
+    ///         field_type: FieldType::SInt32,
+    ///         packed: true,
+    ///     }],
+    /// };
+    /// assert_eq!(
+    ///     "syntax = \"proto2\";\n\
+    ///      \n\
+    ///      message Point {\n  repeated sint32 x = 1 [packed = true];\n}\n",
+    ///     msg.to_proto()
+    /// );
+    /// ```
+    pub fn to_proto(&self) -> String {
+        let mut out = String::from("syntax = \"proto2\";\n\n");
+        self.write_proto(&mut out, 0);
+        out
+    }
+
+    fn write_proto(&self, out: &mut String, depth: usize) {
+        indent(out, depth);
+        writeln!(out, "message {} {{", self.name).unwrap();
+        self.write_body(out, depth + 1);
+        indent(out, depth);
+        out.push_str("}\n");
+    }
+
+    fn write_body(&self, out: &mut String, depth: usize) {
+        // nested message & enum types before the fields (once per name)
+        let mut declared: Vec<&str> = Vec::new();
+        for field in &self.fields {
+            let name = field.field_type.proto_name();
+            if declared.contains(&name) {
+                continue;
+            }
+            match &field.field_type {
+                FieldType::Message(msg) => msg.write_proto(out, depth),
+                FieldType::Enum(e) => {
+                    indent(out, depth);
+                    writeln!(out, "enum {} {{", e.name).unwrap();
+                    for value in &e.values {
+                        indent(out, depth + 1);
+                        writeln!(out, "{} = {};", value.name, value.number).unwrap();
+                    }
+                    indent(out, depth);
+                    out.push_str("}\n");
+                }
+                _ => continue,
+            }
+            declared.push(name);
+        }
+        for field in &self.fields {
+            indent(out, depth);
+            let label = field.label.proto_name();
+            let number = field.number.value();
+            if let FieldType::Group(msg) = &field.field_type {
+                writeln!(out, "{} group {} = {} {{", label, msg.name, number).unwrap();
+                msg.write_body(out, depth + 1);
+                indent(out, depth);
+                out.push_str("}\n");
+                continue;
+            }
+            let name = field.field_type.proto_name();
+            write!(out, "{} {} {} = {}", label, name, field.name, number).unwrap();
+            if field.packed {
+                out.push_str(" [packed = true]");
+            }
+            out.push_str(";\n");
+        }
+    }
+}
+
+fn indent(out: &mut String, depth: usize) {
+    for _ in 0..depth {
+        out.push_str("  ");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{schema::*, FieldNumber};
+
+    #[test]
+    fn to_proto() {
+        let color = FieldType::Enum(EnumDescriptor {
+            name: "Color".into(),
+            values: vec![EnumValueDescriptor {
+                name: "RED".into(),
+                number: 0,
+            }],
+            closed: true,
+        });
+        let field = |number: u32, label, field_type| FieldDescriptor {
+            name: format!("field_{}", number),
+            number: FieldNumber::try_from(number).unwrap(),
+            label,
+            field_type,
+            packed: false,
+        };
+        let msg = MsgDescriptor {
+            name: "Msg".into(),
+            fields: vec![
+                field(1, FieldLabel::Required, color.clone()),
+                field(2, FieldLabel::Repeated, color),
+                field(
+                    3,
+                    FieldLabel::Optional,
+                    FieldType::Group(MsgDescriptor {
+                        name: "Field3".into(),
+                        fields: vec![field(1, FieldLabel::Optional, FieldType::Bytes)],
+                    }),
+                ),
+            ],
+        };
+        assert_eq!(
+            "syntax = \"proto2\";\n\
+             \n\
+             message Msg {\n\
+             \x20 enum Color {\n\
+             \x20   RED = 0;\n\
+             \x20 }\n\
+             \x20 required Color field_1 = 1;\n\
+             \x20 repeated Color field_2 = 2;\n\
+             \x20 optional group Field3 = 3 {\n\
+             \x20   optional bytes field_1 = 1;\n\
+             \x20 }\n\
+             }\n",
+            msg.to_proto()
+        );
+    }
+}
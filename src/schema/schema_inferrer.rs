@@ -0,0 +1,695 @@
+use crate::{decode::*, schema::*, wire::*, FieldNumber};
+use alloc::{collections::BTreeMap, format, string::String};
+
+/// Infers a probable schema from samples of the same (unknown) message
+/// type, e.g. to reverse-engineer an undocumented protobuf API.
+///
+/// The samples are added one by one via [`SchemaInferrer::add`] (only
+/// statistics are kept, not the samples). [`SchemaInferrer::infer`] then
+/// returns the most likely description of the message, that can be printed
+/// as `.proto` file via [`MsgDescriptor::to_proto`]:
+///
+/// * Fields present multiple times in one message are `repeated`, all
+///   others `optional`.
+/// * LEN values are interpreted as string, embedded message, packed
+///   repeated field or bytes depending on the interpretation with the
+///   highest confidence over all values (see [`WireLenRef::classify`]).
+///   Embedded messages & groups are inferred recursively up to a nesting
+///   depth of [`WireLenRef::MAX_SUB_MSG_DEPTH`] (deeper LEN values are not
+///   interpreted as embedded message & the content of deeper groups is
+///   ignored).
+/// * VARINT values with the highest bit set are `int32` (if all values fit)
+///   or `int64`, only 0 & 1 values are `bool`. If at least a third of 4 or
+///   more values are odd (zigzag encoded negative numbers) & the zigzag
+///   decoded values spread out from around zero (the smallest magnitude is
+///   at most half of the largest) the field is `sint32` or `sint64`,
+///   otherwise `int32`, `uint32` or `int64` depending on the largest value.
+///   E.g. the counters 10, 11, 12 & 13 are `int32`, because their zigzag
+///   decoded values 5, -6, 6 & -7 cluster away from zero.
+/// * I32 & I64 values are `float` & `double` if all values are plausible
+///   floating point numbers (see [`LenKind::confidence`]) and not all of
+///   them small integers, otherwise `fixed32`/`fixed64` (`sfixed32`/
+///   `sfixed64` if a value has the highest bit set).
+///
+/// The heuristics can only give hints (e.g. `uint32` & `sint32` can not be
+/// distinguished reliably & an `enum` is inferred as integer). Field names
+/// are `field_<number>` & nested message names `Field<number>`. If a field
+/// is encoded with different wire types the most common one is used
+/// (except packed & unpacked values of the same type, which are merged).
+///
+/// ```
+/// use protobin::schema::SchemaInferrer;
+///
+/// let samples: [&[u8]; 2] = [
+///     // 1: "a", 2: sint32 -1, 3 { 1: 300 }
+///     &[0x0a, 0x01, b'a', 0x10, 0x01, 0x1a, 0x03, 0x08, 0xac, 0x02],
+///     // 1: "b", 2: sint32 1, 2: sint32 -2, 2: sint32 -3
+///     &[0x0a, 0x01, b'b', 0x10, 0x02, 0x10, 0x03, 0x10, 0x05],
+/// ];
+///
+/// let mut inferrer = SchemaInferrer::new();
+/// for sample in samples {
+///     inferrer.add(sample).unwrap();
+/// }
+/// assert_eq!(
+///     "syntax = \"proto2\";\n\
+///      \n\
+///      message Sample {\n\
+///      \x20 message Field3 {\n\
+///      \x20   optional int32 field_1 = 1;\n\
+///      \x20 }\n\
+///      \x20 optional string field_1 = 1;\n\
+///      \x20 repeated sint32 field_2 = 2;\n\
+///      \x20 optional Field3 field_3 = 3;\n\
+///      }\n",
+///     inferrer.infer("Sample").to_proto()
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SchemaInferrer {
+    stats: MsgStats,
+}
+
+impl SchemaInferrer {
+    /// Setup an inferrer without samples.
+    pub fn new() -> SchemaInferrer {
+        Default::default()
+    }
+
+    /// Adds a sample message to the statistics.
+    ///
+    /// Samples that can not be decoded are not added.
+    pub fn add(&mut self, data: &[u8]) -> Result<(), DecodeError> {
+        for record in MsgDecoder::new(data) {
+            record?;
+        }
+        self.stats.add(&mut MsgDecoder::new(data), None, 0);
+        Ok(())
+    }
+
+    /// Number of added samples.
+    #[inline]
+    pub fn samples(&self) -> usize {
+        self.stats.samples
+    }
+
+    /// Returns the most likely description of the message type (with the
+    /// given name) based on the added samples.
+    pub fn infer(&self, name: &str) -> MsgDescriptor {
+        self.stats.infer(String::from(name))
+    }
+}
+
+/// Statistics of the records of a message type.
+#[derive(Debug, Clone, Default)]
+struct MsgStats {
+    samples: usize,
+    fields: BTreeMap<FieldNumber, FieldStats>,
+}
+
+impl MsgStats {
+    /// Adds the records until the end of the data or the EGROUP record of
+    /// `group` (the data must be decodable). `depth` is the nesting depth
+    /// of the message (0 for the samples).
+    fn add(&mut self, decoder: &mut MsgDecoder<'_>, group: Option<FieldNumber>, depth: usize) {
+        self.samples += 1;
+        if depth > WireLenRef::MAX_SUB_MSG_DEPTH {
+            // only groups get this deep, their content is ignored
+            skip_group(decoder, group);
+            return;
+        }
+        let mut counts: BTreeMap<FieldNumber, usize> = BTreeMap::new();
+        while let Some(Ok(record)) = decoder.next() {
+            let number = record.field_number;
+            if record.value == WireValueRef::EGroup {
+                if Some(number) == group {
+                    break;
+                }
+                // unmatched group ends are ignored
+                continue;
+            }
+            *counts.entry(number).or_default() += 1;
+            let field = self.fields.entry(number).or_default();
+            match record.value {
+                WireValueRef::VarInt(v) => field.var_int.add(v.as_uint64()),
+                WireValueRef::I64(v) => field.i64.add(v.0, 64),
+                WireValueRef::I32(v) => field.i32.add(u64::from(v.0), 32),
+                WireValueRef::Len(l) => field.len.add(l.data, depth),
+                WireValueRef::SGroup => field.group.add(decoder, Some(number), depth + 1),
+                WireValueRef::EGroup => unreachable!(),
+            }
+        }
+        for (number, count) in counts {
+            if let Some(field) = self.fields.get_mut(&number) {
+                field.max_count = field.max_count.max(count);
+            }
+        }
+    }
+
+    fn infer(&self, name: String) -> MsgDescriptor {
+        MsgDescriptor {
+            name,
+            fields: self
+                .fields
+                .iter()
+                .map(|(number, field)| field.infer(*number))
+                .collect(),
+        }
+    }
+}
+
+/// Skips the records until the end of the data or the EGROUP record of
+/// `group` (including nested groups).
+fn skip_group(decoder: &mut MsgDecoder<'_>, group: Option<FieldNumber>) {
+    let mut nested = 0usize;
+    while let Some(Ok(record)) = decoder.next() {
+        match record.value {
+            WireValueRef::SGroup => nested += 1,
+            WireValueRef::EGroup if nested > 0 => nested -= 1,
+            WireValueRef::EGroup if Some(record.field_number) == group => break,
+            _ => {}
+        }
+    }
+}
+
+/// Statistics of the values of a field.
+#[derive(Debug, Clone, Default)]
+struct FieldStats {
+    /// Maximum number of records of the field in one message.
+    max_count: usize,
+    var_int: VarIntStats,
+    i64: FixedStats,
+    i32: FixedStats,
+    len: LenStats,
+    group: MsgStats,
+}
+
+impl FieldStats {
+    fn infer(&self, number: FieldNumber) -> FieldDescriptor {
+        let msg_name = || format!("Field{}", number.value());
+        let mut label = if self.max_count > 1 {
+            FieldLabel::Repeated
+        } else {
+            FieldLabel::Optional
+        };
+        let mut packed = false;
+
+        // most common wire type (first one on equal counts)
+        let (wire_type, count) = [
+            (WireType::VarInt, self.var_int.count),
+            (WireType::I64, self.i64.count),
+            (WireType::Len, self.len.count),
+            (WireType::SGroup, self.group.samples),
+            (WireType::I32, self.i32.count),
+        ]
+        .into_iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .unwrap_or((WireType::Len, 0));
+
+        let field_type = match wire_type {
+            WireType::VarInt | WireType::I64 | WireType::I32 => {
+                // merge with packed values of the same type
+                let with_packed =
+                    self.len.count > 0 && self.len.is_possible(packed_kind(wire_type));
+                if with_packed {
+                    label = FieldLabel::Repeated;
+                    packed = self.len.count >= count;
+                }
+                self.scalar_type(wire_type, with_packed)
+            }
+            WireType::SGroup => FieldType::Group(self.group.infer(msg_name())),
+            _ => match self.len.best_kind() {
+                LenKind::String => FieldType::String,
+                LenKind::SubMsg => FieldType::Message(self.len.msg.infer(msg_name())),
+                LenKind::Bytes => FieldType::Bytes,
+                kind => {
+                    label = FieldLabel::Repeated;
+                    packed = true;
+                    let wire_type = match kind {
+                        LenKind::PackedVarInt => WireType::VarInt,
+                        LenKind::PackedI64 => WireType::I64,
+                        _ => WireType::I32,
+                    };
+                    self.scalar_type(wire_type, true)
+                }
+            },
+        };
+        FieldDescriptor {
+            name: format!("field_{}", number.value()),
+            number,
+            label,
+            field_type,
+            packed,
+        }
+    }
+
+    /// Type of the (unpacked & optionally packed) values with the given
+    /// wire type.
+    fn scalar_type(&self, wire_type: WireType, with_packed: bool) -> FieldType {
+        match wire_type {
+            WireType::VarInt => {
+                let mut stats = self.var_int.clone();
+                if with_packed {
+                    stats.merge(&self.len.var_int);
+                }
+                stats.field_type()
+            }
+            WireType::I64 => {
+                let mut stats = self.i64.clone();
+                if with_packed {
+                    stats.merge(&self.len.i64);
+                }
+                stats.field_type(FieldType::Double, FieldType::Fixed64, FieldType::SFixed64)
+            }
+            _ => {
+                let mut stats = self.i32.clone();
+                if with_packed {
+                    stats.merge(&self.len.i32);
+                }
+                stats.field_type(FieldType::Float, FieldType::Fixed32, FieldType::SFixed32)
+            }
+        }
+    }
+}
+
+fn packed_kind(wire_type: WireType) -> LenKind {
+    match wire_type {
+        WireType::VarInt => LenKind::PackedVarInt,
+        WireType::I64 => LenKind::PackedI64,
+        _ => LenKind::PackedI32,
+    }
+}
+
+/// Statistics of VARINT values.
+#[derive(Debug, Clone, Default)]
+struct VarIntStats {
+    count: usize,
+    odd: usize,
+    /// Smallest value without the highest bit set.
+    min_unsigned: Option<u64>,
+    /// Largest value without the highest bit set.
+    max: u64,
+    /// Number of values with the highest bit set (negative `int32` &
+    /// `int64` values).
+    negative: usize,
+    /// Smallest value interpreted as `int64`.
+    min: i64,
+}
+
+impl VarIntStats {
+    fn add(&mut self, value: u64) {
+        self.count += 1;
+        if value & 1 == 1 {
+            self.odd += 1;
+        }
+        match i64::try_from(value) {
+            Ok(_) => {
+                self.min_unsigned = Some(self.min_unsigned.map_or(value, |min| min.min(value)));
+                self.max = self.max.max(value);
+            }
+            Err(_) => self.negative += 1,
+        }
+        self.min = self.min.min(value as i64);
+    }
+
+    fn merge(&mut self, other: &VarIntStats) {
+        self.count += other.count;
+        self.odd += other.odd;
+        self.min_unsigned = match (self.min_unsigned, other.min_unsigned) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.max = self.max.max(other.max);
+        self.negative += other.negative;
+        self.min = self.min.min(other.min);
+    }
+
+    fn field_type(&self) -> FieldType {
+        if self.negative > 0 {
+            if self.min >= i64::from(i32::MIN) && self.max <= i32::MAX as u64 {
+                FieldType::Int32
+            } else {
+                FieldType::Int64
+            }
+        } else if self.max <= 1 {
+            FieldType::Bool
+        } else if self.count >= 4 && self.odd * 3 >= self.count && self.around_zero() {
+            // zigzag encoded negative values are odd
+            if self.max <= u64::from(u32::MAX) {
+                FieldType::SInt32
+            } else {
+                FieldType::SInt64
+            }
+        } else if self.max <= i32::MAX as u64 {
+            FieldType::Int32
+        } else if self.max <= u64::from(u32::MAX) {
+            FieldType::UInt32
+        } else {
+            FieldType::Int64
+        }
+    }
+
+    /// Returns true if the zigzag decoded values spread out from around
+    /// zero (the smallest magnitude is at most half of the largest one),
+    /// counters & IDs cluster away from zero instead.
+    fn around_zero(&self) -> bool {
+        // zigzag decoded magnitude, grows with the encoded value
+        let magnitude = |value: u64| (value >> 1) + (value & 1);
+        matches!(self.min_unsigned, Some(min) if magnitude(min) * 2 <= magnitude(self.max))
+    }
+}
+
+/// Statistics of I32 or I64 values.
+#[derive(Debug, Clone, Default)]
+struct FixedStats {
+    count: usize,
+    /// Number of values that are small integers (below 2^20).
+    ints: usize,
+    /// Number of values that are plausible floating point numbers.
+    floats: usize,
+    /// Number of values with the highest bit set.
+    negative: usize,
+}
+
+impl FixedStats {
+    fn add(&mut self, value: u64, bits: u32) {
+        self.count += 1;
+        if value < (1 << 20) {
+            self.ints += 1;
+        }
+        let float = if bits == 32 {
+            f64::from(f32::from_bits(value as u32))
+        } else {
+            f64::from_bits(value)
+        };
+        if plausible_float(float) {
+            self.floats += 1;
+        }
+        if value >> (bits - 1) == 1 {
+            self.negative += 1;
+        }
+    }
+
+    fn merge(&mut self, other: &FixedStats) {
+        self.count += other.count;
+        self.ints += other.ints;
+        self.floats += other.floats;
+        self.negative += other.negative;
+    }
+
+    fn field_type(&self, float: FieldType, fixed: FieldType, sfixed: FieldType) -> FieldType {
+        if self.floats == self.count && self.ints < self.count {
+            float
+        } else if self.negative > 0 {
+            sfixed
+        } else {
+            fixed
+        }
+    }
+}
+
+/// Statistics of LEN values for all interpretations.
+#[derive(Debug, Clone)]
+struct LenStats {
+    count: usize,
+    /// Sum of the confidences of every interpretation (in the order of
+    /// [`LenKind::ALL`], `None` if impossible for a value).
+    confidences: [Option<u64>; 6],
+    msg: MsgStats,
+    var_int: VarIntStats,
+    i64: FixedStats,
+    i32: FixedStats,
+}
+
+impl Default for LenStats {
+    fn default() -> LenStats {
+        LenStats {
+            count: 0,
+            confidences: [Some(0); 6],
+            msg: Default::default(),
+            var_int: Default::default(),
+            i64: Default::default(),
+            i32: Default::default(),
+        }
+    }
+}
+
+impl LenStats {
+    /// Adds a LEN value of a message at the given nesting depth.
+    fn add(&mut self, data: &[u8], depth: usize) {
+        self.count += 1;
+        for (kind, sum) in LenKind::ALL.iter().zip(self.confidences.iter_mut()) {
+            // no sub-messages from the maximum depth on (see `WireLenRef::kind_at_depth`)
+            let confidence = if *kind == LenKind::SubMsg && depth >= WireLenRef::MAX_SUB_MSG_DEPTH {
+                0
+            } else {
+                kind.confidence(data)
+            };
+            *sum = match sum {
+                Some(sum) if confidence > 0 => Some(*sum + u64::from(confidence)),
+                _ => None,
+            };
+        }
+        // statistics of the interpretations that are still possible
+        if self.is_possible(LenKind::SubMsg) {
+            self.msg.add(&mut MsgDecoder::new(data), None, depth + 1);
+        }
+        if self.is_possible(LenKind::PackedVarInt) {
+            let mut decoder = WireDecoder::new(data);
+            while let Ok(value) = decoder.read_var_uint64() {
+                self.var_int.add(value);
+            }
+        }
+        if self.is_possible(LenKind::PackedI64) {
+            for value in data.chunks_exact(8) {
+                let value = u64::from_le_bytes(value.try_into().unwrap());
+                self.i64.add(value, 64);
+            }
+        }
+        if self.is_possible(LenKind::PackedI32) {
+            for value in data.chunks_exact(4) {
+                let value = u32::from_le_bytes(value.try_into().unwrap());
+                self.i32.add(u64::from(value), 32);
+            }
+        }
+    }
+
+    fn is_possible(&self, kind: LenKind) -> bool {
+        let index = LenKind::ALL.iter().position(|k| *k == kind).unwrap();
+        self.confidences[index].is_some()
+    }
+
+    /// Interpretation with the highest confidence sum (first one on equal
+    /// sums).
+    fn best_kind(&self) -> LenKind {
+        LenKind::ALL
+            .iter()
+            .zip(self.confidences.iter())
+            .rev()
+            .filter_map(|(kind, sum)| sum.map(|sum| (*kind, sum)))
+            .max_by_key(|(_, sum)| *sum)
+            .map_or(LenKind::Bytes, |(kind, _)| kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{schema::*, wire::*, FieldNumber};
+
+    fn infer(samples: &[&[u8]]) -> MsgDescriptor {
+        let mut inferrer = SchemaInferrer::new();
+        for sample in samples {
+            inferrer.add(sample).unwrap();
+        }
+        assert_eq!(samples.len(), inferrer.samples());
+        inferrer.infer("Msg")
+    }
+
+    fn field_type(samples: &[&[u8]]) -> (FieldLabel, FieldType, bool) {
+        let msg = infer(samples);
+        let field = msg.field(FieldNumber::try_from(1).unwrap()).unwrap();
+        (field.label, field.field_type.clone(), field.packed)
+    }
+
+    #[test]
+    fn var_int() {
+        use FieldLabel::*;
+        use FieldType::*;
+
+        assert_eq!(
+            (Optional, Bool, false),
+            field_type(&[&[0x08, 0x00], &[0x08, 0x01]])
+        );
+        assert_eq!(
+            (Optional, Int32, false),
+            field_type(&[&[0x08, 0x02], &[0x08, 0x96, 0x01]])
+        );
+        assert_eq!(
+            (Optional, UInt32, false),
+            field_type(&[&[0x08, 0x80, 0x80, 0x80, 0x80, 0x08]])
+        );
+        assert_eq!(
+            (Optional, Int64, false),
+            field_type(&[&[0x08, 0x80, 0x80, 0x80, 0x80, 0x10]])
+        );
+        // negative int32 (sign extended to 10 bytes)
+        let minus_one: &[u8] = &[
+            0x08, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01,
+        ];
+        assert_eq!(
+            (Optional, Int32, false),
+            field_type(&[minus_one, &[0x08, 0x02]])
+        );
+        // negative int64
+        let min: &[u8] = &[
+            0x08, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x01,
+        ];
+        assert_eq!((Optional, Int64, false), field_type(&[min]));
+        // zigzag encoded -1, 1, -2, 2
+        assert_eq!(
+            (Repeated, SInt32, false),
+            field_type(&[&[0x08, 0x01, 0x08, 0x02], &[0x08, 0x03, 0x08, 0x04]])
+        );
+        // counters & IDs with odd values are not zigzag encoded
+        assert_eq!(
+            (Repeated, Int32, false),
+            field_type(&[&[0x08, 0x0a, 0x08, 0x0b], &[0x08, 0x0c, 0x08, 0x0d]])
+        );
+        assert_eq!(
+            (Repeated, Int32, false),
+            field_type(&[
+                &[0x08, 0xe9, 0x07, 0x08, 0xeb, 0x07],
+                &[0x08, 0xf0, 0x07, 0x08, 0xe7, 0x07, 0x08, 0xf3, 0x07]
+            ])
+        );
+        // packed & unpacked values
+        assert_eq!(
+            (Repeated, Int32, true),
+            field_type(&[
+                &[0x0a, 0x02, 0x96, 0x01],
+                &[0x0a, 0x02, 0x02, 0x04],
+                &[0x08, 0x06]
+            ])
+        );
+        assert_eq!(
+            (Repeated, Int32, false),
+            field_type(&[&[0x0a, 0x02, 0x96, 0x01], &[0x08, 0x02], &[0x08, 0x04]])
+        );
+    }
+
+    #[test]
+    fn fixed() {
+        use FieldLabel::*;
+        use FieldType::*;
+
+        let float: &[u8] = &[0x0d, 0, 0, 0xc0, 0x3f];
+        let int: &[u8] = &[0x0d, 1, 0, 0, 0];
+        let negative: &[u8] = &[0x0d, 0xff, 0xff, 0xff, 0xff];
+        assert_eq!((Optional, Float, false), field_type(&[float]));
+        assert_eq!((Optional, Fixed32, false), field_type(&[float, int]));
+        assert_eq!((Optional, SFixed32, false), field_type(&[int, negative]));
+
+        let double: &[u8] = &[0x09, 0, 0, 0, 0, 0, 0, 0xf8, 0x3f];
+        let int: &[u8] = &[0x09, 1, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!((Optional, Double, false), field_type(&[double]));
+        assert_eq!((Optional, Fixed64, false), field_type(&[double, int]));
+
+        // packed floats
+        assert_eq!(
+            (Repeated, Float, true),
+            field_type(&[&[0x0a, 0x08, 0, 0, 0xc0, 0x3f, 0, 0, 0x20, 0x40]])
+        );
+    }
+
+    #[test]
+    fn len() {
+        use FieldLabel::*;
+        use FieldType::*;
+
+        assert_eq!(
+            (Repeated, String, false),
+            field_type(&[&[0x0a, 0x01, b'a', 0x0a, 0x00]])
+        );
+        assert_eq!(
+            (Optional, Bytes, false),
+            field_type(&[&[0x0a, 0x02, 0xff, 0xff]])
+        );
+
+        // sub-messages (fields present in only one of them)
+        let msg = infer(&[
+            &[0x0a, 0x03, 0x08, 0x96, 0x01],
+            &[0x0a, 0x04, 0x10, 0x02, 0x10, 0x04],
+        ]);
+        assert_eq!(
+            "syntax = \"proto2\";\n\
+             \n\
+             message Msg {\n\
+             \x20 message Field1 {\n\
+             \x20   optional int32 field_1 = 1;\n\
+             \x20   repeated int32 field_2 = 2;\n\
+             \x20 }\n\
+             \x20 optional Field1 field_1 = 1;\n\
+             }\n",
+            msg.to_proto()
+        );
+    }
+
+    #[test]
+    fn group() {
+        // 1 { 2: 1 } 3: 1
+        let msg = infer(&[&[0x0b, 0x10, 0x01, 0x0c, 0x18, 0x01]]);
+        assert_eq!(
+            "syntax = \"proto2\";\n\
+             \n\
+             message Msg {\n\
+             \x20 optional group Field1 = 1 {\n\
+             \x20   optional bool field_2 = 2;\n\
+             \x20 }\n\
+             \x20 optional bool field_3 = 3;\n\
+             }\n",
+            msg.to_proto()
+        );
+    }
+
+    #[test]
+    fn depth_limit() {
+        // a million nested unterminated groups
+        let msg = infer(&[&[0x0b; 1_000_000]]);
+        let mut depth = 0;
+        let mut field = &msg.fields[0];
+        while let FieldType::Group(group) = &field.field_type {
+            depth += 1;
+            match group.fields.first() {
+                Some(f) => field = f,
+                None => break,
+            }
+        }
+        assert_eq!(WireLenRef::MAX_SUB_MSG_DEPTH + 1, depth);
+
+        // sub-messages nested deeper than the limit
+        let mut data = vec![0x08, 0x01];
+        for _ in 0..(WireLenRef::MAX_SUB_MSG_DEPTH + 10) {
+            let mut outer = vec![0x0a];
+            outer.extend_from_slice(VarIntBytes::new(data.len() as u64).as_slice());
+            outer.extend_from_slice(&data);
+            data = outer;
+        }
+        let msg = infer(&[&data]);
+        let mut depth = 0;
+        let mut field = &msg.fields[0];
+        while let FieldType::Message(sub_msg) = &field.field_type {
+            depth += 1;
+            field = &sub_msg.fields[0];
+        }
+        assert_eq!(WireLenRef::MAX_SUB_MSG_DEPTH, depth);
+    }
+
+    #[test]
+    fn error() {
+        let mut inferrer = SchemaInferrer::new();
+        assert!(inferrer.add(&[0x0a, 0x05]).is_err());
+        assert_eq!(0, inferrer.samples());
+        assert!(inferrer.infer("Msg").fields.is_empty());
+    }
+}
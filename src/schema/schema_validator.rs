@@ -0,0 +1,478 @@
+use crate::{decode::*, schema::*, wire::*, FieldNumber};
+use alloc::{collections::BTreeMap, vec::Vec};
+
+/// Maximum nesting depth of embedded messages & groups that are validated
+/// (same as the default recursion limit of the protobuf reference
+/// implementation).
+const MAX_VALIDATE_DEPTH: usize = 100;
+
+impl MsgDescriptor {
+    /// Validates an encoded message against the schema & returns all found
+    /// violations (empty if the message is valid).
+    ///
+    /// The following is checked (recursively for embedded messages &
+    /// groups):
+    ///
+    /// * The wire types of the records match the declared field types
+    ///   (repeated fields of scalar numeric types are accepted packed &
+    ///   unpacked).
+    /// * `required` fields are present.
+    /// * Non repeated fields are present at most once.
+    /// * Values of closed enums are declared.
+    /// * `string` values are valid UTF-8.
+    ///
+    /// Embedded messages & groups nested deeper than 100 levels are reported
+    /// as [`SchemaViolationKind::RecursionLimit`] (also skipped unknown
+    /// groups). Fields not declared in the schema are accepted (unknown fields).
+    ///
+    /// ```
+    /// use protobin::{schema::*, wire::WireType, FieldNumber};
+    ///
+    /// let field = |number: u32, label, field_type| FieldDescriptor {
+    ///     name: format!("field_{}", number),
+    ///     number: FieldNumber::try_from(number).unwrap(),
+    ///     label,
+    ///     field_type,
+    ///     packed: false,
+    /// };
+    /// let header = MsgDescriptor {
+    ///     name: "Header".into(),
+    ///     fields: vec![field(1, FieldLabel::Required, FieldType::String)],
+    /// };
+    /// let msg = MsgDescriptor {
+    ///     name: "Request".into(),
+    ///     fields: vec![
+    ///         field(1, FieldLabel::Optional, FieldType::Message(header)),
+    ///         field(2, FieldLabel::Optional, FieldType::UInt32),
+    ///     ],
+    /// };
+    ///
+    /// // 1 { 1: "id" } 2: 3
+    /// assert!(msg.validate(&[0x0a, 0x04, 0x0a, 0x02, b'i', b'd', 0x10, 0x03]).is_empty());
+    ///
+    /// // 1 { } 2: "x"
+    /// let violations = msg.validate(&[0x0a, 0x00, 0x12, 0x01, b'x']);
+    /// assert_eq!(
+    ///     vec![
+    ///         SchemaViolation {
+    ///             field_path: vec![1.try_into().unwrap(), 1.try_into().unwrap()],
+    ///             kind: SchemaViolationKind::MissingRequired,
+    ///         },
+    ///         SchemaViolation {
+    ///             field_path: vec![2.try_into().unwrap()],
+    ///             kind: SchemaViolationKind::UnexpectedWireType {
+    ///                 expected: WireType::VarInt,
+    ///                 actual: WireType::Len,
+    ///             },
+    ///         },
+    ///     ],
+    ///     violations
+    /// );
+    /// ```
+    pub fn validate(&self, data: &[u8]) -> Vec<SchemaViolation> {
+        let mut validator = Validator {
+            path: Vec::new(),
+            violations: Vec::new(),
+        };
+        validator.msg(Some(self), &mut MsgDecoder::new(data), None);
+        validator.violations
+    }
+}
+
+struct Validator {
+    /// Field path of the currently validated message.
+    path: Vec<FieldNumber>,
+    violations: Vec<SchemaViolation>,
+}
+
+impl Validator {
+    fn push(&mut self, field_number: Option<FieldNumber>, kind: SchemaViolationKind) {
+        let mut field_path = self.path.clone();
+        field_path.extend(field_number);
+        self.violations.push(SchemaViolation { field_path, kind });
+    }
+
+    /// Validates the records until the end of the data or the EGROUP record
+    /// of `group` (without a descriptor the records are only skipped).
+    ///
+    /// Returns false if the decoder encountered an error or the recursion
+    /// limit was reached.
+    fn msg(
+        &mut self,
+        msg: Option<&MsgDescriptor>,
+        decoder: &mut MsgDecoder<'_>,
+        group: Option<FieldNumber>,
+    ) -> bool {
+        // the path contains one entry per nesting level
+        if self.path.len() > MAX_VALIDATE_DEPTH {
+            self.push(None, SchemaViolationKind::RecursionLimit);
+            return false;
+        }
+        let mut counts: BTreeMap<FieldNumber, usize> = BTreeMap::new();
+        let mut decoded = true;
+        let mut group_ended = false;
+        while let Some(record) = decoder.next() {
+            let record = match record {
+                Ok(record) => record,
+                Err(err) => {
+                    self.push(None, SchemaViolationKind::Decode(err));
+                    decoded = false;
+                    break;
+                }
+            };
+            let number = record.field_number;
+            if record.value == WireValueRef::EGroup {
+                if Some(number) == group {
+                    group_ended = true;
+                    break;
+                }
+                self.push(Some(number), SchemaViolationKind::UnmatchedGroup);
+                continue;
+            }
+            self.path.push(number);
+            decoded = match msg.and_then(|msg| msg.field(number)) {
+                Some(field) => {
+                    *counts.entry(number).or_default() += 1;
+                    self.field(field, record.value, decoder)
+                }
+                // skip unknown groups
+                None if record.value == WireValueRef::SGroup => {
+                    self.msg(None, decoder, Some(number))
+                }
+                None => true,
+            };
+            self.path.pop();
+            if !decoded {
+                // the rest of the message can not be decoded
+                break;
+            }
+        }
+        if decoded && group.is_some() && !group_ended {
+            self.push(None, SchemaViolationKind::UnmatchedGroup);
+        }
+
+        for field in msg.map_or(&[][..], |msg| &msg.fields[..]) {
+            let count = counts.get(&field.number).copied().unwrap_or(0);
+            if field.label == FieldLabel::Required && count == 0 {
+                self.push(Some(field.number), SchemaViolationKind::MissingRequired);
+            }
+            if field.label != FieldLabel::Repeated && count > 1 {
+                self.push(
+                    Some(field.number),
+                    SchemaViolationKind::RepeatedSingular { count },
+                );
+            }
+        }
+        decoded
+    }
+
+    /// Validates a value of the field (the field number is the last entry
+    /// of the path).
+    ///
+    /// Returns false if the decoder encountered an error or the recursion
+    /// limit was reached in a group.
+    fn field(
+        &mut self,
+        field: &FieldDescriptor,
+        value: WireValueRef<'_>,
+        decoder: &mut MsgDecoder<'_>,
+    ) -> bool {
+        let expected = field.field_type.wire_type();
+        let actual = value.write_type();
+        match (&field.field_type, value) {
+            (FieldType::String, WireValueRef::Len(l)) => {
+                if let Err(err) = l.try_as_string() {
+                    self.push(None, SchemaViolationKind::InvalidUtf8(err));
+                }
+            }
+            (FieldType::Message(msg), WireValueRef::Len(l)) => {
+                self.msg(Some(msg), &mut l.as_sub_msg(), None);
+            }
+            (FieldType::Group(msg), WireValueRef::SGroup) => {
+                let number = self.path.last().copied();
+                return self.msg(Some(msg), decoder, number);
+            }
+            (FieldType::Enum(e), WireValueRef::VarInt(v)) => self.enum_value(e, v),
+            (field_type, WireValueRef::Len(l))
+                if field.label == FieldLabel::Repeated && field_type.is_packable() =>
+            {
+                self.packed(field_type, l.data);
+            }
+            _ if expected == actual => {}
+            _ => {
+                self.push(
+                    None,
+                    SchemaViolationKind::UnexpectedWireType { expected, actual },
+                );
+                if actual == WireType::SGroup {
+                    // skip the content of the group
+                    let number = self.path.last().copied();
+                    return self.msg(None, decoder, number);
+                }
+            }
+        }
+        true
+    }
+
+    fn enum_value(&mut self, e: &EnumDescriptor, value: WireVarInt) {
+        let known = match value.try_as_enum() {
+            Some(number) => !e.closed || e.value(number).is_some(),
+            None => false,
+        };
+        if !known {
+            self.push(
+                None,
+                SchemaViolationKind::UnknownEnumValue(value.as_int64()),
+            );
+        }
+    }
+
+    fn packed(&mut self, field_type: &FieldType, data: &[u8]) {
+        let mut decoder = WireDecoder::new(data);
+        while !decoder.data.is_empty() {
+            let result = match field_type.wire_type() {
+                WireType::I32 => decoder.read_fixed32().map(|_| ()),
+                WireType::I64 => decoder.read_fixed64().map(|_| ()),
+                _ => decoder.read_var_uint64().map(|value| {
+                    if let FieldType::Enum(e) = field_type {
+                        self.enum_value(e, WireVarInt::from_raw(value));
+                    }
+                }),
+            };
+            if let Err(err) = result {
+                self.push(None, SchemaViolationKind::Decode(err));
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        decode::DecodeError,
+        schema::*,
+        wire::{VarIntBytes, WireType},
+        FieldNumber,
+    };
+    use SchemaViolationKind::*;
+
+    fn fn_(v: u32) -> FieldNumber {
+        FieldNumber::try_from(v).unwrap()
+    }
+
+    fn field(number: u32, label: FieldLabel, field_type: FieldType) -> FieldDescriptor {
+        FieldDescriptor {
+            name: format!("field_{}", number),
+            number: fn_(number),
+            label,
+            field_type,
+            packed: false,
+        }
+    }
+
+    fn color(closed: bool) -> FieldType {
+        FieldType::Enum(EnumDescriptor {
+            name: "Color".into(),
+            values: vec![
+                EnumValueDescriptor {
+                    name: "RED".into(),
+                    number: 0,
+                },
+                EnumValueDescriptor {
+                    name: "GREEN".into(),
+                    number: -1,
+                },
+            ],
+            closed,
+        })
+    }
+
+    fn schema() -> MsgDescriptor {
+        use FieldLabel::*;
+        let inner = MsgDescriptor {
+            name: "Inner".into(),
+            fields: vec![field(1, Required, FieldType::Bool)],
+        };
+        MsgDescriptor {
+            name: "Msg".into(),
+            fields: vec![
+                field(1, Required, FieldType::String),
+                field(2, Repeated, FieldType::SInt32),
+                field(3, Optional, color(true)),
+                field(4, Repeated, color(false)),
+                field(5, Optional, FieldType::Message(inner.clone())),
+                field(6, Optional, FieldType::Group(inner)),
+                field(7, Optional, FieldType::Fixed32),
+            ],
+        }
+    }
+
+    fn violation(path: &[u32], kind: SchemaViolationKind) -> SchemaViolation {
+        SchemaViolation {
+            field_path: path.iter().map(|n| fn_(*n)).collect(),
+            kind,
+        }
+    }
+
+    #[test]
+    fn valid() {
+        let schema = schema();
+        let data = [
+            0x0a, 0x01, b'a', // 1: "a"
+            0x10, 0x01, 0x12, 0x02, 0x02, 0x03, // 2: unpacked & packed
+            0x18, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01, // 3: GREEN
+            0x22, 0x02, 0x05, 0x06, // 4: open enum, undeclared values
+            0x2a, 0x02, 0x08, 0x01, // 5 { 1: true }
+            0x33, 0x08, 0x00, 0x34, // 6 group { 1: false }
+            0x3d, 1, 0, 0, 0, // 7: fixed32
+            0x40, 0x01, 0x4b, 0x08, 0x01, 0x4c, // unknown fields & groups
+        ];
+        assert_eq!(Vec::<SchemaViolation>::new(), schema.validate(&data));
+    }
+
+    #[test]
+    fn violations() {
+        let schema = schema();
+
+        // missing required field (also in sub-message & group)
+        assert_eq!(
+            vec![
+                violation(&[5, 1], MissingRequired),
+                violation(&[6, 1], MissingRequired),
+                violation(&[1], MissingRequired),
+            ],
+            schema.validate(&[0x2a, 0x00, 0x33, 0x34])
+        );
+
+        // wrong wire types, packed singular field & skipped group
+        assert_eq!(
+            vec![
+                violation(
+                    &[7],
+                    UnexpectedWireType {
+                        expected: WireType::I32,
+                        actual: WireType::Len,
+                    }
+                ),
+                violation(
+                    &[5],
+                    UnexpectedWireType {
+                        expected: WireType::Len,
+                        actual: WireType::SGroup,
+                    }
+                ),
+                violation(
+                    &[1],
+                    UnexpectedWireType {
+                        expected: WireType::Len,
+                        actual: WireType::VarInt,
+                    }
+                ),
+            ],
+            schema.validate(&[0x3a, 0x04, 1, 0, 0, 0, 0x2b, 0x08, 0x01, 0x2c, 0x08, 0x01])
+        );
+
+        // repeated singular field, unknown closed enum value & invalid UTF-8
+        let data = [
+            0x0a, 0x01, 0xff, 0x0a, 0x00, // 1: invalid & empty string
+            0x18, 0x02, // 3: unknown value
+            0x22, 0x01, 0x07, // 4: open enum
+        ];
+        let utf8_err = core::str::from_utf8(&data[2..3]).unwrap_err();
+        assert_eq!(
+            vec![
+                violation(&[1], InvalidUtf8(utf8_err)),
+                violation(&[3], UnknownEnumValue(2)),
+                violation(&[1], RepeatedSingular { count: 2 }),
+            ],
+            schema.validate(&data)
+        );
+        // value out of the enum range
+        assert_eq!(
+            vec![violation(&[3], UnknownEnumValue(1 << 32))],
+            schema.validate(&[0x0a, 0x00, 0x18, 0x80, 0x80, 0x80, 0x80, 0x10])
+        );
+    }
+
+    #[test]
+    fn decode_errors() {
+        let schema = schema();
+
+        // truncated record, in a sub-message & in packed values
+        assert_eq!(
+            vec![
+                violation(&[], Decode(DecodeError::Len)),
+                violation(&[1], MissingRequired)
+            ],
+            schema.validate(&[0x0a, 0x05])
+        );
+        assert_eq!(
+            vec![
+                violation(&[5], Decode(DecodeError::Len)),
+                violation(&[5, 1], MissingRequired),
+            ],
+            schema.validate(&[0x0a, 0x00, 0x2a, 0x01, 0x08])
+        );
+        assert_eq!(
+            vec![violation(&[2], Decode(DecodeError::Len))],
+            schema.validate(&[0x0a, 0x00, 0x12, 0x01, 0x80])
+        );
+
+        // unterminated & unmatched groups
+        assert_eq!(
+            vec![
+                violation(&[6], UnmatchedGroup),
+                violation(&[6, 1], MissingRequired)
+            ],
+            schema.validate(&[0x0a, 0x00, 0x33])
+        );
+        assert_eq!(
+            vec![violation(&[6], UnmatchedGroup)],
+            schema.validate(&[0x0a, 0x00, 0x34])
+        );
+    }
+
+    #[test]
+    fn recursion_limit() {
+        let msg = MsgDescriptor {
+            name: "Msg".into(),
+            fields: vec![],
+        };
+
+        // nested unknown groups up to the limit are skipped
+        let mut data = vec![0x0b; super::MAX_VALIDATE_DEPTH];
+        data.extend(vec![0x0c; super::MAX_VALIDATE_DEPTH]);
+        assert_eq!(Vec::<SchemaViolation>::new(), msg.validate(&data));
+
+        // a million nested unterminated groups
+        assert_eq!(
+            vec![violation(
+                &[1; super::MAX_VALIDATE_DEPTH + 1],
+                RecursionLimit
+            )],
+            msg.validate(&[0x0b; 1_000_000])
+        );
+
+        // embedded messages nested one level too deep
+        let mut schema = msg.clone();
+        let mut data = Vec::new();
+        for _ in 0..=super::MAX_VALIDATE_DEPTH {
+            schema = MsgDescriptor {
+                name: "Msg".into(),
+                fields: vec![field(1, FieldLabel::Optional, FieldType::Message(schema))],
+            };
+            let mut outer = vec![0x0a];
+            outer.extend_from_slice(VarIntBytes::new(data.len() as u64).as_slice());
+            outer.extend(data);
+            data = outer;
+        }
+        assert_eq!(
+            vec![violation(
+                &[1; super::MAX_VALIDATE_DEPTH + 1],
+                RecursionLimit
+            )],
+            schema.validate(&data)
+        );
+    }
+}
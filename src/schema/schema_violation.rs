@@ -0,0 +1,11 @@
+use crate::{schema::*, FieldNumber};
+use alloc::vec::Vec;
+
+/// Violation of a schema found by [`MsgDescriptor::validate`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SchemaViolation {
+    /// Field numbers from the top level message to the violating field
+    /// (empty for violations of the top level message itself).
+    pub field_path: Vec<FieldNumber>,
+    pub kind: SchemaViolationKind,
+}
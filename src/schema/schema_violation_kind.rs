@@ -0,0 +1,30 @@
+use crate::{decode::DecodeError, wire::WireType};
+use core::str::Utf8Error;
+
+/// Kind of a [`SchemaViolation`](crate::schema::SchemaViolation).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SchemaViolationKind {
+    /// The data of the message could not be decoded (the rest of the
+    /// message is not validated).
+    Decode(DecodeError),
+    /// A record has a different wire type than the declared field type
+    /// (packed values are only accepted for repeated fields).
+    UnexpectedWireType {
+        expected: WireType,
+        actual: WireType,
+    },
+    /// A `required` field is not present.
+    MissingRequired,
+    /// A non repeated field is present multiple times.
+    RepeatedSingular { count: usize },
+    /// A value of a closed enum is not declared.
+    UnknownEnumValue(i64),
+    /// A `string` value is not valid UTF-8.
+    InvalidUtf8(Utf8Error),
+    /// A group is not terminated or a group end has no matching start.
+    UnmatchedGroup,
+    /// Embedded messages or groups are nested deeper than the supported
+    /// limit of 100 (their content is not validated, after a group the
+    /// rest of the enclosing messages neither).
+    RecursionLimit,
+}
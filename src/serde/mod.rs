@@ -0,0 +1,10 @@
+mod msg_deserializer;
+pub use msg_deserializer::*;
+
+mod msg_serializer;
+pub use msg_serializer::*;
+
+mod raw_serialize;
+
+mod serde_error;
+pub use serde_error::*;
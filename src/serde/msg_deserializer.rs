@@ -0,0 +1,1015 @@
+use crate::{
+    decode::*,
+    serde::{enum_value, field_number, SerdeError},
+    wire::*,
+    FieldNumber,
+};
+use ::serde::de::{
+    self, value::StrDeserializer, Deserialize, DeserializeSeed, IntoDeserializer, Visitor,
+};
+use alloc::{vec, vec::Vec};
+
+/// Maximum nesting depth of sub-messages accepted while decoding (same as
+/// the default recursion limit of the protobuf reference implementation).
+const MAX_DECODE_DEPTH: usize = 100;
+
+/// Decodes a value from a protobuf message.
+///
+/// Fields missing in the message are decoded as their protobuf default
+/// value (e.g. `0`, `""`, `None` or an empty `Vec`), unknown fields are
+/// ignored. If a non repeated field is present multiple times the last one
+/// wins (sub-messages are merged).
+///
+/// ```
+/// #[derive(serde::Deserialize, Debug, PartialEq)]
+/// struct Msg<'a> {
+///     a: u32,
+///     b: &'a str,
+///     c: Vec<u64>,
+/// }
+///
+/// let bytes = [0x08, 0x03, 0x12, 0x01, b'x'];
+/// assert_eq!(
+///     Msg { a: 3, b: "x", c: vec![] },
+///     protobin::serde::from_slice(&bytes).unwrap()
+/// );
+/// ```
+pub fn from_slice<'de, T: Deserialize<'de>>(data: &'de [u8]) -> Result<T, SerdeError> {
+    T::deserialize(MsgDeserializer::new(data))
+}
+
+/// Deserializer reading a value from the records of a message.
+pub struct MsgDeserializer<'de> {
+    /// Encoded message parts (multiple parts are merged).
+    chunks: Vec<&'de [u8]>,
+    /// Nesting depth of the message (0 for the top level message).
+    depth: usize,
+}
+
+impl<'de> MsgDeserializer<'de> {
+    /// Creates a deserializer for the given encoded message.
+    pub fn new(data: &'de [u8]) -> MsgDeserializer<'de> {
+        MsgDeserializer {
+            chunks: vec![data],
+            depth: 0,
+        }
+    }
+
+    fn records(&self) -> Result<Vec<MsgRecordRef<'de>>, SerdeError> {
+        let mut result = Vec::new();
+        for chunk in &self.chunks {
+            for record in MsgDecoder::new(chunk) {
+                result.push(record?);
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Returns all values of the given field in the records.
+fn values<'de>(records: &[MsgRecordRef<'de>], field_number: FieldNumber) -> Vec<WireValueRef<'de>> {
+    records
+        .iter()
+        .filter(|r| r.field_number == field_number)
+        .map(|r| r.value)
+        .collect()
+}
+
+impl<'de> de::Deserializer<'de> for MsgDeserializer<'de> {
+    type Error = SerdeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, SerdeError> {
+        Err(SerdeError::Unsupported(
+            "message has to be a struct, tuple or enum",
+        ))
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        visitor.visit_seq(TupleAccess {
+            records: self.records()?,
+            index: 0,
+            len,
+            depth: self.depth,
+        })
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        visitor.visit_map(StructAccess {
+            records: self.records()?,
+            fields,
+            index: 0,
+            depth: self.depth,
+        })
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        // the last field matching a variant wins (like for oneof fields)
+        let records = self.records()?;
+        for record in records.iter().rev() {
+            for (index, variant) in variants.iter().enumerate() {
+                if field_number(variant, index as u32)? == record.field_number {
+                    return visitor.visit_enum(VariantAccess {
+                        variant,
+                        field: FieldDeserializer {
+                            field_number: record.field_number,
+                            values: values(&records, record.field_number),
+                            depth: self.depth,
+                        },
+                    });
+                }
+            }
+        }
+        Err(SerdeError::UnknownVariant)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_unit()
+    }
+
+    ::serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf seq map identifier
+    }
+}
+
+/// Deserializer reading the values of a field.
+pub struct FieldDeserializer<'de> {
+    field_number: FieldNumber,
+    /// All values of the field in the order they were decoded.
+    values: Vec<WireValueRef<'de>>,
+    /// Nesting depth of the message containing the field.
+    depth: usize,
+}
+
+impl<'de> FieldDeserializer<'de> {
+    /// Returns the last value converted via `f` or the default value if
+    /// the field is not present.
+    fn scalar<T: Default>(
+        &self,
+        f: impl Fn(&WireValueRef<'de>) -> Result<T, WireValueIntoError>,
+    ) -> Result<T, SerdeError> {
+        match self.values.last() {
+            Some(value) => f(value).map_err(SerdeError::value(self.field_number)),
+            None => Ok(T::default()),
+        }
+    }
+
+    fn bytes(&self) -> Result<&'de [u8], SerdeError> {
+        self.scalar(|v| v.try_as_len().map(|l| l.as_bytes()))
+    }
+
+    fn str(&self) -> Result<&'de str, SerdeError> {
+        Ok(core::str::from_utf8(self.bytes()?)?)
+    }
+
+    /// Interprets all values as sub-messages (they are merged).
+    ///
+    /// Returns [`SerdeError::RecursionLimit`] if the sub-message would be
+    /// nested deeper than [`MAX_DECODE_DEPTH`].
+    fn into_msg(self) -> Result<MsgDeserializer<'de>, SerdeError> {
+        let chunks = self
+            .values
+            .iter()
+            .map(|v| v.try_as_len().map(|l| l.as_bytes()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(SerdeError::value(self.field_number))?;
+        let depth = self.depth + 1;
+        if depth > MAX_DECODE_DEPTH {
+            return Err(SerdeError::RecursionLimit);
+        }
+        Ok(MsgDeserializer { chunks, depth })
+    }
+}
+
+impl<'de> de::Deserializer<'de> for FieldDeserializer<'de> {
+    type Error = SerdeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        if self.values.len() > 1 {
+            return self.deserialize_seq(visitor);
+        }
+        match self.values.last() {
+            None | Some(WireValueRef::SGroup) | Some(WireValueRef::EGroup) => visitor.visit_unit(),
+            Some(WireValueRef::VarInt(v)) => visitor.visit_u64(v.as_uint64()),
+            Some(WireValueRef::I64(v)) => visitor.visit_u64(v.0),
+            Some(WireValueRef::I32(v)) => visitor.visit_u32(v.0),
+            Some(WireValueRef::Len(v)) => visitor.visit_borrowed_bytes(v.as_bytes()),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_bool(self.scalar(WireValueRef::try_as_bool)?)
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_i32(visitor)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_i32(visitor)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_i32(self.scalar(WireValueRef::try_as_int32)?)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_i64(self.scalar(WireValueRef::try_as_int64)?)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_u32(visitor)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_u32(visitor)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_u32(self.scalar(WireValueRef::try_as_uint32)?)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_u64(self.scalar(WireValueRef::try_as_uint64)?)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_f32(self.scalar(WireValueRef::as_float)?)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_f64(self.scalar(WireValueRef::as_double)?)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_borrowed_str(self.str()?)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_borrowed_str(self.str()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_borrowed_str(self.str()?)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_borrowed_bytes(self.bytes()?)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_borrowed_bytes(self.bytes()?)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        if self.values.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_seq(RepeatedAccess {
+            field_number: self.field_number,
+            values: self.values.into_iter(),
+            packed: &[],
+            only_empty_packed: false,
+            depth: self.depth,
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.into_msg()?.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.into_msg()?
+            .deserialize_tuple_struct(name, len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_map(EntryAccess {
+            field_number: self.field_number,
+            entries: self.values.into_iter(),
+            value: None,
+            depth: self.depth,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.into_msg()?.deserialize_struct(name, fields, visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        // variants with data are encoded as sub-message
+        if let Some(WireValueRef::Len(_)) = self.values.last() {
+            return self.into_msg()?.deserialize_enum(name, variants, visitor);
+        }
+        let value = self.scalar(WireValueRef::try_as_enum)?;
+        let variant = variants
+            .iter()
+            .enumerate()
+            .find(|(index, variant)| enum_value(variant, *index as u32) == value)
+            .map(|(_, variant)| *variant)
+            .ok_or(SerdeError::UnknownVariant)?;
+        visitor.visit_enum(VariantAccess {
+            variant,
+            field: FieldDeserializer {
+                field_number: self.field_number,
+                values: Vec::new(),
+                depth: self.depth,
+            },
+        })
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_unit()
+    }
+}
+
+/// Access to the fields of a struct (all declared fields are returned,
+/// missing ones with no values).
+struct StructAccess<'de> {
+    records: Vec<MsgRecordRef<'de>>,
+    fields: &'static [&'static str],
+    index: usize,
+    /// Nesting depth of the message.
+    depth: usize,
+}
+
+impl<'de> de::MapAccess<'de> for StructAccess<'de> {
+    type Error = SerdeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, SerdeError> {
+        match self.fields.get(self.index) {
+            Some(name) => {
+                let key: StrDeserializer<'_, SerdeError> = name.into_deserializer();
+                seed.deserialize(key).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, SerdeError> {
+        let field_number = field_number(self.fields[self.index], self.index as u32)?;
+        self.index += 1;
+        seed.deserialize(FieldDeserializer {
+            field_number,
+            values: values(&self.records, field_number),
+            depth: self.depth,
+        })
+    }
+}
+
+/// Access to the fields of a tuple.
+struct TupleAccess<'de> {
+    records: Vec<MsgRecordRef<'de>>,
+    index: usize,
+    len: usize,
+    /// Nesting depth of the message.
+    depth: usize,
+}
+
+impl<'de> de::SeqAccess<'de> for TupleAccess<'de> {
+    type Error = SerdeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, SerdeError> {
+        if self.index >= self.len {
+            return Ok(None);
+        }
+        let field_number = field_number("", self.index as u32)?;
+        self.index += 1;
+        seed.deserialize(FieldDeserializer {
+            field_number,
+            values: values(&self.records, field_number),
+            depth: self.depth,
+        })
+        .map(Some)
+    }
+}
+
+/// Access to the elements of a repeated field (packed & non packed).
+struct RepeatedAccess<'de> {
+    field_number: FieldNumber,
+    values: vec::IntoIter<WireValueRef<'de>>,
+    /// Not yet decoded part of a packed field.
+    packed: &'de [u8],
+    /// Set if a scalar element was requested but only empty packed fields
+    /// were left (the sequence ends instead of reporting an error).
+    only_empty_packed: bool,
+    /// Nesting depth of the message containing the field.
+    depth: usize,
+}
+
+impl<'de> RepeatedAccess<'de> {
+    /// Returns the next value (packed values are not supported).
+    fn next_value(&mut self) -> Result<WireValueRef<'de>, SerdeError> {
+        if !self.packed.is_empty() {
+            return Err(SerdeError::Unsupported("packed values of non scalar types"));
+        }
+        self.values
+            .next()
+            .ok_or(SerdeError::Decode(DecodeError::Len))
+    }
+
+    /// Returns the next scalar value (LEN values are decoded as packed
+    /// values of the given wire type).
+    fn next_scalar(&mut self, wire_type: WireType) -> Result<WireValueRef<'de>, SerdeError> {
+        loop {
+            if !self.packed.is_empty() {
+                let mut decoder = WireDecoder::new(self.packed);
+                let value = match wire_type {
+                    WireType::I32 => WireValueRef::I32(WireI32(decoder.read_fixed32()?)),
+                    WireType::I64 => WireValueRef::I64(WireI64(decoder.read_fixed64()?)),
+                    _ => WireValueRef::VarInt(WireVarInt::from_raw(decoder.read_var_uint64()?)),
+                };
+                self.packed = decoder.data;
+                return Ok(value);
+            }
+            match self.values.next() {
+                Some(WireValueRef::Len(l)) => self.packed = l.as_bytes(),
+                Some(value) => return Ok(value),
+                // only reached if the last values were empty packed fields
+                None => {
+                    self.only_empty_packed = true;
+                    return Err(SerdeError::Decode(DecodeError::Len));
+                }
+            }
+        }
+    }
+
+    fn single(&self, value: WireValueRef<'de>) -> FieldDeserializer<'de> {
+        FieldDeserializer {
+            field_number: self.field_number,
+            values: vec![value],
+            depth: self.depth,
+        }
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for RepeatedAccess<'de> {
+    type Error = SerdeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, SerdeError> {
+        if self.packed.is_empty() && self.values.len() == 0 {
+            return Ok(None);
+        }
+        // empty LEN values are only known to be empty packed fields (and
+        // not e.g. empty strings) once a scalar element is requested
+        match seed.deserialize(ElementDeserializer { access: self }) {
+            Ok(value) => Ok(Some(value)),
+            Err(_) if self.only_empty_packed => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Deserializer for an element of a repeated field.
+struct ElementDeserializer<'a, 'de> {
+    access: &'a mut RepeatedAccess<'de>,
+}
+
+macro_rules! scalar_element {
+    ($($f:ident: $wire_type:ident,)*) => {
+        $(
+            fn $f<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+                let value = self.access.next_scalar(WireType::$wire_type)?;
+                self.access.single(value).$f(visitor)
+            }
+        )*
+    };
+}
+
+macro_rules! other_element {
+    ($($f:ident($($arg:ident: $t:ty),*),)*) => {
+        $(
+            fn $f<V: Visitor<'de>>(self, $($arg: $t,)* visitor: V) -> Result<V::Value, SerdeError> {
+                let value = self.access.next_value()?;
+                self.access.single(value).$f($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'a, 'de> de::Deserializer<'de> for ElementDeserializer<'a, 'de> {
+    type Error = SerdeError;
+
+    scalar_element! {
+        deserialize_bool: VarInt,
+        deserialize_i8: VarInt,
+        deserialize_i16: VarInt,
+        deserialize_i32: VarInt,
+        deserialize_i64: VarInt,
+        deserialize_u8: VarInt,
+        deserialize_u16: VarInt,
+        deserialize_u32: VarInt,
+        deserialize_u64: VarInt,
+        deserialize_f32: I32,
+        deserialize_f64: I64,
+    }
+
+    other_element! {
+        deserialize_any(),
+        deserialize_char(),
+        deserialize_str(),
+        deserialize_string(),
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_option(),
+        deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_seq(),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier(),
+        deserialize_ignored_any(),
+    }
+}
+
+/// Access to the entries of a `map<K, V>` field.
+struct EntryAccess<'de> {
+    field_number: FieldNumber,
+    entries: vec::IntoIter<WireValueRef<'de>>,
+    /// Value of the entry whose key was returned last.
+    value: Option<WireValueRef<'de>>,
+    /// Nesting depth of the message containing the field.
+    depth: usize,
+}
+
+impl<'de> de::MapAccess<'de> for EntryAccess<'de> {
+    type Error = SerdeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, SerdeError> {
+        let Some(entry) = self.entries.next() else {
+            return Ok(None);
+        };
+        let entry = entry
+            .try_as_len()
+            .map_err(SerdeError::value(self.field_number))?
+            .try_as_map_entry()?;
+        self.value = entry.value;
+        seed.deserialize(FieldDeserializer {
+            field_number: FieldNumber::MAP_KEY,
+            values: entry.key.into_iter().collect(),
+            depth: self.depth + 1,
+        })
+        .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, SerdeError> {
+        seed.deserialize(FieldDeserializer {
+            field_number: FieldNumber::MAP_VALUE,
+            values: self.value.take().into_iter().collect(),
+            depth: self.depth + 1,
+        })
+    }
+}
+
+/// Access to the variant of an enum & its data.
+struct VariantAccess<'de> {
+    variant: &'static str,
+    /// Values of the field of the variant (empty for protobuf enum values).
+    field: FieldDeserializer<'de>,
+}
+
+impl<'de> de::EnumAccess<'de> for VariantAccess<'de> {
+    type Error = SerdeError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self), SerdeError> {
+        let key: StrDeserializer<'_, SerdeError> = self.variant.into_deserializer();
+        Ok((seed.deserialize(key)?, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for VariantAccess<'de> {
+    type Error = SerdeError;
+
+    fn unit_variant(self) -> Result<(), SerdeError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, SerdeError> {
+        seed.deserialize(self.field)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        de::Deserializer::deserialize_tuple(self.field, len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        de::Deserializer::deserialize_struct(self.field, "", fields, visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{builders::*, serde::to_vec};
+    use ::serde::{Deserialize, Serialize};
+    use alloc::{boxed::Box, collections::BTreeMap, string::String};
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+    struct Inner {
+        a: i32,
+        #[serde(rename = "5")]
+        b: String,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    enum Kind {
+        Empty,
+        #[serde(rename = "7")]
+        Seven,
+        Num(u64),
+        Pair(bool, f32),
+        Named {
+            x: Option<u32>,
+        },
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    struct Outer<'a> {
+        scalar: u32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        skipped: Option<u32>,
+        inner: Inner,
+        repeated: Vec<i64>,
+        map: BTreeMap<u8, String>,
+        kinds: Vec<Kind>,
+        borrowed: &'a str,
+        tuple: (char, f64),
+        small: i8,
+        kind: Kind,
+    }
+
+    fn fn_(v: u32) -> FieldNumber {
+        FieldNumber::try_from(v).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let value = Outer {
+            scalar: 300,
+            skipped: Some(2),
+            inner: Inner {
+                a: -1,
+                b: "x".into(),
+            },
+            repeated: vec![1, -2],
+            map: [(1, "a".into()), (2, "b".into())].into_iter().collect(),
+            kinds: vec![
+                Kind::Empty,
+                Kind::Seven,
+                Kind::Num(3),
+                Kind::Pair(true, 1.5),
+                Kind::Named { x: Some(4) },
+                Kind::Named { x: None },
+            ],
+            borrowed: "y",
+            tuple: ('ä', 2.5),
+            small: -3,
+            kind: Kind::Num(0),
+        };
+        let bytes = to_vec(&value).unwrap();
+        assert_eq!(Ok(value.clone()), from_slice::<Outer>(&bytes));
+
+        // skipped field
+        let value = Outer {
+            skipped: None,
+            ..value
+        };
+        let bytes = to_vec(&value).unwrap();
+        assert_eq!(Ok(value), from_slice::<Outer>(&bytes));
+
+        // top level enum
+        for kind in [Kind::Empty, Kind::Num(1), Kind::Named { x: Some(2) }] {
+            let bytes = to_vec(&kind).unwrap();
+            assert_eq!(Ok(kind), from_slice::<Kind>(&bytes));
+        }
+    }
+
+    #[test]
+    fn defaults() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Msg {
+            a: u32,
+            b: String,
+            c: Vec<u32>,
+            d: Option<Inner>,
+            e: Inner,
+            f: Kind,
+            g: Option<Kind>,
+        }
+        assert_eq!(
+            Ok(Msg {
+                a: 0,
+                b: String::new(),
+                c: Vec::new(),
+                d: None,
+                e: Inner::default(),
+                f: Kind::Empty,
+                g: None,
+            }),
+            from_slice::<Msg>(&[])
+        );
+    }
+
+    #[test]
+    fn packed_merged_unknown() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Msg {
+            a: Vec<u32>,
+            b: Vec<f32>,
+            c: Inner,
+            d: u32,
+        }
+        fn ser<S: MsgScribe>(s: &mut S) {
+            s.start_packed(fn_(1)).add_uint32(1).add_uint32(300);
+            s.end_packed(fn_(1))
+                .add_uint32(fn_(1), 2)
+                .add_float(fn_(2), 0.5);
+            s.start_packed(fn_(2)).add_float(1.5);
+            s.end_packed(fn_(2))
+                .start_msg(fn_(3))
+                .add_int32(fn_(1), 1)
+                .end_msg(fn_(3))
+                .add_string(fn_(9), "unknown")
+                .add_uint32(fn_(4), 1)
+                .add_uint32(fn_(4), 2)
+                .start_msg(fn_(3))
+                .add_string(fn_(5), "z")
+                .end_msg(fn_(3));
+        }
+        let mut builder = MsgBuilder::new();
+        let mut len = builder.start(None);
+        ser(&mut len);
+        let mut s = len.end();
+        ser(&mut s);
+        let bytes = s.end().to_vec();
+        assert_eq!(
+            Ok(Msg {
+                a: vec![1, 300, 2],
+                b: vec![0.5, 1.5],
+                c: Inner {
+                    a: 1,
+                    b: "z".into()
+                },
+                d: 2,
+            }),
+            from_slice::<Msg>(&bytes)
+        );
+    }
+
+    #[test]
+    fn empty_packed() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Msg {
+            a: u64,
+            c: Vec<u64>,
+        }
+        assert_eq!(
+            Ok(Msg { a: 1, c: vec![] }),
+            from_slice::<Msg>(&[0x08, 0x01, 0x12, 0x00])
+        );
+        // empty packed fields between & after packed values
+        assert_eq!(
+            Ok(Msg {
+                a: 1,
+                c: vec![2, 3]
+            }),
+            from_slice::<Msg>(&[
+                0x08, 0x01, 0x12, 0x00, 0x12, 0x01, 0x02, 0x12, 0x00, 0x10, 0x03, 0x12, 0x00
+            ])
+        );
+
+        // empty LEN values of non scalar elements are kept
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Strings {
+            a: Vec<String>,
+        }
+        assert_eq!(
+            Ok(Strings {
+                a: vec!["".into(), "".into()]
+            }),
+            from_slice::<Strings>(&[0x0a, 0x00, 0x0a, 0x00])
+        );
+    }
+
+    #[test]
+    fn error() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Msg {
+            a: String,
+            b: u8,
+            c: Kind,
+        }
+        // wrong wire type
+        assert_eq!(
+            Err(SerdeError::Value {
+                field_number: fn_(1),
+                err: WireValueIntoError::UnexpectedType {
+                    expected: WireType::Len,
+                    actual: WireType::VarInt
+                }
+            }),
+            from_slice::<Msg>(&[0x08, 0x01])
+        );
+        // invalid utf8
+        assert!(matches!(
+            from_slice::<Msg>(&[0x0a, 0x01, 0xff]),
+            Err(SerdeError::Utf8(_))
+        ));
+        // out of range
+        assert!(matches!(
+            from_slice::<Msg>(&[0x10, 0xac, 0x02]),
+            Err(SerdeError::Custom(_))
+        ));
+        // unknown enum value
+        assert_eq!(
+            Err(SerdeError::UnknownVariant),
+            from_slice::<Msg>(&[0x18, 0x05])
+        );
+        // broken record
+        assert_eq!(
+            Err(SerdeError::Decode(DecodeError::Len)),
+            from_slice::<Msg>(&[0x0a, 0x05])
+        );
+        // not a message
+        assert_eq!(
+            Err(SerdeError::Unsupported(
+                "message has to be a struct, tuple or enum"
+            )),
+            from_slice::<u32>(&[])
+        );
+    }
+
+    /// Message with `depth` nested sub-messages in field 1.
+    fn nested(depth: usize) -> Vec<u8> {
+        // content lengths from the innermost message outwards
+        let mut lens = vec![0usize];
+        for _ in 0..depth {
+            let len = *lens.last().unwrap();
+            lens.push(1 + VarIntBytes::new(len as u64).as_slice().len() + len);
+        }
+        let mut data = Vec::with_capacity(lens[depth]);
+        for len in lens[..depth].iter().rev() {
+            data.push(0x0a);
+            data.extend_from_slice(VarIntBytes::new(*len as u64).as_slice());
+        }
+        data
+    }
+
+    #[test]
+    fn recursion_limit() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Node {
+            child: Option<Box<Node>>,
+            v: u32,
+        }
+        impl Node {
+            fn depth(&self) -> usize {
+                self.child.as_ref().map_or(0, |c| c.depth() + 1)
+            }
+        }
+
+        let node: Node = from_slice(&nested(MAX_DECODE_DEPTH)).unwrap();
+        assert_eq!(MAX_DECODE_DEPTH, node.depth());
+        assert_eq!(
+            Err(SerdeError::RecursionLimit),
+            from_slice::<Node>(&nested(MAX_DECODE_DEPTH + 1))
+        );
+        // deeply nested input does not overflow the stack
+        assert_eq!(
+            Err(SerdeError::RecursionLimit),
+            from_slice::<Node>(&nested(200_000))
+        );
+    }
+}
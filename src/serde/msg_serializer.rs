@@ -0,0 +1,769 @@
+use crate::{builders::*, serde::SerdeError, FieldNumber};
+use ::serde::ser::{self, Impossible, Serialize};
+use alloc::vec::Vec;
+
+/// Encodes a value as protobuf message.
+///
+/// The value has to be a struct, tuple or enum (see the [module
+/// documentation](crate::serde) for how the serde data model is mapped).
+///
+/// ```
+/// #[derive(serde::Serialize)]
+/// struct Msg<'a> {
+///     a: u32,     // field 1
+///     b: &'a str, // field 2
+/// }
+///
+/// let bytes = protobin::serde::to_vec(&Msg { a: 3, b: "x" }).unwrap();
+/// assert_eq!(bytes, [0x08, 0x03, 0x12, 0x01, b'x']);
+/// ```
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, SerdeError> {
+    let mut builder = MsgBuilder::new();
+    to_builder(&mut builder, value).map(|bytes| bytes.to_vec())
+}
+
+/// Encodes a value as protobuf message using the given builder (the
+/// builder should be reused to avoid allocations).
+///
+/// The value is serialized twice, once with the length builder & once with
+/// the serialization builder (see [`MsgBuilder::try_start`]).
+pub fn to_builder<'a, T: Serialize + ?Sized>(
+    builder: &'a mut MsgBuilder,
+    value: &T,
+) -> Result<&'a [u8], SerdeError> {
+    let mut len = builder.try_start(None);
+    ser(value, &mut len)?;
+    let mut s = len.end()?;
+    ser(value, &mut s)?;
+    Ok(s.end()?)
+}
+
+/// Adds the fields of a value to the given scribe.
+///
+/// Can be used to serialize serde types as part of a hand written
+/// serialization function (e.g. as content of a sub-message between
+/// `start_msg` & `end_msg`).
+pub fn ser<S: MsgScribe, T: Serialize + ?Sized>(value: &T, s: &mut S) -> Result<(), SerdeError> {
+    value.serialize(MsgSerializer::new(s))
+}
+
+/// Returns the field number of a struct field or enum variant.
+///
+/// Names that are a number (e.g. set via `#[serde(rename = "5")]`) are used
+/// as field number, otherwise the field number is the position plus one.
+pub(crate) fn field_number(name: &str, index: u32) -> Result<FieldNumber, SerdeError> {
+    let value = name.parse::<u64>().unwrap_or(u64::from(index) + 1);
+    u32::try_from(value)
+        .ok()
+        .and_then(|v| FieldNumber::try_from(v).ok())
+        .ok_or(SerdeError::InvalidFieldNumber(value))
+}
+
+/// Returns the protobuf enum value of an unit variant.
+///
+/// Names that are a number are used as value, otherwise the position of
+/// the variant is used.
+pub(crate) fn enum_value(name: &str, index: u32) -> i32 {
+    name.parse::<i32>().unwrap_or(index as i32)
+}
+
+/// Serializer writing the fields of a message to a [`MsgScribe`].
+pub struct MsgSerializer<'s, S> {
+    s: &'s mut S,
+    /// Sub-message that has to be ended after the value is serialized.
+    end: Option<FieldNumber>,
+}
+
+impl<'s, S: MsgScribe> MsgSerializer<'s, S> {
+    /// Creates a serializer adding the fields of a value to the scribe.
+    pub fn new(s: &'s mut S) -> MsgSerializer<'s, S> {
+        MsgSerializer { s, end: None }
+    }
+
+    fn close(self) -> Result<(), SerdeError> {
+        if let Some(field_number) = self.end {
+            self.s.end_msg(field_number);
+        }
+        Ok(())
+    }
+
+    fn fields(self, start: Option<FieldNumber>) -> FieldsSerializer<'s, S> {
+        if let Some(field_number) = start {
+            self.s.start_msg(field_number);
+        }
+        FieldsSerializer {
+            s: self.s,
+            next_index: 0,
+            ends: [start, self.end],
+        }
+    }
+}
+
+const NOT_A_MSG: &str = "message has to be a struct, tuple or enum";
+
+macro_rules! not_a_msg {
+    ($($f:ident: $t:ty,)*) => {
+        $(
+            fn $f(self, _: $t) -> Result<(), SerdeError> {
+                Err(SerdeError::Unsupported(NOT_A_MSG))
+            }
+        )*
+    };
+}
+
+impl<'s, S: MsgScribe> ser::Serializer for MsgSerializer<'s, S> {
+    type Ok = ();
+    type Error = SerdeError;
+    type SerializeSeq = Impossible<(), SerdeError>;
+    type SerializeTuple = FieldsSerializer<'s, S>;
+    type SerializeTupleStruct = FieldsSerializer<'s, S>;
+    type SerializeTupleVariant = FieldsSerializer<'s, S>;
+    type SerializeMap = Impossible<(), SerdeError>;
+    type SerializeStruct = FieldsSerializer<'s, S>;
+    type SerializeStructVariant = FieldsSerializer<'s, S>;
+
+    not_a_msg! {
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_char: char,
+        serialize_str: &str,
+        serialize_bytes: &[u8],
+    }
+
+    fn serialize_none(self) -> Result<(), SerdeError> {
+        self.close()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), SerdeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), SerdeError> {
+        self.close()
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), SerdeError> {
+        self.close()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), SerdeError> {
+        // empty sub-message in the field of the variant
+        let field_number = field_number(variant, variant_index)?;
+        self.s.start_msg(field_number).end_msg(field_number);
+        self.close()
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        value.serialize(FieldSerializer {
+            s: &mut *self.s,
+            field_number: field_number(variant, variant_index)?,
+            in_seq: false,
+        })?;
+        self.close()
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, SerdeError> {
+        Err(SerdeError::Unsupported(NOT_A_MSG))
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<FieldsSerializer<'s, S>, SerdeError> {
+        Ok(self.fields(None))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<FieldsSerializer<'s, S>, SerdeError> {
+        Ok(self.fields(None))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<FieldsSerializer<'s, S>, SerdeError> {
+        let field_number = field_number(variant, variant_index)?;
+        Ok(self.fields(Some(field_number)))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, SerdeError> {
+        Err(SerdeError::Unsupported(NOT_A_MSG))
+    }
+
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<FieldsSerializer<'s, S>, SerdeError> {
+        Ok(self.fields(None))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<FieldsSerializer<'s, S>, SerdeError> {
+        let field_number = field_number(variant, variant_index)?;
+        Ok(self.fields(Some(field_number)))
+    }
+}
+
+/// Serializer writing a value as field of a message.
+pub struct FieldSerializer<'s, S> {
+    s: &'s mut S,
+    field_number: FieldNumber,
+    /// True if the value is an element of a repeated field.
+    in_seq: bool,
+}
+
+impl<'s, S: MsgScribe> FieldSerializer<'s, S> {
+    /// Starts a sub-message in the field & returns a serializer for its
+    /// content.
+    fn sub_msg(self) -> MsgSerializer<'s, S> {
+        self.s.start_msg(self.field_number);
+        MsgSerializer {
+            s: self.s,
+            end: Some(self.field_number),
+        }
+    }
+}
+
+impl<'s, S: MsgScribe> ser::Serializer for FieldSerializer<'s, S> {
+    type Ok = ();
+    type Error = SerdeError;
+    type SerializeSeq = SeqSerializer<'s, S>;
+    type SerializeTuple = FieldsSerializer<'s, S>;
+    type SerializeTupleStruct = FieldsSerializer<'s, S>;
+    type SerializeTupleVariant = FieldsSerializer<'s, S>;
+    type SerializeMap = MapSerializer<'s, S>;
+    type SerializeStruct = FieldsSerializer<'s, S>;
+    type SerializeStructVariant = FieldsSerializer<'s, S>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), SerdeError> {
+        self.s.add_bool(self.field_number, v);
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), SerdeError> {
+        self.serialize_i32(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), SerdeError> {
+        self.serialize_i32(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), SerdeError> {
+        self.s.add_int32(self.field_number, v);
+        Ok(())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), SerdeError> {
+        self.s.add_int64(self.field_number, v);
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), SerdeError> {
+        self.serialize_u32(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), SerdeError> {
+        self.serialize_u32(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), SerdeError> {
+        self.s.add_uint32(self.field_number, v);
+        Ok(())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), SerdeError> {
+        self.s.add_uint64(self.field_number, v);
+        Ok(())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), SerdeError> {
+        self.s.add_float(self.field_number, v);
+        Ok(())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), SerdeError> {
+        self.s.add_double(self.field_number, v);
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), SerdeError> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), SerdeError> {
+        self.s.add_string(self.field_number, v);
+        Ok(())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), SerdeError> {
+        self.s.add_bytes(self.field_number, v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), SerdeError> {
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), SerdeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), SerdeError> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), SerdeError> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), SerdeError> {
+        self.s
+            .add_enum(self.field_number, enum_value(variant, variant_index));
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        self.sub_msg()
+            .serialize_newtype_variant(name, variant_index, variant, value)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<SeqSerializer<'s, S>, SerdeError> {
+        if self.in_seq {
+            return Err(SerdeError::Unsupported("nested sequences"));
+        }
+        Ok(SeqSerializer {
+            s: self.s,
+            field_number: self.field_number,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<FieldsSerializer<'s, S>, SerdeError> {
+        self.sub_msg().serialize_tuple(len)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<FieldsSerializer<'s, S>, SerdeError> {
+        self.sub_msg().serialize_tuple_struct(name, len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<FieldsSerializer<'s, S>, SerdeError> {
+        self.sub_msg()
+            .serialize_tuple_variant(name, variant_index, variant, len)
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<MapSerializer<'s, S>, SerdeError> {
+        if self.in_seq {
+            return Err(SerdeError::Unsupported("maps in sequences"));
+        }
+        Ok(MapSerializer {
+            s: self.s,
+            field_number: self.field_number,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<FieldsSerializer<'s, S>, SerdeError> {
+        self.sub_msg().serialize_struct(name, len)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<FieldsSerializer<'s, S>, SerdeError> {
+        self.sub_msg()
+            .serialize_struct_variant(name, variant_index, variant, len)
+    }
+}
+
+/// Serializer for the fields of a struct, tuple or enum variant.
+pub struct FieldsSerializer<'s, S> {
+    s: &'s mut S,
+    /// Position of the next field.
+    next_index: u32,
+    /// Sub-messages that have to be ended (innermost first).
+    ends: [Option<FieldNumber>; 2],
+}
+
+impl<'s, S: MsgScribe> FieldsSerializer<'s, S> {
+    fn field<T: Serialize + ?Sized>(&mut self, name: &str, value: &T) -> Result<(), SerdeError> {
+        let field_number = field_number(name, self.next_index)?;
+        self.next_index += 1;
+        value.serialize(FieldSerializer {
+            s: &mut *self.s,
+            field_number,
+            in_seq: false,
+        })
+    }
+
+    fn close(self) -> Result<(), SerdeError> {
+        for field_number in self.ends.into_iter().flatten() {
+            self.s.end_msg(field_number);
+        }
+        Ok(())
+    }
+}
+
+impl<'s, S: MsgScribe> ser::SerializeStruct for FieldsSerializer<'s, S> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        self.field(key, value)
+    }
+
+    fn skip_field(&mut self, _: &'static str) -> Result<(), SerdeError> {
+        // skipped fields keep their field number
+        self.next_index += 1;
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), SerdeError> {
+        self.close()
+    }
+}
+
+impl<'s, S: MsgScribe> ser::SerializeStructVariant for FieldsSerializer<'s, S> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        self.field(key, value)
+    }
+
+    fn skip_field(&mut self, _: &'static str) -> Result<(), SerdeError> {
+        self.next_index += 1;
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), SerdeError> {
+        self.close()
+    }
+}
+
+impl<'s, S: MsgScribe> ser::SerializeTuple for FieldsSerializer<'s, S> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.field("", value)
+    }
+
+    fn end(self) -> Result<(), SerdeError> {
+        self.close()
+    }
+}
+
+impl<'s, S: MsgScribe> ser::SerializeTupleStruct for FieldsSerializer<'s, S> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.field("", value)
+    }
+
+    fn end(self) -> Result<(), SerdeError> {
+        self.close()
+    }
+}
+
+impl<'s, S: MsgScribe> ser::SerializeTupleVariant for FieldsSerializer<'s, S> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.field("", value)
+    }
+
+    fn end(self) -> Result<(), SerdeError> {
+        self.close()
+    }
+}
+
+/// Serializer for the elements of a repeated field.
+pub struct SeqSerializer<'s, S> {
+    s: &'s mut S,
+    field_number: FieldNumber,
+}
+
+impl<'s, S: MsgScribe> ser::SerializeSeq for SeqSerializer<'s, S> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        value.serialize(FieldSerializer {
+            s: &mut *self.s,
+            field_number: self.field_number,
+            in_seq: true,
+        })
+    }
+
+    fn end(self) -> Result<(), SerdeError> {
+        Ok(())
+    }
+}
+
+/// Serializer for the entries of a `map<K, V>` field.
+pub struct MapSerializer<'s, S> {
+    s: &'s mut S,
+    field_number: FieldNumber,
+}
+
+impl<'s, S: MsgScribe> ser::SerializeMap for MapSerializer<'s, S> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), SerdeError> {
+        self.s.start_msg(self.field_number);
+        key.serialize(FieldSerializer {
+            s: &mut *self.s,
+            field_number: FieldNumber::MAP_KEY,
+            in_seq: true,
+        })
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        value.serialize(FieldSerializer {
+            s: &mut *self.s,
+            field_number: FieldNumber::MAP_VALUE,
+            in_seq: false,
+        })?;
+        self.s.end_msg(self.field_number);
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), SerdeError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::serde::Serialize;
+    use alloc::{collections::BTreeMap, string::String, vec};
+
+    #[derive(Serialize)]
+    struct Inner {
+        a: i32,
+        #[serde(rename = "5")]
+        b: String,
+    }
+
+    #[derive(Serialize)]
+    enum Kind {
+        Empty,
+        #[serde(rename = "7")]
+        Seven,
+        Num(u64),
+        Pair(bool, f32),
+        Named {
+            x: Option<u32>,
+        },
+    }
+
+    #[derive(Serialize)]
+    struct Outer {
+        scalar: u32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        skipped: Option<u32>,
+        inner: Inner,
+        repeated: Vec<i64>,
+        map: BTreeMap<u8, String>,
+        kinds: Vec<Kind>,
+        #[serde(with = "serde_bytes_like")]
+        bytes: Vec<u8>,
+        tuple: (char, f64),
+    }
+
+    mod serde_bytes_like {
+        pub fn serialize<S: ::serde::Serializer>(v: &[u8], s: S) -> Result<S::Ok, S::Error> {
+            s.serialize_bytes(v)
+        }
+    }
+
+    fn fn_(v: u32) -> FieldNumber {
+        FieldNumber::try_from(v).unwrap()
+    }
+
+    #[test]
+    fn same_as_builder() {
+        let value = Outer {
+            scalar: 300,
+            skipped: None,
+            inner: Inner {
+                a: -1,
+                b: "x".into(),
+            },
+            repeated: vec![1, -2],
+            map: [(1, "a".into()), (2, "b".into())].into_iter().collect(),
+            kinds: vec![
+                Kind::Empty,
+                Kind::Seven,
+                Kind::Num(3),
+                Kind::Pair(true, 1.5),
+                Kind::Named { x: Some(4) },
+                Kind::Named { x: None },
+            ],
+            bytes: vec![1, 2],
+            tuple: ('ä', 2.5),
+        };
+
+        fn ser_expected<S: MsgScribe>(s: &mut S) {
+            s.add_uint32(fn_(1), 300);
+            s.start_msg(fn_(3))
+                .add_int32(fn_(1), -1)
+                .add_string(fn_(5), "x")
+                .end_msg(fn_(3));
+            s.add_int64(fn_(4), 1).add_int64(fn_(4), -2);
+            for (k, v) in [(1, "a"), (2, "b")] {
+                s.add_map_entry(fn_(5), k, v, S::add_uint32, S::add_string);
+            }
+            s.add_enum(fn_(6), 0).add_enum(fn_(6), 7);
+            s.start_msg(fn_(6)).add_uint64(fn_(3), 3).end_msg(fn_(6));
+            s.start_msg(fn_(6))
+                .start_msg(fn_(4))
+                .add_bool(fn_(1), true)
+                .add_float(fn_(2), 1.5)
+                .end_msg(fn_(4))
+                .end_msg(fn_(6));
+            s.start_msg(fn_(6))
+                .start_msg(fn_(5))
+                .add_uint32(fn_(1), 4)
+                .end_msg(fn_(5))
+                .end_msg(fn_(6));
+            s.start_msg(fn_(6))
+                .start_msg(fn_(5))
+                .end_msg(fn_(5))
+                .end_msg(fn_(6));
+            s.add_bytes(fn_(7), &[1, 2]);
+            s.start_msg(fn_(8))
+                .add_string(fn_(1), "ä")
+                .add_double(fn_(2), 2.5)
+                .end_msg(fn_(8));
+        }
+
+        let mut builder = MsgBuilder::new();
+        let mut len = builder.start(None);
+        ser_expected(&mut len);
+        let mut s = len.end();
+        ser_expected(&mut s);
+        let expected = s.end().to_vec();
+
+        assert_eq!(Ok(expected), to_vec(&value));
+    }
+
+    #[test]
+    fn top_level_enum() {
+        assert_eq!(Ok(vec![0x0a, 0x00]), to_vec(&Kind::Empty));
+        assert_eq!(Ok(vec![0x18, 0x02]), to_vec(&Kind::Num(2)));
+        assert_eq!(
+            Ok(vec![0x22, 0x07, 0x08, 0x01, 0x15, 0, 0, 0, 0]),
+            to_vec(&Kind::Pair(true, 0.0))
+        );
+    }
+
+    #[test]
+    fn error() {
+        assert_eq!(
+            Err(SerdeError::Unsupported(
+                "message has to be a struct, tuple or enum"
+            )),
+            to_vec(&1u32)
+        );
+        assert_eq!(
+            Err(SerdeError::Unsupported("nested sequences")),
+            to_vec(&(vec![vec![1u32]],))
+        );
+        #[derive(Serialize)]
+        struct Zero {
+            #[serde(rename = "0")]
+            a: u32,
+        }
+        assert_eq!(
+            Err(SerdeError::InvalidFieldNumber(0)),
+            to_vec(&Zero { a: 1 })
+        );
+        #[derive(Serialize)]
+        struct Custom;
+        assert_eq!(Ok(vec![]), to_vec(&Custom));
+    }
+}
@@ -0,0 +1,283 @@
+use crate::{decode::*, wire::*, FieldNumber};
+use ::serde::ser::{Error, Serialize, SerializeMap, SerializeSeq, Serializer};
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+
+/// Serializes the decoded records of a message without a schema (e.g. to
+/// dump an unknown payload as JSON).
+///
+/// The message is serialized as map with the field numbers as keys (in the
+/// order they first appear). Fields present once are serialized as single
+/// value, fields present multiple times as sequence of their values. For
+/// the values see the `Serialize` implementation of [`WireValueRef`].
+///
+/// A decoding error is reported as serializer error.
+///
+/// ```
+/// use protobin::decode::MsgDecoder;
+///
+/// // message { uint32 a = 1; message b = 2 { string c = 1; } }
+/// let bytes = [0x08, 0x03, 0x12, 0x03, 0x0a, 0x01, b'x'];
+/// assert_eq!(
+///     r#"{"1":3,"2":{"1":"x"}}"#,
+///     serde_json::to_string(&MsgDecoder::new(&bytes)).unwrap()
+/// );
+/// ```
+impl<'a> Serialize for MsgDecoder<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RawMsg {
+            decoder: self.clone(),
+            depth: 0,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Serializes the value without a schema.
+///
+/// VARINT, I64 & I32 values are serialized as unsigned integers (as the
+/// signedness & encoding is unknown) & group markers as unit. LEN values
+/// are serialized according to their most likely interpretation (see
+/// [`WireLenRef::classify`]):
+///
+/// * strings as string,
+/// * sub-messages as map (see the `Serialize` implementation of
+///   [`MsgDecoder`]),
+/// * packed repeated fields as sequence of unsigned integers,
+/// * everything else as bytes.
+impl<'a> Serialize for WireValueRef<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RawValue {
+            value: *self,
+            depth: 0,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Serializes the record as map with the field number as only key (see the
+/// `Serialize` implementation of [`WireValueRef`] for the value).
+impl<'a> Serialize for MsgRecordRef<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(&self.field_number.value(), &self.value)?;
+        map.end()
+    }
+}
+
+struct RawMsg<'a> {
+    decoder: MsgDecoder<'a>,
+    depth: usize,
+}
+
+impl<'a> Serialize for RawMsg<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // group the values by field number (in order of first appearance)
+        let mut fields: Vec<(FieldNumber, Vec<WireValueRef<'a>>)> = Vec::new();
+        // index of every field number in `fields`
+        let mut positions: BTreeMap<FieldNumber, usize> = BTreeMap::new();
+        for record in self.decoder.clone() {
+            let record = record.map_err(decode_error::<S>)?;
+            match positions.get(&record.field_number) {
+                Some(&index) => fields[index].1.push(record.value),
+                None => {
+                    positions.insert(record.field_number, fields.len());
+                    fields.push((record.field_number, vec![record.value]));
+                }
+            }
+        }
+
+        let mut map = serializer.serialize_map(Some(fields.len()))?;
+        for (field_number, values) in &fields {
+            map.serialize_key(&field_number.value())?;
+            if let [value] = &values[..] {
+                map.serialize_value(&RawValue {
+                    value: *value,
+                    depth: self.depth,
+                })?;
+            } else {
+                map.serialize_value(&RawValues {
+                    values,
+                    depth: self.depth,
+                })?;
+            }
+        }
+        map.end()
+    }
+}
+
+struct RawValues<'a, 'b> {
+    values: &'b [WireValueRef<'a>],
+    depth: usize,
+}
+
+impl<'a, 'b> Serialize for RawValues<'a, 'b> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.values.len()))?;
+        for value in self.values {
+            seq.serialize_element(&RawValue {
+                value: *value,
+                depth: self.depth,
+            })?;
+        }
+        seq.end()
+    }
+}
+
+struct RawValue<'a> {
+    value: WireValueRef<'a>,
+    depth: usize,
+}
+
+impl<'a> Serialize for RawValue<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.value {
+            WireValueRef::VarInt(v) => serializer.serialize_u64(v.as_uint64()),
+            WireValueRef::I64(v) => serializer.serialize_u64(v.0),
+            WireValueRef::I32(v) => serializer.serialize_u32(v.0),
+            WireValueRef::SGroup | WireValueRef::EGroup => serializer.serialize_unit(),
+            WireValueRef::Len(l) => match l.kind_at_depth(self.depth) {
+                LenKind::String => {
+                    // validated by kind_at_depth
+                    serializer.serialize_str(l.try_as_string().map_err(S::Error::custom)?)
+                }
+                LenKind::SubMsg => RawMsg {
+                    decoder: l.as_sub_msg(),
+                    depth: self.depth + 1,
+                }
+                .serialize(serializer),
+                kind @ (LenKind::PackedVarInt | LenKind::PackedI32 | LenKind::PackedI64) => {
+                    RawPacked {
+                        data: l.as_bytes(),
+                        kind,
+                    }
+                    .serialize(serializer)
+                }
+                LenKind::Bytes => serializer.serialize_bytes(l.as_bytes()),
+            },
+        }
+    }
+}
+
+struct RawPacked<'a> {
+    data: &'a [u8],
+    kind: LenKind,
+}
+
+impl<'a> Serialize for RawPacked<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let len = match self.kind {
+            LenKind::PackedI32 => self.data.len() / 4,
+            LenKind::PackedI64 => self.data.len() / 8,
+            // one byte without continuation bit per VARINT
+            _ => self.data.iter().filter(|b| **b & 0x80 == 0).count(),
+        };
+        let mut seq = serializer.serialize_seq(Some(len))?;
+        let mut decoder = WireDecoder::new(self.data);
+        while !decoder.data.is_empty() {
+            // validated by kind_at_depth
+            match self.kind {
+                LenKind::PackedI32 => {
+                    seq.serialize_element(&decoder.read_fixed32().map_err(decode_error::<S>)?)?
+                }
+                LenKind::PackedI64 => {
+                    seq.serialize_element(&decoder.read_fixed64().map_err(decode_error::<S>)?)?
+                }
+                _ => {
+                    seq.serialize_element(&decoder.read_var_uint64().map_err(decode_error::<S>)?)?
+                }
+            }
+        }
+        seq.end()
+    }
+}
+
+fn decode_error<S: Serializer>(err: DecodeError) -> S::Error {
+    S::Error::custom(format_args!("{:?}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builders::*;
+    use alloc::string::String;
+
+    fn fn_(v: u32) -> FieldNumber {
+        FieldNumber::try_from(v).unwrap()
+    }
+
+    fn ser<S: MsgScribe>(s: &mut S) {
+        s.add_uint32(fn_(1), 300)
+            .add_sint32(fn_(2), -1)
+            .add_fixed64(fn_(3), 7)
+            .add_float(fn_(4), 1.0)
+            .add_string(fn_(5), "a\tb")
+            .add_string(fn_(5), "")
+            .add_bytes(fn_(6), &[0xff, 0x00]);
+        s.start_msg(fn_(7))
+            .add_bool(fn_(1), true)
+            .start_msg(fn_(2))
+            .add_string(fn_(3), "deep")
+            .end_msg(fn_(2))
+            .end_msg(fn_(7));
+        s.add_uint32(fn_(1), 2);
+    }
+
+    #[test]
+    fn json() {
+        let mut builder = MsgBuilder::new();
+        let mut len = builder.start(None);
+        ser(&mut len);
+        let mut s = len.end();
+        ser(&mut s);
+        let bytes = s.end();
+
+        assert_eq!(
+            r#"{"1":[300,2],"2":1,"3":7,"4":1065353216,"5":["a\tb",""],"6":[255,0],"7":{"1":1,"2":{"3":"deep"}}}"#,
+            serde_json::to_string(&MsgDecoder::new(bytes)).unwrap()
+        );
+
+        // single record
+        let record = MsgDecoder::new(bytes).next().unwrap().unwrap();
+        assert_eq!(r#"{"1":300}"#, serde_json::to_string(&record).unwrap());
+
+        // fields in the order of their first appearance
+        assert_eq!(
+            r#"{"3":[1,3],"1":2}"#,
+            serde_json::to_string(&MsgDecoder::new(&[0x18, 0x01, 0x08, 0x02, 0x18, 0x03])).unwrap()
+        );
+
+        // packed repeated fields
+        let bytes = [0x0a, 0x03, 0xac, 0x02, 0x01, 0x12, 0x04, 0, 0, 0xc0, 0x3f];
+        assert_eq!(
+            r#"{"1":[300,1],"2":[1069547520]}"#,
+            serde_json::to_string(&MsgDecoder::new(&bytes)).unwrap()
+        );
+    }
+
+    #[test]
+    fn error() {
+        // truncated LEN record
+        assert!(serde_json::to_string(&MsgDecoder::new(&[0x0a, 0x05])).is_err());
+        // broken data inside a LEN value is serialized as bytes
+        assert_eq!(
+            r#"{"1":[10,5]}"#,
+            serde_json::to_string(&MsgDecoder::new(&[0x0a, 0x02, 0x0a, 0x05])).unwrap()
+        );
+    }
+
+    #[test]
+    fn depth_limit() {
+        // nested sub-messages deeper than WireLenRef::MAX_SUB_MSG_DEPTH
+        let mut bytes = vec![0x08, 0x01];
+        for _ in 0..(WireLenRef::MAX_SUB_MSG_DEPTH + 10) {
+            let mut outer = vec![0x0a, bytes.len() as u8];
+            if bytes.len() > 127 {
+                outer = vec![0x0a, (bytes.len() as u8) | 0x80, (bytes.len() >> 7) as u8];
+            }
+            outer.extend_from_slice(&bytes);
+            bytes = outer;
+        }
+        let json: String = serde_json::to_string(&MsgDecoder::new(&bytes)).unwrap();
+        assert_eq!(WireLenRef::MAX_SUB_MSG_DEPTH + 1, json.matches('{').count());
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{builders::EncodeError, decode::DecodeError, wire::WireValueIntoError, FieldNumber};
+use alloc::string::{String, ToString};
+use core::{fmt, str::Utf8Error};
+
+/// Error when serializing or deserializing a value via serde.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SerdeError {
+    /// Error reported by a `Serialize` or `Deserialize` implementation.
+    Custom(String),
+
+    /// The serde data type can not be represented in protobuf (e.g. nested
+    /// sequences).
+    Unsupported(&'static str),
+
+    /// The field number derived from a field name or its position is not
+    /// an allowed protobuf field number.
+    InvalidFieldNumber(u64),
+
+    /// The decoded enum value or message does not match any variant of the
+    /// enum.
+    UnknownVariant,
+
+    /// Error reported by the builder while encoding.
+    Encode(EncodeError),
+
+    /// Error while decoding the message records.
+    Decode(DecodeError),
+
+    /// A field contained a value that could not be interpreted as the
+    /// type of the field.
+    Value {
+        field_number: FieldNumber,
+        err: WireValueIntoError,
+    },
+
+    /// A `string` field contained invalid UTF-8.
+    Utf8(Utf8Error),
+
+    /// The sub-messages are nested deeper than the supported limit of 100.
+    RecursionLimit,
+}
+
+impl SerdeError {
+    /// Returns a closure converting a [`WireValueIntoError`] of the
+    /// given field into a [`SerdeError`].
+    #[inline]
+    pub(crate) fn value(field_number: FieldNumber) -> impl Fn(WireValueIntoError) -> SerdeError {
+        move |err| SerdeError::Value { field_number, err }
+    }
+}
+
+impl fmt::Display for SerdeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use SerdeError::*;
+        match self {
+            Custom(msg) => f.write_str(msg),
+            Unsupported(what) => write!(f, "not supported by protobuf: {}", what),
+            InvalidFieldNumber(value) => write!(f, "invalid field number {}", value),
+            UnknownVariant => f.write_str("value does not match any enum variant"),
+            Encode(err) => write!(f, "encoding error: {:?}", err),
+            Decode(err) => write!(f, "decoding error: {:?}", err),
+            Value { field_number, err } => {
+                write!(f, "field {}: {:?}", field_number.value(), err)
+            }
+            Utf8(err) => write!(f, "{}", err),
+            RecursionLimit => f.write_str("sub-messages nested too deeply"),
+        }
+    }
+}
+
+impl ::serde::ser::StdError for SerdeError {}
+
+impl ::serde::ser::Error for SerdeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        SerdeError::Custom(msg.to_string())
+    }
+}
+
+impl ::serde::de::Error for SerdeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        SerdeError::Custom(msg.to_string())
+    }
+}
+
+impl From<EncodeError> for SerdeError {
+    fn from(value: EncodeError) -> Self {
+        SerdeError::Encode(value)
+    }
+}
+
+impl From<DecodeError> for SerdeError {
+    fn from(value: DecodeError) -> Self {
+        SerdeError::Decode(value)
+    }
+}
+
+impl From<Utf8Error> for SerdeError {
+    fn from(value: Utf8Error) -> Self {
+        SerdeError::Utf8(value)
+    }
+}
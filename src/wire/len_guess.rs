@@ -0,0 +1,10 @@
+use crate::wire::*;
+
+/// Possible interpretation of a LEN value together with its confidence (see
+/// [`WireLenRef::classify`]).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct LenGuess {
+    pub kind: LenKind,
+    /// Confidence between 0 (impossible) & 100 (see [`LenKind::confidence`]).
+    pub confidence: u8,
+}
@@ -0,0 +1,261 @@
+use crate::{decode::*, wire::*, FieldNumber};
+
+/// Interpretation of the data of a LEN value (see
+/// [`WireLenRef::classify`]).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum LenKind {
+    /// UTF-8 encoded `string`.
+    String,
+    /// Embedded message.
+    SubMsg,
+    /// Packed repeated VARINT field (e.g. `repeated int32`).
+    PackedVarInt,
+    /// Packed repeated I32 field (`repeated fixed32`, `sfixed32` or
+    /// `float`).
+    PackedI32,
+    /// Packed repeated I64 field (`repeated fixed64`, `sfixed64` or
+    /// `double`).
+    PackedI64,
+    /// Arbitrary `bytes` (always possible).
+    Bytes,
+}
+
+impl LenKind {
+    /// All interpretations (in the order used for equal confidences).
+    pub const ALL: [LenKind; 6] = [
+        LenKind::String,
+        LenKind::SubMsg,
+        LenKind::PackedVarInt,
+        LenKind::PackedI32,
+        LenKind::PackedI64,
+        LenKind::Bytes,
+    ];
+
+    /// Confidence of [`LenKind::Bytes`] (the other interpretations have to
+    /// be more plausible to be preferred over plain bytes).
+    pub const BYTES_CONFIDENCE: u8 = 40;
+
+    /// Returns how plausible it is that the data has this type as
+    /// confidence between 0 (impossible) & 100.
+    ///
+    /// * `String`: 90 for valid UTF-8 without control characters (except
+    ///   tab, line feed & carriage return), otherwise up to 60 depending on
+    ///   the ratio of printable characters. 70 if the data also decodes
+    ///   completely as records with tab, line feed or carriage return as
+    ///   tags (e.g. a sub-message starting with a LEN field 1, whose tag is
+    ///   a line feed & whose length is often printable).
+    /// * `SubMsg`: 80 if the data decodes completely as records with
+    ///   balanced groups, 50 if it contains unusually large or reserved
+    ///   field numbers.
+    /// * `PackedVarInt`: 45 if the data decodes completely as minimally
+    ///   encoded VARINTs, 10 if some are not minimally encoded.
+    /// * `PackedI32` & `PackedI64`: between 20 & 50 for a multiple of 4 or 8
+    ///   bytes depending on the ratio of values that are plausible as
+    ///   integer (below 2^20) or floating point number (0 or magnitude
+    ///   between 1e-9 & 1e9).
+    /// * `Bytes`: always [`LenKind::BYTES_CONFIDENCE`].
+    ///
+    /// Note that the sub-message check does not decode nested LEN values.
+    pub fn confidence(&self, data: &[u8]) -> u8 {
+        match self {
+            LenKind::String => string_confidence(data),
+            LenKind::SubMsg => sub_msg_confidence(data),
+            LenKind::PackedVarInt => packed_var_int_confidence(data),
+            LenKind::PackedI32 => packed_fixed_confidence(data, 4, |b| {
+                let v = u32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+                v < (1 << 20) || plausible_float(f64::from(f32::from_bits(v)))
+            }),
+            LenKind::PackedI64 => packed_fixed_confidence(data, 8, |b| {
+                let v = u64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]);
+                v < (1 << 20) || plausible_float(f64::from_bits(v))
+            }),
+            LenKind::Bytes => LenKind::BYTES_CONFIDENCE,
+        }
+    }
+}
+
+fn string_confidence(data: &[u8]) -> u8 {
+    let Ok(s) = core::str::from_utf8(data) else {
+        return 0;
+    };
+    let mut chars = 0usize;
+    let mut printable = 0usize;
+    for c in s.chars() {
+        chars += 1;
+        if !c.is_control() || matches!(c, '\t' | '\n' | '\r') {
+            printable += 1;
+        }
+    }
+    if printable == chars {
+        if looks_like_sub_msg(data) {
+            70
+        } else {
+            90
+        }
+    } else {
+        (printable * 60 / chars) as u8
+    }
+}
+
+/// Returns true if printable text decodes completely as records with
+/// control characters (tab, line feed or carriage return) as tags.
+fn looks_like_sub_msg(data: &[u8]) -> bool {
+    let mut decoder = MsgDecoder::new(data);
+    let mut records = 0usize;
+    while let Some(record) = decoder.next_with_raw() {
+        let Ok((_, raw)) = record else {
+            return false;
+        };
+        if !raw[0].is_ascii_control() {
+            return false;
+        }
+        records += 1;
+    }
+    records > 0 && sub_msg_confidence(data) > 0
+}
+
+fn sub_msg_confidence(data: &[u8]) -> u8 {
+    let mut unusual = false;
+    let mut group_depth = 0usize;
+    for record in MsgDecoder::new(data) {
+        let Ok(record) = record else {
+            return 0;
+        };
+        let n = record.field_number.value();
+        if n >= (1 << 16) || FieldNumber::RESERVED_FOR_PROTO_INTERNAL.contains(&n) {
+            unusual = true;
+        }
+        match record.value {
+            WireValueRef::SGroup => group_depth += 1,
+            WireValueRef::EGroup => match group_depth.checked_sub(1) {
+                Some(d) => group_depth = d,
+                None => return 0,
+            },
+            _ => {}
+        }
+    }
+    if group_depth != 0 {
+        0
+    } else if unusual {
+        50
+    } else {
+        80
+    }
+}
+
+fn packed_var_int_confidence(data: &[u8]) -> u8 {
+    let mut decoder = WireDecoder::new(data);
+    let mut minimal = true;
+    while !decoder.data.is_empty() {
+        let len = decoder.data.len();
+        let Ok(value) = decoder.read_var_uint64() else {
+            return 0;
+        };
+        if len - decoder.data.len() != WireVarInt::uint64_byte_len(value) as usize {
+            minimal = false;
+        }
+    }
+    if minimal {
+        45
+    } else {
+        10
+    }
+}
+
+fn packed_fixed_confidence(data: &[u8], size: usize, plausible: impl Fn(&[u8]) -> bool) -> u8 {
+    if data.len() % size != 0 {
+        return 0;
+    }
+    let count = data.len() / size;
+    if count == 0 {
+        return 20;
+    }
+    let plausible = data.chunks_exact(size).filter(|b| plausible(b)).count();
+    (20 + plausible * 30 / count) as u8
+}
+
+/// Returns true if the value is 0 or has a magnitude between 1e-9 & 1e9
+/// (typical for floating point numbers, unlike reinterpreted integers).
+pub(crate) fn plausible_float(value: f64) -> bool {
+    value == 0.0 || (1e-9..=1e9).contains(&value.abs())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wire::*;
+
+    fn kinds(data: &[u8]) -> [(LenKind, u8); 6] {
+        WireLenRef { data }
+            .classify()
+            .map(|guess| (guess.kind, guess.confidence))
+    }
+
+    #[test]
+    fn confidence() {
+        use LenKind::*;
+
+        // string
+        assert_eq!(90, String.confidence(b"a\tb\n"));
+        assert_eq!(90, String.confidence(b""));
+        assert_eq!(30, String.confidence(&[b'a', 0x01]));
+        // field 1 as I64 & as I32 with printable values
+        assert_eq!(70, String.confidence(b"\tabcdefgh\rwxyz"));
+        assert_eq!(0, String.confidence(&[0xff]));
+
+        // sub-message
+        assert_eq!(80, SubMsg.confidence(&[0x08, 0x01, 0x0b, 0x08, 0x01, 0x0c]));
+        assert_eq!(50, SubMsg.confidence(&[0x80, 0x80, 0x20, 0x01]));
+        assert_eq!(0, SubMsg.confidence(&[0x0a, 0x05]));
+        // unbalanced groups
+        assert_eq!(0, SubMsg.confidence(&[0x0b]));
+        assert_eq!(0, SubMsg.confidence(&[0x0c]));
+
+        // packed VARINT
+        assert_eq!(45, PackedVarInt.confidence(&[0xac, 0x02, 0x01]));
+        assert_eq!(10, PackedVarInt.confidence(&[0x81, 0x00]));
+        assert_eq!(0, PackedVarInt.confidence(&[0x81]));
+
+        // packed I32 & I64
+        let floats: [u8; 8] = [0, 0, 0xc0, 0x3f, 0xff, 0xff, 0xff, 0xff];
+        assert_eq!(35, PackedI32.confidence(&floats));
+        assert_eq!(20, PackedI64.confidence(&floats));
+        assert_eq!(50, PackedI64.confidence(&1.5f64.to_le_bytes()));
+        assert_eq!(0, PackedI32.confidence(&[0; 3]));
+        assert_eq!(0, PackedI64.confidence(&[0; 4]));
+
+        assert_eq!(LenKind::BYTES_CONFIDENCE, Bytes.confidence(&[0xff]));
+    }
+
+    #[test]
+    fn classify() {
+        use LenKind::*;
+
+        assert_eq!(
+            [
+                (String, 90),
+                (SubMsg, 80),
+                (PackedVarInt, 45),
+                (Bytes, 40),
+                (PackedI32, 20),
+                (PackedI64, 20),
+            ],
+            kinds(&[])
+        );
+        // message { uint32 a = 1; string b = 2; }
+        assert_eq!(
+            (SubMsg, 80),
+            kinds(&[0x08, 0x96, 0x01, 0x12, 0x01, b'x'])[0]
+        );
+        // message { string a = 1; } with a 33 byte string (the tag is a
+        // line feed & the length a printable '!')
+        let mut msg = vec![0x0a, 0x21];
+        msg.extend_from_slice(&[b'x'; 0x21]);
+        assert_eq!([(SubMsg, 80), (String, 70)], kinds(&msg)[..2]);
+        // text starting with a line feed that does not decode as records
+        assert_eq!((String, 90), kinds(b"\nabc")[0]);
+        // packed float [1.5]
+        assert_eq!((PackedI32, 50), kinds(&1.5f32.to_le_bytes())[0]);
+        // nothing plausible
+        assert_eq!((Bytes, 40), kinds(&[0xff, 0xff])[0]);
+    }
+}
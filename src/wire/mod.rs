@@ -1,7 +1,18 @@
+mod len_guess;
+pub use len_guess::*;
+
+mod len_kind;
+pub use len_kind::*;
+
+mod var_int_bytes;
+pub(crate) use var_int_bytes::*;
+
 mod wire_decoder;
 pub use wire_decoder::*;
 
+#[cfg(feature = "alloc")]
 mod wire_encoder;
+#[cfg(feature = "alloc")]
 pub use wire_encoder::*;
 
 mod wire_i32;
@@ -19,6 +30,16 @@ pub use wire_len_ref::*;
 mod wire_type;
 pub use wire_type::*;
 
+#[cfg(feature = "alloc")]
+mod wire_value;
+#[cfg(feature = "alloc")]
+pub use wire_value::*;
+
+#[cfg(feature = "bytes")]
+mod wire_value_bytes;
+#[cfg(feature = "bytes")]
+pub use wire_value_bytes::*;
+
 mod wire_value_ref;
 pub use wire_value_ref::*;
 
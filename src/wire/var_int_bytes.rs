@@ -0,0 +1,98 @@
+use crate::FieldNumber;
+
+/// Minimal VARINT encoding of a single value stored on the stack.
+///
+/// Used by the encoders that do not write via a [`crate::wire::WireEncoder`]
+/// (fixed capacity, single pass, reverse & `bytes` builders) to encode
+/// VARINTs without allocating. Signed & zig zag encoded values are
+/// converted via [`crate::wire::WireVarInt`] first.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub(crate) struct VarIntBytes {
+    buf: [u8; VarIntBytes::MAX_LEN],
+    len: u8,
+}
+
+impl VarIntBytes {
+    /// Maximum number of bytes of an encoded VARINT.
+    pub(crate) const MAX_LEN: usize = 10;
+
+    /// Encodes the value as minimal VARINT.
+    #[inline]
+    pub(crate) fn new(value: u64) -> VarIntBytes {
+        let mut buf = [0u8; VarIntBytes::MAX_LEN];
+        let len = write_var_uint64(&mut buf, value);
+        VarIntBytes {
+            buf,
+            len: len as u8,
+        }
+    }
+
+    /// Encodes the tag of a record with the given field number & wire type.
+    #[inline]
+    pub(crate) fn tag(field_number: FieldNumber, wire_type: u32) -> VarIntBytes {
+        VarIntBytes::new(u64::from((field_number.0 << 3) | wire_type))
+    }
+
+    /// Returns the encoded bytes.
+    #[inline]
+    pub(crate) fn as_slice(&self) -> &[u8] {
+        &self.buf[..usize::from(self.len)]
+    }
+}
+
+/// Writes the value as minimal VARINT to the start of `out` & returns the
+/// number of written bytes (panics if `out` is too short).
+#[inline]
+pub(crate) fn write_var_uint64(out: &mut [u8], mut value: u64) -> usize {
+    let mut i = 0;
+    while value >= 0x80 {
+        out[i] = (value as u8) | 0x80;
+        value >>= 7;
+        i += 1;
+    }
+    out[i] = value as u8;
+    i + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wire::WireVarInt;
+
+    #[test]
+    fn new() {
+        assert_eq!(VarIntBytes::new(0).as_slice(), &[0]);
+        assert_eq!(VarIntBytes::new(0x7f).as_slice(), &[0x7f]);
+        assert_eq!(VarIntBytes::new(150).as_slice(), &[0x96, 0x01]);
+        assert_eq!(
+            VarIntBytes::new(u64::MAX).as_slice(),
+            &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]
+        );
+        for value in [0, 1, 127, 128, 300, u64::from(u32::MAX), u64::MAX] {
+            assert_eq!(
+                VarIntBytes::new(value).as_slice().len(),
+                WireVarInt::uint64_byte_len(value) as usize
+            );
+        }
+    }
+
+    #[test]
+    fn tag() {
+        assert_eq!(VarIntBytes::tag(FieldNumber(1), 0).as_slice(), &[0x08]);
+        assert_eq!(
+            VarIntBytes::tag(FieldNumber(16), 3).as_slice(),
+            &[0x83, 0x01]
+        );
+        assert_eq!(
+            VarIntBytes::tag(FieldNumber(FieldNumber::MAX_ALLOWED_U32), 4).as_slice(),
+            &[0xfc, 0xff, 0xff, 0xff, 0x0f]
+        );
+    }
+
+    #[test]
+    fn write_var_uint64_len() {
+        let mut out = [0u8; 12];
+        assert_eq!(write_var_uint64(&mut out[1..], 300), 2);
+        assert_eq!(&out[..4], &[0, 0xac, 0x02, 0]);
+    }
+}
@@ -26,7 +26,78 @@ impl<'a> WireDecoder<'a> {
 
     /// Read a VARINT uint64 from the `data` slice and move `data`
     /// by the amount of read bytes.
+    #[inline]
     pub fn read_var_uint64(&mut self) -> Result<u64, DecodeError> {
+        if self.data.len() >= 10 {
+            self.read_var_uint64_fast()
+        } else {
+            self.read_var_uint64_slow()
+        }
+    }
+
+    /// Reads a VARINT uint64 if at least 10 bytes are left (no bounds
+    /// checks per byte needed).
+    ///
+    /// Values with more than 2 bytes are decoded by loading the first 8
+    /// bytes as one little endian [`u64`], determining the terminating byte
+    /// via the highest bits & merging the 7 bit groups with masks & shifts.
+    /// Same results & errors as [`WireDecoder::read_var_uint64_slow`].
+    #[inline]
+    fn read_var_uint64_fast(&mut self) -> Result<u64, DecodeError> {
+        const MSBS: u64 = 0x8080_8080_8080_8080;
+
+        // one & two byte values (most common case)
+        let first = self.data[0];
+        if first < 0x80 {
+            self.data = &self.data[1..];
+            return Ok(u64::from(first));
+        }
+        let second = self.data[1];
+        if second < 0x80 {
+            self.data = &self.data[2..];
+            return Ok(u64::from(first & 0x7f) | (u64::from(second) << 7));
+        }
+
+        let word = u64::from_le_bytes([
+            self.data[0],
+            self.data[1],
+            self.data[2],
+            self.data[3],
+            self.data[4],
+            self.data[5],
+            self.data[6],
+            self.data[7],
+        ]);
+        let stop_bits = !word & MSBS;
+        let (result, len) = if stop_bits != 0 {
+            // terminated within the first 8 bytes
+            let len = (stop_bits.trailing_zeros() / 8 + 1) as usize;
+            let mask = if len == 8 {
+                u64::MAX
+            } else {
+                (1u64 << (8 * len)) - 1
+            };
+            (compact_7bit_groups(word & mask), len)
+        } else if self.data[8] < 0x80 {
+            (
+                compact_7bit_groups(word) | (u64::from(self.data[8]) << 56),
+                9,
+            )
+        } else if self.data[9] < 0x80 {
+            // bits exceeding 64 bits are ignored (same as in the slow path)
+            let high = (u64::from(self.data[8] & 0x7f) << 56) | (u64::from(self.data[9]) << 63);
+            (compact_7bit_groups(word) | high, 10)
+        } else {
+            self.data = &self.data[10..];
+            return Err(DecodeError::UnexpectedContinuationBit);
+        };
+        self.data = &self.data[len..];
+        Ok(result)
+    }
+
+    /// Reads a VARINT uint64 byte by byte.
+    #[inline]
+    fn read_var_uint64_slow(&mut self) -> Result<u64, DecodeError> {
         let mut result = 0u64;
         for i in 0..10 {
             let v = self.take_byte()?;
@@ -133,11 +204,22 @@ impl<'a> WireDecoder<'a> {
     }
 }
 
+/// Merges the 7 bit groups of the (up to 8) little endian VARINT bytes
+/// in `word` into one value (continuation bits are dropped).
+#[inline]
+fn compact_7bit_groups(word: u64) -> u64 {
+    let x = word & 0x7f7f_7f7f_7f7f_7f7f;
+    let x = (x & 0x007f_007f_007f_007f) | ((x & 0x7f00_7f00_7f00_7f00) >> 1);
+    let x = (x & 0x0000_3fff_0000_3fff) | ((x & 0x3fff_0000_3fff_0000) >> 2);
+    (x & 0x0000_0000_0fff_ffff) | ((x & 0x0fff_ffff_0000_0000) >> 4)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::wire::*;
     use proptest::prelude::*;
 
+    #[cfg(feature = "alloc")]
     proptest! {
         #[test]
         fn test_var_u32(value in any::<u32>()) {
@@ -149,6 +231,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "alloc")]
     proptest! {
         #[test]
         fn test_var_u64(value in any::<u64>()) {
@@ -160,6 +243,33 @@ mod tests {
         }
     }
 
+    proptest! {
+        #[test]
+        fn read_var_uint64_fast_path(
+            data in prop::collection::vec(prop_oneof![0x80u8..=0xff, any::<u8>()], 10..16)
+        ) {
+            // fast path has the same results as the byte by byte decoding
+            let mut fast = WireDecoder::new(&data);
+            let mut slow = WireDecoder::new(&data);
+            prop_assert_eq!(slow.read_var_uint64_slow(), fast.read_var_uint64_fast());
+            prop_assert_eq!(slow.data, fast.data);
+
+            // all value lengths (with trailing data)
+            #[cfg(feature = "alloc")]
+            {
+                let value = u64::from_le_bytes(data[..8].try_into().unwrap()) >> (data[8] % 64);
+                let mut writer = WireEncoder::new();
+                writer.add_var_uint64(value);
+                let len = writer.buf.len();
+                writer.buf.extend_from_slice(&data);
+                let mut reader = WireDecoder::new(&writer.buf);
+                prop_assert_eq!(Ok(value), reader.read_var_uint64());
+                prop_assert_eq!(&writer.buf[len..], reader.data);
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
     proptest! {
         #[test]
         fn test_fixed32(value in any::<u32>()) {
@@ -171,6 +281,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "alloc")]
     proptest! {
         #[test]
         fn test_fixed64(value in any::<u64>()) {
@@ -182,6 +293,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "alloc")]
     proptest! {
         #[test]
         fn test_read_float(value in any::<f32>()) {
@@ -193,6 +305,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "alloc")]
     proptest! {
         #[test]
         fn test_read_double(value in any::<f64>()) {
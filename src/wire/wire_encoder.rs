@@ -1,3 +1,6 @@
+use crate::wire::WireVarInt;
+use alloc::vec::Vec;
+
 #[derive(Default, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct WireEncoder {
     /// Buffer where the encoded bytes will be written to.
@@ -16,46 +19,22 @@ impl WireEncoder {
     }
 
     /// Add the given [`u32`] as VARINT to the `buf`.
+    #[inline]
     pub fn add_var_uint32(&mut self, value: u32) {
-        // determine needed byte length
-        if value < (1 << 7) {
-            // 1 byte
-            self.buf.push(value as u8);
-        } else if value < (1 << (7 * 2)) {
-            // 2 bytes
-            self.buf
-                .extend_from_slice(&[0b1000_0000 | (value as u8), (value >> 7) as u8]);
-        } else if value < (1 << (7 * 3)) {
-            // 3 bytes
-            self.buf.extend_from_slice(&[
-                0b1000_0000 | (value as u8),
-                0b1000_0000 | ((value >> 7) as u8),
-                ((value >> (7 * 2)) as u8),
-            ]);
-        } else if value < (1 << (7 * 4)) {
-            // 4 bytes
-            self.buf.extend_from_slice(&[
-                0b1000_0000 | (value as u8),
-                0b1000_0000 | ((value >> 7) as u8),
-                0b1000_0000 | ((value >> (7 * 2)) as u8),
-                ((value >> (7 * 3)) as u8),
-            ]);
-        } else {
-            // 5 bytes
-            self.buf.extend_from_slice(&[
-                0b1000_0000 | (value as u8),
-                0b1000_0000 | ((value >> 7) as u8),
-                0b1000_0000 | ((value >> (7 * 2)) as u8),
-                0b1000_0000 | ((value >> (7 * 3)) as u8),
-                ((value >> (7 * 4)) as u8),
-            ]);
-        }
+        self.add_var_uint64(u64::from(value))
     }
 
     /// Add a protobuf `int32` to the `buf` encoded as VARINT.
+    ///
+    /// Negative values are sign extended to 64 bits (and therefore
+    /// always use 10 bytes) as required by the protobuf specification.
     #[inline]
     pub fn add_var_int32(&mut self, value: i32) {
-        self.add_var_uint32(u32::from_ne_bytes(value.to_ne_bytes()))
+        if value < 0 {
+            self.add_var_int64(i64::from(value))
+        } else {
+            self.add_var_uint32(value as u32)
+        }
     }
 
     /// Add a protobuf `sint32` to the `buf` encoded as VARINT with zig zag encoding.
@@ -66,25 +45,138 @@ impl WireEncoder {
     }
 
     /// Add the given [`u64`] as VARINT to the `buf`.
+    #[inline]
     pub fn add_var_uint64(&mut self, value: u64) {
+        // single byte values (most common case)
+        if value < 0x80 {
+            self.buf.push(value as u8);
+            return;
+        }
+
+        // spread the lowest 56 bits to 7 bits per byte & set the
+        // continuation bits of all bytes except the last one
+        const MSBS: u64 = 0x8080_8080_8080_8080;
+        let len = WireVarInt::uint64_byte_len(value) as usize;
+        let continuation = if len > 8 {
+            MSBS
+        } else {
+            MSBS & ((1 << (8 * (len - 1))) - 1)
+        };
+        let low = spread_7bit_groups(value & ((1 << 56) - 1)) | continuation;
+        // bytes 9 & 10 (only part of the encoded value if len > 8)
+        let high = [
+            ((value >> 56) as u8 & 0x7f) | if len > 9 { 0x80 } else { 0 },
+            (value >> 63) as u8,
+        ];
+
+        self.buf.reserve(10);
+        let old_len = self.buf.len();
+        // SAFETY: `reserve` guarantees space for at least 10 more bytes &
+        // the length is only increased by the `len` (max 10) written bytes.
+        unsafe {
+            let dst = self.buf.as_mut_ptr().add(old_len);
+            core::ptr::write_unaligned(dst as *mut [u8; 8], low.to_le_bytes());
+            core::ptr::write_unaligned(dst.add(8) as *mut [u8; 2], high);
+            self.buf.set_len(old_len + len);
+        }
+    }
+
+    /// Add a protobuf `int64` to the `buf` encoded as VARINT.
+    #[inline]
+    pub fn add_var_int64(&mut self, value: i64) {
+        self.add_var_uint64(u64::from_ne_bytes(value.to_ne_bytes()))
+    }
+
+    /// Add a protobuf `sint64` to the `buf` encoded as VARINT with zig zag encoding.
+    #[inline]
+    pub fn add_var_sint64(&mut self, value: i64) {
+        let zigzag = (value << 1) ^ (value >> 63);
+        self.add_var_uint64(u64::from_ne_bytes(zigzag.to_ne_bytes()))
+    }
+
+    /// Add a protobuf `bool` to the `buf` encoded as VARINT.
+    #[inline]
+    pub fn add_bool(&mut self, value: bool) {
+        self.buf.push(if value { 1 } else { 0 });
+    }
+
+    /// Add the given [`u32`] as `fixed32`` to the `buf`.
+    #[inline]
+    pub fn add_fixed32(&mut self, value: u32) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Add the given [`i32`] as `sfixed32`` to the `buf`.
+    #[inline]
+    pub fn add_sfixed32(&mut self, value: i32) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Add the given [`u64`] as fixed64 to the `buf`.
+    #[inline]
+    pub fn add_fixed64(&mut self, value: u64) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Add the given [`i64`] as sfixed64 to the `buf`.
+    #[inline]
+    pub fn add_sfixed64(&mut self, value: i64) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Add the given [`f32`] as float to the `buf`.
+    #[inline]
+    pub fn add_float(&mut self, value: f32) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Add the given [`f64`] as double to the `buf`.
+    #[inline]
+    pub fn add_double(&mut self, value: f64) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Destroys the [`WireEncoder`] and returns the `buf` of the destroyed
+    /// [`WireEncoder`].
+    #[inline]
+    pub fn take_buf(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+/// Spreads the lowest 56 bits of `value` to 7 bits per byte (little
+/// endian, highest bit of every byte is zero).
+#[inline]
+fn spread_7bit_groups(value: u64) -> u64 {
+    let x = (value & 0x0000_0000_0fff_ffff) | ((value & 0x00ff_ffff_f000_0000) << 4);
+    let x = (x & 0x0000_3fff_0000_3fff) | ((x & 0x0fff_c000_0fff_c000) << 2);
+    (x & 0x007f_007f_007f_007f) | ((x & 0x3f80_3f80_3f80_3f80) << 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wire::*;
+    use proptest::prelude::*;
+
+    /// Previous if/else cascade implementation used as reference.
+    fn cascade_add_var_uint64(buf: &mut Vec<u8>, value: u64) {
         // determine needed byte length
         if value < (1 << 7) {
             // 1 byte
-            self.buf.push(value as u8);
+            buf.push(value as u8);
         } else if value < (1 << (7 * 2)) {
             // 2 bytes
-            self.buf
-                .extend_from_slice(&[0b1000_0000 | (value as u8), (value >> 7) as u8]);
+            buf.extend_from_slice(&[0b1000_0000 | (value as u8), (value >> 7) as u8]);
         } else if value < (1 << (7 * 3)) {
             // 3 bytes
-            self.buf.extend_from_slice(&[
+            buf.extend_from_slice(&[
                 0b1000_0000 | (value as u8),
                 0b1000_0000 | ((value >> 7) as u8),
                 ((value >> (7 * 2)) as u8),
             ]);
         } else if value < (1 << (7 * 4)) {
             // 4 bytes
-            self.buf.extend_from_slice(&[
+            buf.extend_from_slice(&[
                 0b1000_0000 | (value as u8),
                 0b1000_0000 | ((value >> 7) as u8),
                 0b1000_0000 | ((value >> (7 * 2)) as u8),
@@ -92,7 +184,7 @@ impl WireEncoder {
             ]);
         } else if value < (1 << (7 * 5)) {
             // 5 bytes
-            self.buf.extend_from_slice(&[
+            buf.extend_from_slice(&[
                 0b1000_0000 | (value as u8),
                 0b1000_0000 | ((value >> 7) as u8),
                 0b1000_0000 | ((value >> (7 * 2)) as u8),
@@ -101,7 +193,7 @@ impl WireEncoder {
             ]);
         } else if value < (1 << (7 * 6)) {
             // 6 bytes
-            self.buf.extend_from_slice(&[
+            buf.extend_from_slice(&[
                 0b1000_0000 | (value as u8),
                 0b1000_0000 | ((value >> 7) as u8),
                 0b1000_0000 | ((value >> (7 * 2)) as u8),
@@ -111,7 +203,7 @@ impl WireEncoder {
             ]);
         } else if value < (1 << (7 * 7)) {
             // 7 bytes
-            self.buf.extend_from_slice(&[
+            buf.extend_from_slice(&[
                 0b1000_0000 | (value as u8 & 0b0111_1111),
                 0b1000_0000 | ((value >> 7) as u8),
                 0b1000_0000 | ((value >> (7 * 2)) as u8),
@@ -122,7 +214,7 @@ impl WireEncoder {
             ]);
         } else if value < (1 << (7 * 8)) {
             // 8 bytes
-            self.buf.extend_from_slice(&[
+            buf.extend_from_slice(&[
                 0b1000_0000 | (value as u8),
                 0b1000_0000 | ((value >> 7) as u8),
                 0b1000_0000 | ((value >> (7 * 2)) as u8),
@@ -134,7 +226,7 @@ impl WireEncoder {
             ]);
         } else if value < (1 << (7 * 9)) {
             // 9 bytes
-            self.buf.extend_from_slice(&[
+            buf.extend_from_slice(&[
                 0b1000_0000 | (value as u8),
                 0b1000_0000 | ((value >> 7) as u8),
                 0b1000_0000 | ((value >> (7 * 2)) as u8),
@@ -147,7 +239,7 @@ impl WireEncoder {
             ]);
         } else {
             // 10 bytes
-            self.buf.extend_from_slice(&[
+            buf.extend_from_slice(&[
                 0b1000_0000 | (value as u8),
                 0b1000_0000 | ((value >> 7) as u8),
                 0b1000_0000 | ((value >> (7 * 2)) as u8),
@@ -162,65 +254,35 @@ impl WireEncoder {
         }
     }
 
-    /// Add a protobuf `int64` to the `buf` encoded as VARINT.
-    #[inline]
-    pub fn add_var_int64(&mut self, value: i64) {
-        self.add_var_uint64(u64::from_ne_bytes(value.to_ne_bytes()))
-    }
-
-    /// Add a protobuf `sint64` to the `buf` encoded as VARINT with zig zag encoding.
-    #[inline]
-    pub fn add_var_sint64(&mut self, value: i64) {
-        let zigzag = (value << 1) ^ (value >> 63);
-        self.add_var_uint64(u64::from_ne_bytes(zigzag.to_ne_bytes()))
-    }
-
-    /// Add a protobuf `bool` to the `buf` encoded as VARINT.
-    #[inline]
-    pub fn add_bool(&mut self, value: bool) {
-        self.buf.push(if value { 1 } else { 0 });
+    /// Values with all bit lengths.
+    fn any_bit_len() -> impl Strategy<Value = u64> {
+        (any::<u64>(), 0u32..64).prop_map(|(value, shift)| value >> shift)
     }
 
-    /// Add the given [`u32`] as `fixed32`` to the `buf`.
-    #[inline]
-    pub fn add_fixed32(&mut self, value: u32) {
-        self.buf.extend_from_slice(&value.to_le_bytes());
-    }
-
-    /// Add the given [`i32`] as `sfixed32`` to the `buf`.
-    #[inline]
-    pub fn add_sfixed32(&mut self, value: i32) {
-        self.buf.extend_from_slice(&value.to_le_bytes());
-    }
-
-    /// Add the given [`u64`] as fixed64 to the `buf`.
-    #[inline]
-    pub fn add_fixed64(&mut self, value: u64) {
-        self.buf.extend_from_slice(&value.to_le_bytes());
-    }
-
-    /// Add the given [`i64`] as sfixed64 to the `buf`.
-    #[inline]
-    pub fn add_sfixed64(&mut self, value: i64) {
-        self.buf.extend_from_slice(&value.to_le_bytes());
-    }
-
-    /// Add the given [`f32`] as float to the `buf`.
-    #[inline]
-    pub fn add_float(&mut self, value: f32) {
-        self.buf.extend_from_slice(&value.to_le_bytes());
-    }
-
-    /// Add the given [`f64`] as double to the `buf`.
-    #[inline]
-    pub fn add_double(&mut self, value: f64) {
-        self.buf.extend_from_slice(&value.to_le_bytes());
+    proptest! {
+        #[test]
+        fn add_var_uint64_same_as_cascade(
+            prefix_len in 0usize..16,
+            values in prop::collection::vec(any_bit_len(), 1..8)
+        ) {
+            let mut encoder = WireEncoder::with_buf(vec![0xaa; prefix_len]);
+            let mut expected = vec![0xaa; prefix_len];
+            for value in values {
+                encoder.add_var_uint64(value);
+                cascade_add_var_uint64(&mut expected, value);
+                prop_assert_eq!(&expected, &encoder.buf);
+            }
+        }
     }
 
-    /// Destroys the [`WireEncoder`] and returns the `buf` of the destroyed
-    /// [`WireEncoder`].
-    #[inline]
-    pub fn take_buf(self) -> Vec<u8> {
-        self.buf
+    proptest! {
+        #[test]
+        fn add_var_uint32_same_as_cascade(value in any::<u32>()) {
+            let mut encoder = WireEncoder::new();
+            encoder.add_var_uint32(value);
+            let mut expected = Vec::new();
+            cascade_add_var_uint64(&mut expected, u64::from(value));
+            prop_assert_eq!(expected, encoder.buf);
+        }
     }
 }
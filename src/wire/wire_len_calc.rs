@@ -1,3 +1,5 @@
+use crate::wire::WireVarInt;
+
 /// Helper to calculate the number of bytes used when encoding a
 /// given set of values.a
 #[derive(Default, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
@@ -12,45 +14,15 @@ impl WireLenCalc {
     }
 
     /// Add the needed len to encode the given [`u32`] as VARINT to the overall `len`.
+    #[inline]
     pub fn add_var_uint32(&mut self, value: u32) {
-        // determine needed byte length
-        if value < (1 << 7) {
-            self.len += 1;
-        } else if value < (1 << (7 * 2)) {
-            self.len += 2;
-        } else if value < (1 << (7 * 3)) {
-            self.len += 3;
-        } else if value < (1 << (7 * 4)) {
-            self.len += 4;
-        } else {
-            self.len += 5;
-        }
+        self.len += WireVarInt::uint32_byte_len(value) as usize;
     }
 
     /// Add the needed len to encode the given [`u64`] as VARINT to the overall `len`.
+    #[inline]
     pub fn add_var_uint64(&mut self, value: u64) {
-        // determine needed byte length
-        if value < (1 << 7) {
-            self.len += 1;
-        } else if value < (1 << (7 * 2)) {
-            self.len += 2;
-        } else if value < (1 << (7 * 3)) {
-            self.len += 3;
-        } else if value < (1 << (7 * 4)) {
-            self.len += 4;
-        } else if value < (1 << (7 * 5)) {
-            self.len += 5;
-        } else if value < (1 << (7 * 6)) {
-            self.len += 6;
-        } else if value < (1 << (7 * 7)) {
-            self.len += 7;
-        } else if value < (1 << (7 * 8)) {
-            self.len += 8;
-        } else if value < (1 << (7 * 9)) {
-            self.len += 9;
-        } else {
-            self.len += 10;
-        }
+        self.len += WireVarInt::uint64_byte_len(value) as usize;
     }
 
     /// Add the needed len to encode a fixed32 to the overall `len`.
@@ -10,20 +10,88 @@ pub struct WireLenRef<'a> {
 }
 
 impl<'a> WireLenRef<'a> {
+    /// Maximum sub-message nesting depth of [`WireLenRef::kind_at_depth`]
+    /// (deeper LEN values are never classified as sub-message).
+    pub const MAX_SUB_MSG_DEPTH: usize = 100;
+
     #[inline]
-    pub fn as_bytes(&self) -> &[u8] {
+    pub fn as_bytes(&self) -> &'a [u8] {
         self.data
     }
 
     #[inline]
-    pub fn try_as_string(&self) -> Result<&str, Utf8Error> {
+    pub fn try_as_string(&self) -> Result<&'a str, Utf8Error> {
         core::str::from_utf8(self.data)
     }
 
+    /// Interpret the data as an entry of a protobuf `map<K, V>` field.
+    #[inline]
+    pub fn try_as_map_entry(&self) -> Result<MapEntryRef<'a>, DecodeError> {
+        MapEntryRef::decode(self.data)
+    }
+
     #[inline]
     pub fn as_sub_msg(&self) -> MsgDecoder<'a> {
         MsgDecoder {
             wire_decoder: WireDecoder { data: self.data },
         }
     }
+
+    /// Guesses the type of the data (e.g. when decoding without a schema).
+    ///
+    /// Returns all interpretations ranked by their confidence (most likely
+    /// first, see [`LenKind::confidence`] for the heuristics). As
+    /// [`LenKind::Bytes`] is always possible the first guess is never
+    /// impossible.
+    ///
+    /// ```
+    /// use protobin::wire::*;
+    ///
+    /// let guesses = WireLenRef { data: b"hello" }.classify();
+    /// assert_eq!(LenKind::String, guesses[0].kind);
+    ///
+    /// // message { uint32 a = 1; }
+    /// let guesses = WireLenRef { data: &[0x08, 0x03] }.classify();
+    /// assert_eq!(LenKind::SubMsg, guesses[0].kind);
+    ///
+    /// // packed [300, 1]
+    /// let guesses = WireLenRef { data: &[0xac, 0x02, 0x01] }.classify();
+    /// assert_eq!(LenKind::PackedVarInt, guesses[0].kind);
+    /// ```
+    pub fn classify(&self) -> [LenGuess; 6] {
+        let mut guesses = LenKind::ALL.map(|kind| LenGuess {
+            kind,
+            confidence: kind.confidence(self.data),
+        });
+        // stable order for equal confidences
+        guesses.sort_unstable_by(|a, b| b.confidence.cmp(&a.confidence).then(a.kind.cmp(&b.kind)));
+        guesses
+    }
+
+    /// Returns the most likely interpretation of the data (see
+    /// [`WireLenRef::classify`]) for a LEN value at the given sub-message
+    /// nesting depth.
+    ///
+    /// From [`WireLenRef::MAX_SUB_MSG_DEPTH`] on the data is not classified
+    /// as [`LenKind::SubMsg`], so recursive decoding of messages of unknown
+    /// type ends even for maliciously deep nested data.
+    ///
+    /// ```
+    /// use protobin::wire::*;
+    ///
+    /// // message { uint32 a = 1; }
+    /// let value = WireLenRef { data: &[0x08, 0x03] };
+    /// assert_eq!(LenKind::SubMsg, value.kind_at_depth(0));
+    /// assert_eq!(
+    ///     LenKind::PackedVarInt,
+    ///     value.kind_at_depth(WireLenRef::MAX_SUB_MSG_DEPTH)
+    /// );
+    /// ```
+    pub fn kind_at_depth(&self, depth: usize) -> LenKind {
+        self.classify()
+            .iter()
+            .map(|guess| guess.kind)
+            .find(|kind| *kind != LenKind::SubMsg || depth < Self::MAX_SUB_MSG_DEPTH)
+            .unwrap_or(LenKind::Bytes)
+    }
 }
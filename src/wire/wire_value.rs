@@ -0,0 +1,56 @@
+use crate::wire::*;
+use alloc::vec::Vec;
+
+/// Owned version of [`WireValueRef`] (the data of LEN values is copied
+/// into a [`Vec`]).
+///
+/// Can be stored or sent to other threads after the decoded data is gone
+/// (e.g. as part of a [`Message`](crate::decode::Message)). Use
+/// [`WireValue::as_ref`] to interpret the value.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum WireValue {
+    /// Variable sized integer used for `int32`,
+    /// `int64`, `uint32`, `uint64`, `sint32`, `sint64`,
+    /// `bool` and `enum`.
+    VarInt(WireVarInt),
+    /// Fixed size 64bit value used for `fixed64`,
+    /// `sfixed64` and `double`.
+    I64(WireI64),
+    /// LEN wire type that can be interpred as `string`, `bytes`,
+    /// "embedded messages" and "packed repeated fields" Prototype
+    /// types.
+    Len(Vec<u8>),
+    SGroup,
+    EGroup,
+    /// Fixed size 32bit value used for `fixed32`,
+    /// `sfixed32` and `float`.
+    I32(WireI32),
+}
+
+impl WireValue {
+    /// Returns a [`WireValueRef`] referencing the value (e.g. to use
+    /// [`WireValueRef::try_as_uint32`]).
+    pub fn as_ref(&self) -> WireValueRef<'_> {
+        match self {
+            WireValue::VarInt(v) => WireValueRef::VarInt(*v),
+            WireValue::I64(v) => WireValueRef::I64(*v),
+            WireValue::Len(data) => WireValueRef::Len(WireLenRef { data }),
+            WireValue::SGroup => WireValueRef::SGroup,
+            WireValue::EGroup => WireValueRef::EGroup,
+            WireValue::I32(v) => WireValueRef::I32(*v),
+        }
+    }
+}
+
+impl<'a> From<WireValueRef<'a>> for WireValue {
+    fn from(value: WireValueRef<'a>) -> Self {
+        match value {
+            WireValueRef::VarInt(v) => WireValue::VarInt(v),
+            WireValueRef::I64(v) => WireValue::I64(v),
+            WireValueRef::Len(len) => WireValue::Len(len.data.to_vec()),
+            WireValueRef::SGroup => WireValue::SGroup,
+            WireValueRef::EGroup => WireValue::EGroup,
+            WireValueRef::I32(v) => WireValue::I32(v),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use crate::wire::*;
+use bytes::Bytes;
+
+/// Same as [`WireValueRef`] but the data of LEN values is stored as
+/// [`Bytes`] (a reference counted slice of the decoded data), so it can
+/// outlive the decoder without copying (see
+/// [`BytesMsgDecoder`](crate::decode::BytesMsgDecoder)).
+///
+/// Use [`WireValueBytes::as_ref`] to interpret the value.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum WireValueBytes {
+    /// Variable sized integer used for `int32`,
+    /// `int64`, `uint32`, `uint64`, `sint32`, `sint64`,
+    /// `bool` and `enum`.
+    VarInt(WireVarInt),
+    /// Fixed size 64bit value used for `fixed64`,
+    /// `sfixed64` and `double`.
+    I64(WireI64),
+    /// LEN wire type that can be interpred as `string`, `bytes`,
+    /// "embedded messages" and "packed repeated fields" Prototype
+    /// types.
+    Len(Bytes),
+    SGroup,
+    EGroup,
+    /// Fixed size 32bit value used for `fixed32`,
+    /// `sfixed32` and `float`.
+    I32(WireI32),
+}
+
+impl WireValueBytes {
+    /// Converts a value decoded from `data` (LEN values have to be slices
+    /// of `data`, their data is not copied).
+    pub(crate) fn from_ref(data: &Bytes, value: WireValueRef<'_>) -> WireValueBytes {
+        match value {
+            WireValueRef::VarInt(v) => WireValueBytes::VarInt(v),
+            WireValueRef::I64(v) => WireValueBytes::I64(v),
+            WireValueRef::Len(len) => WireValueBytes::Len(data.slice_ref(len.data)),
+            WireValueRef::SGroup => WireValueBytes::SGroup,
+            WireValueRef::EGroup => WireValueBytes::EGroup,
+            WireValueRef::I32(v) => WireValueBytes::I32(v),
+        }
+    }
+
+    /// Returns a [`WireValueRef`] referencing the value (e.g. to use
+    /// [`WireValueRef::try_as_uint32`]).
+    pub fn as_ref(&self) -> WireValueRef<'_> {
+        match self {
+            WireValueBytes::VarInt(v) => WireValueRef::VarInt(*v),
+            WireValueBytes::I64(v) => WireValueRef::I64(*v),
+            WireValueBytes::Len(data) => WireValueRef::Len(WireLenRef { data }),
+            WireValueBytes::SGroup => WireValueRef::SGroup,
+            WireValueBytes::EGroup => WireValueRef::EGroup,
+            WireValueBytes::I32(v) => WireValueRef::I32(*v),
+        }
+    }
+
+    /// Returns the data of a LEN value (without copying).
+    pub fn try_into_len(self) -> Result<Bytes, WireValueIntoError> {
+        match self {
+            WireValueBytes::Len(data) => Ok(data),
+            other => Err(WireValueIntoError::UnexpectedType {
+                expected: WireType::Len,
+                actual: other.as_ref().write_type(),
+            }),
+        }
+    }
+}
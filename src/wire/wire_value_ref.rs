@@ -91,6 +91,25 @@ impl<'a> WireValueRef<'a> {
         }
     }
 
+    /// Try to interpret the value as a Protobuf `enum` (varint,
+    /// same encoding as `int32`).
+    ///
+    /// If the value is not a [`WireValueRef::VarInt`] or if the
+    /// [`WireVarInt`] contains a value that is bigger then
+    /// 32 bits an error is returned instead.
+    #[inline]
+    pub fn try_as_enum(&self) -> Result<i32, WireValueIntoError> {
+        use WireValueIntoError::*;
+        if let WireValueRef::VarInt(v) = &self {
+            v.try_as_enum().ok_or(VarIntValueTooBigFor32Bit(v.raw()))
+        } else {
+            Err(UnexpectedType {
+                expected: WireType::VarInt,
+                actual: self.write_type(),
+            })
+        }
+    }
+
     /// Try to interpret the value as a Protobuf `sint32` (varint,
     /// ZigZag encoding).
     ///
@@ -166,6 +185,24 @@ impl<'a> WireValueRef<'a> {
         }
     }
 
+    /// Try to interpret the value as a LEN value (`string`, `bytes`,
+    /// embedded message or packed repeated field).
+    ///
+    /// If the value is not a [`WireValueRef::Len`] an error is returned
+    /// instead.
+    #[inline]
+    pub fn try_as_len(&self) -> Result<WireLenRef<'a>, WireValueIntoError> {
+        use WireValueIntoError::*;
+        if let WireValueRef::Len(v) = &self {
+            Ok(*v)
+        } else {
+            Err(UnexpectedType {
+                expected: WireType::Len,
+                actual: self.write_type(),
+            })
+        }
+    }
+
     /// Try to interpret the value as a Protobuf `fixed32`.
     ///
     /// If the value is not a [`WireValueRef::I32`] `None` is
@@ -177,7 +214,7 @@ impl<'a> WireValueRef<'a> {
             Ok(v.as_fixed32())
         } else {
             Err(UnexpectedType {
-                expected: WireType::VarInt,
+                expected: WireType::I32,
                 actual: self.write_type(),
             })
         }
@@ -194,7 +231,7 @@ impl<'a> WireValueRef<'a> {
             Ok(v.as_sfixed32())
         } else {
             Err(UnexpectedType {
-                expected: WireType::VarInt,
+                expected: WireType::I32,
                 actual: self.write_type(),
             })
         }
@@ -211,7 +248,7 @@ impl<'a> WireValueRef<'a> {
             Ok(v.as_float())
         } else {
             Err(UnexpectedType {
-                expected: WireType::VarInt,
+                expected: WireType::I32,
                 actual: self.write_type(),
             })
         }
@@ -228,7 +265,7 @@ impl<'a> WireValueRef<'a> {
             Ok(v.as_fixed64())
         } else {
             Err(UnexpectedType {
-                expected: WireType::VarInt,
+                expected: WireType::I64,
                 actual: self.write_type(),
             })
         }
@@ -245,7 +282,7 @@ impl<'a> WireValueRef<'a> {
             Ok(v.as_sfixed64())
         } else {
             Err(UnexpectedType {
-                expected: WireType::VarInt,
+                expected: WireType::I64,
                 actual: self.write_type(),
             })
         }
@@ -262,7 +299,7 @@ impl<'a> WireValueRef<'a> {
             Ok(v.as_double())
         } else {
             Err(UnexpectedType {
-                expected: WireType::VarInt,
+                expected: WireType::I64,
                 actual: self.write_type(),
             })
         }
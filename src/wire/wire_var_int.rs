@@ -28,10 +28,17 @@ impl WireVarInt {
         WireVarInt(u64::from(value))
     }
 
-    /// Encode the given [`i32`] as Protobuf `int32` (encodes using two’s complements).
+    /// Encode the given [`i32`] as Protobuf `int32` (encodes using two’s complements,
+    /// negative values are sign extended to 64 bits).
     #[inline]
     pub fn from_int32(value: i32) -> WireVarInt {
-        WireVarInt(u64::from(u32::from_ne_bytes(value.to_ne_bytes())))
+        WireVarInt::from_int64(i64::from(value))
+    }
+
+    /// Encode the given [`i32`] as Protobuf `enum` (same encoding as `int32`).
+    #[inline]
+    pub fn from_enum(value: i32) -> WireVarInt {
+        WireVarInt::from_int32(value)
     }
 
     /// Encode the given [`i32`] as Protobuf `sint32` (encodes using ZigZag).
@@ -93,16 +100,25 @@ impl WireVarInt {
     /// Try to interpret the varint as a Protobuf `int32` (encoded
     /// using two’s complements).
     ///
-    /// If the varint contains a value that is bigger then 32 bits
-    /// `None` is returned instead.
+    /// Negative values are accepted both sign extended to 64 bits (as
+    /// required by the protobuf specification) and truncated to 32 bits
+    /// (as written by some older encoders). If the varint contains any
+    /// other value that does not fit into 32 bits `None` is returned instead.
     pub fn try_as_int32(&self) -> Option<i32> {
-        if self.0 > u64::from(u32::MAX) {
-            None
-        } else {
+        if self.0 <= u64::from(u32::MAX) {
             Some(i32::from_ne_bytes((self.0 as u32).to_ne_bytes()))
+        } else {
+            i32::try_from(self.as_int64()).ok()
         }
     }
 
+    /// Try to interpret the varint as a Protobuf `enum` (same encoding
+    /// as `int32`, see [`Self::try_as_int32`]).
+    #[inline]
+    pub fn try_as_enum(&self) -> Option<i32> {
+        self.try_as_int32()
+    }
+
     /// Try to interpret the varint as a Protobuf `sint32` (encoded
     /// using ZigZag).
     ///
@@ -139,63 +155,36 @@ impl WireVarInt {
     ///
     /// Tag is the value written before a message field
     /// value is written.
+    #[inline]
     pub fn tag_byte_len(field_number: FieldNumber) -> i32 {
-        if field_number.0 < (1 << (7 - 3)) {
-            1
-        } else if field_number.0 < (1 << ((7 * 2) - 3)) {
-            2
-        } else if field_number.0 < (1 << ((7 * 3) - 3)) {
-            3
-        } else if field_number.0 < (1 << ((7 * 4) - 3)) {
-            4
-        } else {
-            5
-        }
+        // field numbers are limited to 29 bits, so the tag fits in 32 bits
+        Self::varint32_len(field_number.0 << 3)
     }
 
+    #[inline]
     fn varint32_len(value: u32) -> i32 {
-        if value < (1 << 7) {
-            1
-        } else if value < (1 << (7 * 2)) {
-            2
-        } else if value < (1 << (7 * 3)) {
-            3
-        } else if value < (1 << (7 * 4)) {
-            4
-        } else {
-            5
-        }
+        Self::varint64_len(u64::from(value))
     }
 
+    /// Returns the VARINT byte length based on the number of used bits
+    /// (`ceil(bits / 7)` calculated as `(bits * 9 + 64) / 64`, zero is
+    /// treated as one bit).
+    #[inline]
     fn varint64_len(value: u64) -> i32 {
-        if value < (1 << 7) {
-            1
-        } else if value < (1 << (7 * 2)) {
-            2
-        } else if value < (1 << (7 * 3)) {
-            3
-        } else if value < (1 << (7 * 4)) {
-            4
-        } else if value < (1 << (7 * 5)) {
-            5
-        } else if value < (1 << (7 * 6)) {
-            6
-        } else if value < (1 << (7 * 7)) {
-            7
-        } else if value < (1 << (7 * 8)) {
-            8
-        } else if value < (1 << (7 * 9)) {
-            9
-        } else {
-            10
-        }
+        let bits = 64 - (value | 1).leading_zeros() as i32;
+        (bits * 9 + 64) / 64
     }
 
-    /// Returns the encoded byte len of a "int32" encoded
-    /// as varint (encoded using two’s complements).
+    /// Returns the encoded byte len of a "int32" or "enum" encoded
+    /// as varint (encoded using two’s complements, negative values
+    /// are sign extended to 64 bits and always use 10 bytes).
     #[inline]
     pub fn int32_byte_len(value: i32) -> i32 {
-        Self::varint32_len(u32::from_ne_bytes(value.to_ne_bytes()))
+        if value < 0 {
+            10
+        } else {
+            Self::varint32_len(value as u32)
+        }
     }
 
     /// Returns the encoded byte len of a "int64" encoded
@@ -241,6 +230,57 @@ mod tests {
     use crate::wire::*;
     use proptest::prelude::*;
 
+    /// Previous if/else cascade implementation used as reference.
+    fn cascade_varint64_len(value: u64) -> i32 {
+        if value < (1 << 7) {
+            1
+        } else if value < (1 << (7 * 2)) {
+            2
+        } else if value < (1 << (7 * 3)) {
+            3
+        } else if value < (1 << (7 * 4)) {
+            4
+        } else if value < (1 << (7 * 5)) {
+            5
+        } else if value < (1 << (7 * 6)) {
+            6
+        } else if value < (1 << (7 * 7)) {
+            7
+        } else if value < (1 << (7 * 8)) {
+            8
+        } else if value < (1 << (7 * 9)) {
+            9
+        } else {
+            10
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn byte_len_same_as_cascade(
+            value in (any::<u64>(), 0u32..64).prop_map(|(value, shift)| value >> shift),
+            field_number in 1u32..(1 << 29)
+        ) {
+            let expected = cascade_varint64_len(value);
+            prop_assert_eq!(expected, WireVarInt::uint64_byte_len(value));
+            let mut calc = WireLenCalc::new();
+            calc.add_var_uint64(value);
+            prop_assert_eq!(expected as usize, calc.len);
+
+            let value32 = value as u32;
+            let expected32 = cascade_varint64_len(u64::from(value32));
+            prop_assert_eq!(expected32, WireVarInt::uint32_byte_len(value32));
+            let mut calc = WireLenCalc::new();
+            calc.add_var_uint32(value32);
+            prop_assert_eq!(expected32 as usize, calc.len);
+
+            prop_assert_eq!(
+                cascade_varint64_len(u64::from(field_number << 3)),
+                WireVarInt::tag_byte_len(crate::FieldNumber::try_from(field_number).unwrap())
+            );
+        }
+    }
+
     proptest! {
         #[test]
         fn from_and_as_bool(
@@ -299,17 +339,26 @@ mod tests {
         #[test]
         fn from_and_as_int32(
             value in any::<i32>(),
-            bad_value in (u32::MAX as u64 + 1)..=u64::MAX,
+            bad_value in (u32::MAX as u64 + 1)..(i32::MIN as i64 as u64),
         ) {
             // ok case
             {
                 let v = WireVarInt::from_int32(value);
+                prop_assert_eq!(i64::from(value), v.as_int64());
+                prop_assert_eq!(Some(value), v.try_as_int32());
+                prop_assert_eq!(Some(value), v.try_as_enum());
+                prop_assert_eq!(WireVarInt::int32_byte_len(value), WireVarInt::int64_byte_len(i64::from(value)));
+            }
+            // ok case (negative value truncated to 32 bits)
+            {
+                let v = WireVarInt::from_raw(u64::from(value as u32));
                 prop_assert_eq!(Some(value), v.try_as_int32());
             }
             // err
             {
                 let v = WireVarInt::from_raw(bad_value);
                 prop_assert_eq!(None, v.try_as_int32());
+                prop_assert_eq!(None, v.try_as_enum());
             }
         }
     }
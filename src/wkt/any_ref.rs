@@ -0,0 +1,178 @@
+use crate::{builders::MsgScribe, decode::MsgDecoder, wkt::WktError, FieldNumber};
+
+/// Well-known type `google.protobuf.Any` (arbitrary serialized message
+/// together with a URL describing its type).
+///
+/// ```proto
+/// message Any {
+///   string type_url = 1;
+///   bytes value = 2;
+/// }
+/// ```
+///
+/// # Packing without an intermediate buffer
+///
+/// As `bytes` & embedded messages share the same encoding the packed
+/// message can also be serialized directly as sub-message into the
+/// `value` field:
+///
+/// ```
+/// use protobin::{builders::*, wkt::AnyRef};
+///
+/// fn ser<S: MsgScribe>(mut s: S) -> S::End {
+///     let any = 1.try_into().unwrap();
+///     s.start_msg(any);
+///     s.add_string(AnyRef::TYPE_URL, "type.googleapis.com/example.Person");
+///     s.start_msg(AnyRef::VALUE);
+///     s.add_string(1.try_into().unwrap(), "Alice");
+///     s.end_msg(AnyRef::VALUE);
+///     s.end_msg(any);
+///     s.end()
+/// }
+/// ```
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct AnyRef<'a> {
+    /// URL identifying the type of the serialized message (e.g.
+    /// `type.googleapis.com/google.protobuf.Duration`).
+    pub type_url: &'a str,
+    /// Serialized message.
+    pub value: &'a [u8],
+}
+
+impl<'a> AnyRef<'a> {
+    /// Fully qualified protobuf type name.
+    pub const TYPE_NAME: &'static str = "google.protobuf.Any";
+
+    /// Default prefix of type URLs.
+    pub const DEFAULT_TYPE_URL_PREFIX: &'static str = "type.googleapis.com/";
+
+    /// Field number of `type_url`.
+    pub const TYPE_URL: FieldNumber = FieldNumber(1);
+
+    /// Field number of `value`.
+    pub const VALUE: FieldNumber = FieldNumber(2);
+
+    /// Packs an already serialized message.
+    pub fn pack(type_url: &'a str, value: &'a [u8]) -> AnyRef<'a> {
+        AnyRef { type_url, value }
+    }
+
+    /// Returns the fully qualified type name of the packed message (part of
+    /// the type URL after the last `/`).
+    pub fn type_name(&self) -> &'a str {
+        match self.type_url.rfind('/') {
+            Some(i) => &self.type_url[i + 1..],
+            None => self.type_url,
+        }
+    }
+
+    /// Returns a decoder for the packed message if the type name of the
+    /// packed message matches the given fully qualified type name.
+    pub fn unpack(&self, type_name: &str) -> Option<MsgDecoder<'a>> {
+        if self.type_name() == type_name {
+            Some(MsgDecoder::new(self.value))
+        } else {
+            None
+        }
+    }
+
+    /// Adds the fields of the any (fields with default values are skipped).
+    pub fn add_fields<S: MsgScribe>(&self, s: &mut S) {
+        if !self.type_url.is_empty() {
+            s.add_string(Self::TYPE_URL, self.type_url);
+        }
+        if !self.value.is_empty() {
+            s.add_bytes(Self::VALUE, self.value);
+        }
+    }
+
+    /// Adds the any as sub-message field.
+    pub fn add_msg<S: MsgScribe>(&self, s: &mut S, field_number: FieldNumber) {
+        s.start_msg(field_number);
+        self.add_fields(s);
+        s.end_msg(field_number);
+    }
+
+    /// Decodes an any from the encoded message data.
+    pub fn decode(data: &'a [u8]) -> Result<AnyRef<'a>, WktError> {
+        let mut result = AnyRef::default();
+        for record in MsgDecoder::new(data) {
+            let record = record?;
+            let f = record.field_number;
+            if f == Self::TYPE_URL {
+                let len_ref = record.value.try_as_len().map_err(WktError::value(f))?;
+                result.type_url = core::str::from_utf8(len_ref.data)?;
+            } else if f == Self::VALUE {
+                result.value = record.value.try_as_len().map_err(WktError::value(f))?.data;
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "alloc")]
+    use crate::builders::*;
+    use crate::wkt::*;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn pack_unpack() {
+        fn ser_duration<S: MsgScribe>(mut s: S) -> S::End {
+            Duration {
+                seconds: 3,
+                nanos: 0,
+            }
+            .add_fields(&mut s);
+            s.end()
+        }
+        fn ser_any<S: MsgScribe>(any: &AnyRef<'_>, mut s: S) -> S::End {
+            any.add_fields(&mut s);
+            s.end()
+        }
+
+        let mut builder = MsgBuilder::new();
+        let step2 = ser_duration(builder.start(None));
+        let duration = ser_duration(step2).to_vec();
+
+        let type_url = format!("{}{}", AnyRef::DEFAULT_TYPE_URL_PREFIX, Duration::TYPE_NAME);
+        let any = AnyRef::pack(&type_url, &duration);
+        let step2 = ser_any(&any, builder.start(None));
+        let bytes = ser_any(&any, step2);
+
+        let decoded = AnyRef::decode(bytes).unwrap();
+        assert_eq!(any, decoded);
+        assert_eq!(Duration::TYPE_NAME, decoded.type_name());
+        assert!(decoded.unpack(Timestamp::TYPE_NAME).is_none());
+        let unpacked = decoded.unpack(Duration::TYPE_NAME).unwrap();
+        assert_eq!(
+            Ok(Duration {
+                seconds: 3,
+                nanos: 0
+            }),
+            Duration::decode(unpacked.wire_decoder.data)
+        );
+    }
+
+    #[test]
+    fn type_name() {
+        assert_eq!("a.B", AnyRef::pack("a.B", &[]).type_name());
+        assert_eq!("a.B", AnyRef::pack("example.com/x/a.B", &[]).type_name());
+        assert_eq!("", AnyRef::pack("example.com/", &[]).type_name());
+    }
+
+    #[test]
+    fn decode_error() {
+        // invalid UTF-8 in type_url
+        assert!(matches!(
+            AnyRef::decode(&[0x0a, 0x01, 0xff]),
+            Err(WktError::Utf8(_))
+        ));
+        // value with varint wire type
+        assert!(matches!(
+            AnyRef::decode(&[0x10, 0x01]),
+            Err(WktError::Value { .. })
+        ));
+    }
+}
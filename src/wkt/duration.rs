@@ -0,0 +1,203 @@
+use crate::{builders::MsgScribe, decode::MsgDecoder, wkt::WktError, FieldNumber};
+
+/// Well-known type `google.protobuf.Duration` (signed, fixed-length span
+/// of time).
+///
+/// ```proto
+/// message Duration {
+///   int64 seconds = 1;
+///   int32 nanos = 2;
+/// }
+/// ```
+///
+/// Can be converted from & to [`std::time::Duration`] (as long as the
+/// duration is not negative).
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Duration {
+    /// Signed seconds of the span of time.
+    pub seconds: i64,
+    /// Signed fractions of a second at nanosecond resolution
+    /// (`-999_999_999..=999_999_999`). Must have the same sign as
+    /// `seconds` if `seconds` is not zero.
+    pub nanos: i32,
+}
+
+impl Duration {
+    /// Fully qualified protobuf type name.
+    pub const TYPE_NAME: &'static str = "google.protobuf.Duration";
+
+    /// Maximum absolute `seconds` value (approximately 10,000 years).
+    pub const MAX_SECONDS: i64 = 315_576_000_000;
+
+    /// Field number of `seconds`.
+    pub const SECONDS: FieldNumber = FieldNumber(1);
+
+    /// Field number of `nanos`.
+    pub const NANOS: FieldNumber = FieldNumber(2);
+
+    /// Returns true if `seconds` & `nanos` are in the ranges allowed by
+    /// the protobuf definition and have matching signs.
+    pub fn is_valid(&self) -> bool {
+        (-Self::MAX_SECONDS..=Self::MAX_SECONDS).contains(&self.seconds)
+            && (-999_999_999..=999_999_999).contains(&self.nanos)
+            && !(self.seconds > 0 && self.nanos < 0)
+            && !(self.seconds < 0 && self.nanos > 0)
+    }
+
+    /// Adds the fields of the duration (fields with default values are
+    /// skipped).
+    pub fn add_fields<S: MsgScribe>(&self, s: &mut S) {
+        if self.seconds != 0 {
+            s.add_int64(Self::SECONDS, self.seconds);
+        }
+        if self.nanos != 0 {
+            s.add_int32(Self::NANOS, self.nanos);
+        }
+    }
+
+    /// Adds the duration as sub-message field.
+    pub fn add_msg<S: MsgScribe>(&self, s: &mut S, field_number: FieldNumber) {
+        s.start_msg(field_number);
+        self.add_fields(s);
+        s.end_msg(field_number);
+    }
+
+    /// Decodes a duration from the encoded message data.
+    pub fn decode(data: &[u8]) -> Result<Duration, WktError> {
+        let mut result = Duration::default();
+        for record in MsgDecoder::new(data) {
+            let record = record?;
+            let f = record.field_number;
+            if f == Self::SECONDS {
+                result.seconds = record.value.try_as_int64().map_err(WktError::value(f))?;
+            } else if f == Self::NANOS {
+                result.nanos = record.value.try_as_int32().map_err(WktError::value(f))?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl TryFrom<core::time::Duration> for Duration {
+    type Error = WktError;
+
+    /// Converts the [`std::time::Duration`] to a [`Duration`].
+    ///
+    /// Returns [`WktError::OutOfRange`] if the duration is longer than
+    /// [`Duration::MAX_SECONDS`].
+    fn try_from(value: core::time::Duration) -> Result<Self, Self::Error> {
+        let seconds = i64::try_from(value.as_secs()).map_err(|_| WktError::OutOfRange)?;
+        if seconds > Self::MAX_SECONDS {
+            return Err(WktError::OutOfRange);
+        }
+        Ok(Duration {
+            seconds,
+            nanos: value.subsec_nanos() as i32,
+        })
+    }
+}
+
+impl TryFrom<Duration> for core::time::Duration {
+    type Error = WktError;
+
+    /// Converts the [`Duration`] to a [`std::time::Duration`].
+    ///
+    /// Returns [`WktError::NegativeDuration`] if the duration is negative
+    /// and [`WktError::OutOfRange`] if it is not valid (see
+    /// [`Duration::is_valid`]).
+    fn try_from(value: Duration) -> Result<Self, Self::Error> {
+        if !value.is_valid() {
+            Err(WktError::OutOfRange)
+        } else if value.seconds < 0 || value.nanos < 0 {
+            Err(WktError::NegativeDuration)
+        } else {
+            Ok(core::time::Duration::new(
+                value.seconds as u64,
+                value.nanos as u32,
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "alloc")]
+    use crate::builders::*;
+    use crate::wkt::*;
+
+    #[cfg(feature = "alloc")]
+    fn ser<S: MsgScribe>(d: &Duration, mut s: S) -> S::End {
+        d.add_msg(&mut s, 3.try_into().unwrap());
+        s.end()
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn encode_decode() {
+        let mut builder = MsgBuilder::new();
+        for d in [
+            Duration::default(),
+            Duration {
+                seconds: 1,
+                nanos: 500,
+            },
+            Duration {
+                seconds: -1,
+                nanos: -500,
+            },
+        ] {
+            let step2 = ser(&d, builder.start(None));
+            let bytes = ser(&d, step2);
+            let mut decoder = crate::decode::MsgDecoder::new(bytes);
+            let record = decoder.next().unwrap().unwrap();
+            let data = record.value.try_as_len().unwrap().data;
+            assert_eq!(Ok(d), Duration::decode(data));
+        }
+    }
+
+    #[test]
+    fn std_duration_conversion() {
+        // ok
+        {
+            let std = std::time::Duration::new(5, 6);
+            let d = Duration {
+                seconds: 5,
+                nanos: 6,
+            };
+            assert_eq!(Ok(d), Duration::try_from(std));
+            assert_eq!(Ok(std), std::time::Duration::try_from(d));
+        }
+        // negative
+        for d in [
+            Duration {
+                seconds: -1,
+                nanos: 0,
+            },
+            Duration {
+                seconds: 0,
+                nanos: -1,
+            },
+        ] {
+            assert_eq!(
+                Err(WktError::NegativeDuration),
+                std::time::Duration::try_from(d)
+            );
+        }
+        // out of range
+        {
+            assert_eq!(
+                Err(WktError::OutOfRange),
+                Duration::try_from(std::time::Duration::from_secs(
+                    Duration::MAX_SECONDS as u64 + 1
+                ))
+            );
+            assert_eq!(
+                Err(WktError::OutOfRange),
+                std::time::Duration::try_from(Duration {
+                    seconds: 1,
+                    nanos: -1
+                })
+            );
+        }
+    }
+}
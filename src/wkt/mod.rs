@@ -0,0 +1,19 @@
+mod any_ref;
+pub use any_ref::*;
+
+mod duration;
+pub use duration::*;
+
+mod timestamp;
+pub use timestamp::*;
+
+#[cfg(feature = "alloc")]
+mod value;
+#[cfg(feature = "alloc")]
+pub use value::*;
+
+mod wkt_error;
+pub use wkt_error::*;
+
+mod wrappers;
+pub use wrappers::*;
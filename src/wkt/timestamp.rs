@@ -0,0 +1,280 @@
+use crate::{builders::MsgScribe, decode::MsgDecoder, wkt::WktError, FieldNumber};
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Well-known type `google.protobuf.Timestamp` (point in time
+/// independent of any time zone).
+///
+/// ```proto
+/// message Timestamp {
+///   int64 seconds = 1;
+///   int32 nanos = 2;
+/// }
+/// ```
+///
+/// Can be converted from & to [`SystemTime`] (requires the `std` feature):
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # fn main() {
+/// use protobin::{builders::*, wkt::Timestamp};
+/// use std::time::SystemTime;
+///
+/// let now = SystemTime::now();
+///
+/// fn ser<S: MsgScribe>(t: &Timestamp, mut s: S) -> S::End {
+///     t.add_msg(&mut s, 1.try_into().unwrap());
+///     s.end()
+/// }
+/// let t = Timestamp::try_from(now).unwrap();
+/// let mut builder = MsgBuilder::new();
+/// let step2 = ser(&t, builder.start(None));
+/// let bytes = ser(&t, step2);
+/// # assert_eq!(bytes[0], 0x0a);
+/// # }
+/// # #[cfg(not(feature = "std"))]
+/// # fn main() {}
+/// ```
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Timestamp {
+    /// Seconds since the Unix epoch (1970-01-01T00:00:00Z).
+    pub seconds: i64,
+    /// Non-negative fractions of a second at nanosecond resolution
+    /// (`0..=999_999_999`).
+    pub nanos: i32,
+}
+
+impl Timestamp {
+    /// Fully qualified protobuf type name.
+    pub const TYPE_NAME: &'static str = "google.protobuf.Timestamp";
+
+    /// Minimum allowed `seconds` value (`0001-01-01T00:00:00Z`).
+    pub const MIN_SECONDS: i64 = -62_135_596_800;
+
+    /// Maximum allowed `seconds` value (`9999-12-31T23:59:59Z`).
+    pub const MAX_SECONDS: i64 = 253_402_300_799;
+
+    /// Field number of `seconds`.
+    pub const SECONDS: FieldNumber = FieldNumber(1);
+
+    /// Field number of `nanos`.
+    pub const NANOS: FieldNumber = FieldNumber(2);
+
+    /// Returns true if `seconds` & `nanos` are in the ranges allowed by
+    /// the protobuf definition.
+    pub fn is_valid(&self) -> bool {
+        (Self::MIN_SECONDS..=Self::MAX_SECONDS).contains(&self.seconds)
+            && (0..1_000_000_000).contains(&self.nanos)
+    }
+
+    /// Adds the fields of the timestamp (fields with default values are
+    /// skipped).
+    pub fn add_fields<S: MsgScribe>(&self, s: &mut S) {
+        if self.seconds != 0 {
+            s.add_int64(Self::SECONDS, self.seconds);
+        }
+        if self.nanos != 0 {
+            s.add_int32(Self::NANOS, self.nanos);
+        }
+    }
+
+    /// Adds the timestamp as sub-message field.
+    pub fn add_msg<S: MsgScribe>(&self, s: &mut S, field_number: FieldNumber) {
+        s.start_msg(field_number);
+        self.add_fields(s);
+        s.end_msg(field_number);
+    }
+
+    /// Decodes a timestamp from the encoded message data.
+    pub fn decode(data: &[u8]) -> Result<Timestamp, WktError> {
+        let mut result = Timestamp::default();
+        for record in MsgDecoder::new(data) {
+            let record = record?;
+            let f = record.field_number;
+            if f == Self::SECONDS {
+                result.seconds = record.value.try_as_int64().map_err(WktError::value(f))?;
+            } else if f == Self::NANOS {
+                result.nanos = record.value.try_as_int32().map_err(WktError::value(f))?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryFrom<SystemTime> for Timestamp {
+    type Error = WktError;
+
+    /// Converts the [`SystemTime`] to a [`Timestamp`].
+    ///
+    /// Returns [`WktError::OutOfRange`] if the time is outside of the
+    /// range supported by [`Timestamp`].
+    fn try_from(value: SystemTime) -> Result<Self, Self::Error> {
+        let result = match value.duration_since(UNIX_EPOCH) {
+            Ok(d) => Timestamp {
+                seconds: i64::try_from(d.as_secs()).map_err(|_| WktError::OutOfRange)?,
+                nanos: d.subsec_nanos() as i32,
+            },
+            Err(err) => {
+                // before the epoch, nanos are still counted forward in time
+                let d = err.duration();
+                let seconds = i64::try_from(d.as_secs()).map_err(|_| WktError::OutOfRange)?;
+                if d.subsec_nanos() == 0 {
+                    Timestamp {
+                        seconds: -seconds,
+                        nanos: 0,
+                    }
+                } else {
+                    Timestamp {
+                        seconds: -seconds - 1,
+                        nanos: 1_000_000_000 - d.subsec_nanos() as i32,
+                    }
+                }
+            }
+        };
+        if result.is_valid() {
+            Ok(result)
+        } else {
+            Err(WktError::OutOfRange)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryFrom<Timestamp> for SystemTime {
+    type Error = WktError;
+
+    /// Converts the [`Timestamp`] to a [`SystemTime`].
+    ///
+    /// Returns [`WktError::OutOfRange`] if the timestamp is not valid (see
+    /// [`Timestamp::is_valid`]) or can not be represented as [`SystemTime`].
+    fn try_from(value: Timestamp) -> Result<Self, Self::Error> {
+        use std::time::Duration;
+
+        if !value.is_valid() {
+            return Err(WktError::OutOfRange);
+        }
+        let nanos = Duration::from_nanos(value.nanos as u64);
+        let result = if value.seconds >= 0 {
+            UNIX_EPOCH.checked_add(Duration::from_secs(value.seconds as u64))
+        } else {
+            UNIX_EPOCH.checked_sub(Duration::from_secs(value.seconds.unsigned_abs()))
+        };
+        result
+            .and_then(|t| t.checked_add(nanos))
+            .ok_or(WktError::OutOfRange)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "alloc")]
+    use crate::builders::*;
+    use crate::wkt::*;
+
+    #[cfg(feature = "alloc")]
+    fn ser<S: MsgScribe>(t: &Timestamp, mut s: S) -> S::End {
+        t.add_fields(&mut s);
+        s.end()
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn encode_decode() {
+        let tests = [
+            (Timestamp::default(), &[][..]),
+            (
+                Timestamp {
+                    seconds: 1,
+                    nanos: 2,
+                },
+                &[0x08, 0x01, 0x10, 0x02][..],
+            ),
+            (
+                Timestamp {
+                    seconds: 0,
+                    nanos: 300,
+                },
+                &[0x10, 0xac, 0x02][..],
+            ),
+        ];
+        let mut builder = MsgBuilder::new();
+        for (t, expected) in tests {
+            let step2 = ser(&t, builder.start(None));
+            let bytes = ser(&t, step2);
+            assert_eq!(expected, bytes);
+            assert_eq!(Ok(t), Timestamp::decode(bytes));
+        }
+    }
+
+    #[test]
+    fn decode_error() {
+        // seconds with I32 wire type
+        assert!(matches!(
+            Timestamp::decode(&[0x0d, 0, 0, 0, 0]),
+            Err(WktError::Value { .. })
+        ));
+        // truncated
+        assert!(matches!(
+            Timestamp::decode(&[0x08]),
+            Err(WktError::Decode(_))
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn system_time_conversion() {
+        use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+        let tests = [
+            (UNIX_EPOCH, Timestamp::default()),
+            (
+                UNIX_EPOCH + Duration::new(1_700_000_000, 123),
+                Timestamp {
+                    seconds: 1_700_000_000,
+                    nanos: 123,
+                },
+            ),
+            (
+                UNIX_EPOCH - Duration::new(1, 0),
+                Timestamp {
+                    seconds: -1,
+                    nanos: 0,
+                },
+            ),
+            (
+                UNIX_EPOCH - Duration::new(1, 250_000_000),
+                Timestamp {
+                    seconds: -2,
+                    nanos: 750_000_000,
+                },
+            ),
+        ];
+        for (time, t) in tests {
+            assert_eq!(Ok(t), Timestamp::try_from(time));
+            assert_eq!(Ok(time), SystemTime::try_from(t));
+        }
+
+        // out of range
+        for t in [
+            Timestamp {
+                seconds: 0,
+                nanos: -1,
+            },
+            Timestamp {
+                seconds: 0,
+                nanos: 1_000_000_000,
+            },
+            Timestamp {
+                seconds: Timestamp::MAX_SECONDS + 1,
+                nanos: 0,
+            },
+            Timestamp {
+                seconds: Timestamp::MIN_SECONDS - 1,
+                nanos: 0,
+            },
+        ] {
+            assert_eq!(Err(WktError::OutOfRange), SystemTime::try_from(t));
+        }
+    }
+}
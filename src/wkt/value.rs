@@ -0,0 +1,365 @@
+use crate::{builders::MsgScribe, decode::*, wkt::WktError, FieldNumber};
+use alloc::{borrow::ToOwned, collections::BTreeMap, string::String, vec::Vec};
+
+/// Maximum nesting depth of [`Value`], [`Struct`] & [`ListValue`] messages
+/// accepted while decoding (same as the default recursion limit of the
+/// protobuf reference implementation).
+const MAX_DECODE_DEPTH: usize = 100;
+
+/// Well-known type `google.protobuf.Value` (dynamically typed JSON-like
+/// value).
+///
+/// ```proto
+/// message Value {
+///   oneof kind {
+///     NullValue null_value = 1;
+///     double number_value = 2;
+///     string string_value = 3;
+///     bool bool_value = 4;
+///     Struct struct_value = 5;
+///     ListValue list_value = 6;
+///   }
+/// }
+/// ```
+#[derive(Default, Debug, Clone, PartialEq)]
+pub enum Value {
+    #[default]
+    Null,
+    Number(f64),
+    String(String),
+    Bool(bool),
+    Struct(Struct),
+    List(ListValue),
+}
+
+impl Value {
+    /// Fully qualified protobuf type name.
+    pub const TYPE_NAME: &'static str = "google.protobuf.Value";
+
+    /// Field number of `null_value`.
+    pub const NULL_VALUE: FieldNumber = FieldNumber(1);
+    /// Field number of `number_value`.
+    pub const NUMBER_VALUE: FieldNumber = FieldNumber(2);
+    /// Field number of `string_value`.
+    pub const STRING_VALUE: FieldNumber = FieldNumber(3);
+    /// Field number of `bool_value`.
+    pub const BOOL_VALUE: FieldNumber = FieldNumber(4);
+    /// Field number of `struct_value`.
+    pub const STRUCT_VALUE: FieldNumber = FieldNumber(5);
+    /// Field number of `list_value`.
+    pub const LIST_VALUE: FieldNumber = FieldNumber(6);
+
+    /// Adds the fields of the value (the active `kind` is always written,
+    /// even if it contains a default value).
+    pub fn add_fields<S: MsgScribe>(&self, s: &mut S) {
+        match self {
+            Value::Null => {
+                s.add_enum(Self::NULL_VALUE, 0);
+            }
+            Value::Number(v) => {
+                s.add_double(Self::NUMBER_VALUE, *v);
+            }
+            Value::String(v) => {
+                s.add_string(Self::STRING_VALUE, v);
+            }
+            Value::Bool(v) => {
+                s.add_bool(Self::BOOL_VALUE, *v);
+            }
+            Value::Struct(v) => v.add_msg(s, Self::STRUCT_VALUE),
+            Value::List(v) => v.add_msg(s, Self::LIST_VALUE),
+        }
+    }
+
+    /// Adds the value as sub-message field.
+    pub fn add_msg<S: MsgScribe>(&self, s: &mut S, field_number: FieldNumber) {
+        s.start_msg(field_number);
+        self.add_fields(s);
+        s.end_msg(field_number);
+    }
+
+    /// Decodes a value from the encoded message data.
+    ///
+    /// If multiple `kind` fields are present the last one wins. A message
+    /// without any `kind` field is decoded as [`Value::Null`].
+    pub fn decode(data: &[u8]) -> Result<Value, WktError> {
+        Self::decode_with_depth(data, 0)
+    }
+
+    fn decode_with_depth(data: &[u8], depth: usize) -> Result<Value, WktError> {
+        if depth > MAX_DECODE_DEPTH {
+            return Err(WktError::RecursionLimit);
+        }
+        let mut result = Value::Null;
+        for record in MsgDecoder::new(data) {
+            let record = record?;
+            let f = record.field_number;
+            let value = &record.value;
+            if f == Self::NULL_VALUE {
+                value.try_as_int32().map_err(WktError::value(f))?;
+                result = Value::Null;
+            } else if f == Self::NUMBER_VALUE {
+                result = Value::Number(value.as_double().map_err(WktError::value(f))?);
+            } else if f == Self::STRING_VALUE {
+                let len_ref = value.try_as_len().map_err(WktError::value(f))?;
+                result = Value::String(len_ref.try_as_string()?.to_owned());
+            } else if f == Self::BOOL_VALUE {
+                result = Value::Bool(value.try_as_bool().map_err(WktError::value(f))?);
+            } else if f == Self::STRUCT_VALUE {
+                let len_ref = value.try_as_len().map_err(WktError::value(f))?;
+                result = Value::Struct(Struct::decode_with_depth(len_ref.data, depth + 1)?);
+            } else if f == Self::LIST_VALUE {
+                let len_ref = value.try_as_len().map_err(WktError::value(f))?;
+                result = Value::List(ListValue::decode_with_depth(len_ref.data, depth + 1)?);
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Number(value)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_owned())
+    }
+}
+
+impl From<Struct> for Value {
+    fn from(value: Struct) -> Self {
+        Value::Struct(value)
+    }
+}
+
+impl From<ListValue> for Value {
+    fn from(value: ListValue) -> Self {
+        Value::List(value)
+    }
+}
+
+/// Well-known type `google.protobuf.Struct` (JSON-like object).
+///
+/// ```proto
+/// message Struct {
+///   map<string, Value> fields = 1;
+/// }
+/// ```
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Struct(pub BTreeMap<String, Value>);
+
+impl Struct {
+    /// Fully qualified protobuf type name.
+    pub const TYPE_NAME: &'static str = "google.protobuf.Struct";
+
+    /// Field number of `fields`.
+    pub const FIELDS: FieldNumber = FieldNumber(1);
+
+    /// Adds the fields of the struct (one map entry per key).
+    pub fn add_fields<S: MsgScribe>(&self, s: &mut S) {
+        for (key, value) in &self.0 {
+            s.start_msg(Self::FIELDS);
+            s.add_string(FieldNumber::MAP_KEY, key);
+            value.add_msg(s, FieldNumber::MAP_VALUE);
+            s.end_msg(Self::FIELDS);
+        }
+    }
+
+    /// Adds the struct as sub-message field.
+    pub fn add_msg<S: MsgScribe>(&self, s: &mut S, field_number: FieldNumber) {
+        s.start_msg(field_number);
+        self.add_fields(s);
+        s.end_msg(field_number);
+    }
+
+    /// Decodes a struct from the encoded message data.
+    ///
+    /// If a key is present multiple times the last entry wins.
+    pub fn decode(data: &[u8]) -> Result<Struct, WktError> {
+        Self::decode_with_depth(data, 0)
+    }
+
+    fn decode_with_depth(data: &[u8], depth: usize) -> Result<Struct, WktError> {
+        if depth > MAX_DECODE_DEPTH {
+            return Err(WktError::RecursionLimit);
+        }
+        let mut result = Struct::default();
+        for entry in MapEntryDecoder::new(MsgDecoder::new(data), Self::FIELDS) {
+            let entry = entry?;
+            let key = match entry.key {
+                Some(key) => key
+                    .try_as_len()
+                    .map_err(WktError::value(FieldNumber::MAP_KEY))?
+                    .try_as_string()?,
+                None => "",
+            };
+            let value = match entry.value {
+                Some(value) => Value::decode_with_depth(
+                    value
+                        .try_as_len()
+                        .map_err(WktError::value(FieldNumber::MAP_VALUE))?
+                        .data,
+                    depth + 1,
+                )?,
+                None => Value::Null,
+            };
+            result.0.insert(key.to_owned(), value);
+        }
+        Ok(result)
+    }
+}
+
+/// Well-known type `google.protobuf.ListValue` (JSON-like array).
+///
+/// ```proto
+/// message ListValue {
+///   repeated Value values = 1;
+/// }
+/// ```
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct ListValue(pub Vec<Value>);
+
+impl ListValue {
+    /// Fully qualified protobuf type name.
+    pub const TYPE_NAME: &'static str = "google.protobuf.ListValue";
+
+    /// Field number of `values`.
+    pub const VALUES: FieldNumber = FieldNumber(1);
+
+    /// Adds the fields of the list.
+    pub fn add_fields<S: MsgScribe>(&self, s: &mut S) {
+        for value in &self.0 {
+            value.add_msg(s, Self::VALUES);
+        }
+    }
+
+    /// Adds the list as sub-message field.
+    pub fn add_msg<S: MsgScribe>(&self, s: &mut S, field_number: FieldNumber) {
+        s.start_msg(field_number);
+        self.add_fields(s);
+        s.end_msg(field_number);
+    }
+
+    /// Decodes a list from the encoded message data.
+    pub fn decode(data: &[u8]) -> Result<ListValue, WktError> {
+        Self::decode_with_depth(data, 0)
+    }
+
+    fn decode_with_depth(data: &[u8], depth: usize) -> Result<ListValue, WktError> {
+        if depth > MAX_DECODE_DEPTH {
+            return Err(WktError::RecursionLimit);
+        }
+        let mut result = ListValue::default();
+        for record in MsgDecoder::new(data) {
+            let record = record?;
+            let f = record.field_number;
+            if f == Self::VALUES {
+                let len_ref = record.value.try_as_len().map_err(WktError::value(f))?;
+                result
+                    .0
+                    .push(Value::decode_with_depth(len_ref.data, depth + 1)?);
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{builders::*, wkt::*};
+
+    fn encode(value: &Value) -> Vec<u8> {
+        fn ser<S: MsgScribe>(value: &Value, mut s: S) -> S::End {
+            value.add_fields(&mut s);
+            s.end()
+        }
+        let mut builder = MsgBuilder::new();
+        let step2 = ser(value, builder.start(None));
+        ser(value, step2).to_vec()
+    }
+
+    #[test]
+    fn encode_decode() {
+        let value = Value::Struct(Struct(
+            [
+                ("null".to_owned(), Value::Null),
+                ("number".to_owned(), Value::from(1.5)),
+                ("zero".to_owned(), Value::from(0.0)),
+                ("string".to_owned(), Value::from("text")),
+                ("bool".to_owned(), Value::from(false)),
+                ("empty".to_owned(), Value::Struct(Struct::default())),
+                (
+                    "list".to_owned(),
+                    Value::List(ListValue(vec![
+                        Value::from(true),
+                        Value::from("a"),
+                        Value::List(ListValue::default()),
+                    ])),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        ));
+        assert_eq!(Ok(value.clone()), Value::decode(&encode(&value)));
+    }
+
+    #[test]
+    fn encode_null_and_defaults() {
+        assert_eq!(vec![0x08, 0x00], encode(&Value::Null));
+        assert_eq!(vec![0x20, 0x00], encode(&Value::Bool(false)));
+        assert_eq!(vec![0x1a, 0x00], encode(&Value::from("")));
+        assert_eq!(vec![0x2a, 0x00], encode(&Value::Struct(Struct::default())));
+    }
+
+    #[test]
+    fn decode_special_cases() {
+        // empty message is null
+        assert_eq!(Ok(Value::Null), Value::decode(&[]));
+        // last kind wins
+        assert_eq!(
+            Ok(Value::Bool(true)),
+            Value::decode(&[0x1a, 0x01, b'a', 0x20, 0x01])
+        );
+        // struct entry without key & value
+        assert_eq!(
+            Ok(Struct([("".to_owned(), Value::Null)].into_iter().collect())),
+            Struct::decode(&[0x0a, 0x00])
+        );
+        // wrong wire type
+        assert!(matches!(
+            Value::decode(&[0x10, 0x01]),
+            Err(WktError::Value { .. })
+        ));
+    }
+
+    #[test]
+    fn decode_recursion_limit() {
+        // every list adds two nesting levels (ListValue & Value)
+        let mut value = Value::Null;
+        for _ in 0..super::MAX_DECODE_DEPTH / 2 {
+            value = Value::List(ListValue(vec![value]));
+        }
+        assert_eq!(Ok(value.clone()), Value::decode(&encode(&value)));
+
+        // one level too deep
+        let value = Value::List(ListValue(vec![value]));
+        assert_eq!(
+            Err(WktError::RecursionLimit),
+            Value::decode(&encode(&value))
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use crate::{decode::DecodeError, wire::WireValueIntoError, FieldNumber};
+use core::str::Utf8Error;
+
+/// Error when decoding or converting a well-known type.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum WktError {
+    /// Error while decoding the message records.
+    Decode(DecodeError),
+
+    /// A field contained a value that could not be interpreted as the
+    /// type of the field.
+    Value {
+        field_number: FieldNumber,
+        err: WireValueIntoError,
+    },
+
+    /// A `string` field contained invalid UTF-8.
+    Utf8(Utf8Error),
+
+    /// The value is outside of the range allowed for the well-known type
+    /// or the range of the Rust type it is converted to.
+    OutOfRange,
+
+    /// The nesting depth of `google.protobuf.Struct`, `Value` or
+    /// `ListValue` messages exceeded the supported limit.
+    RecursionLimit,
+
+    /// A negative `google.protobuf.Duration` can not be converted to a
+    /// [`std::time::Duration`].
+    NegativeDuration,
+}
+
+impl WktError {
+    /// Returns a closure converting a [`WireValueIntoError`] of the
+    /// given field into a [`WktError`].
+    #[inline]
+    pub(crate) fn value(field_number: FieldNumber) -> impl Fn(WireValueIntoError) -> WktError {
+        move |err| WktError::Value { field_number, err }
+    }
+}
+
+impl From<DecodeError> for WktError {
+    fn from(value: DecodeError) -> Self {
+        WktError::Decode(value)
+    }
+}
+
+impl From<Utf8Error> for WktError {
+    fn from(value: Utf8Error) -> Self {
+        WktError::Utf8(value)
+    }
+}
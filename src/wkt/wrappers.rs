@@ -0,0 +1,259 @@
+use crate::{builders::MsgScribe, decode::MsgDecoder, wkt::WktError, FieldNumber};
+
+/// Generates a wrapper well-known type (message with a single
+/// `value = 1` field) for a scalar type.
+macro_rules! scalar_wrapper {
+    (
+        $(#[$doc:meta])*
+        $name:ident, $type_name:literal, $t:ty, $add:ident, $try_as:ident, $is_default:expr
+    ) => {
+        $(#[$doc])*
+        #[derive(Default, Debug, Copy, Clone, PartialEq, PartialOrd)]
+        pub struct $name(pub $t);
+
+        impl $name {
+            /// Fully qualified protobuf type name.
+            pub const TYPE_NAME: &'static str = $type_name;
+
+            /// Field number of `value`.
+            pub const VALUE: FieldNumber = FieldNumber(1);
+
+            /// Adds the fields of the wrapper (skipped if the value is the
+            /// default value).
+            pub fn add_fields<S: MsgScribe>(&self, s: &mut S) {
+                let is_default: fn($t) -> bool = $is_default;
+                if !is_default(self.0) {
+                    s.$add(Self::VALUE, self.0);
+                }
+            }
+
+            /// Adds the wrapper as sub-message field.
+            pub fn add_msg<S: MsgScribe>(&self, s: &mut S, field_number: FieldNumber) {
+                s.start_msg(field_number);
+                self.add_fields(s);
+                s.end_msg(field_number);
+            }
+
+            /// Decodes the wrapper from the encoded message data.
+            pub fn decode(data: &[u8]) -> Result<$name, WktError> {
+                let mut result = $name::default();
+                for record in MsgDecoder::new(data) {
+                    let record = record?;
+                    let f = record.field_number;
+                    if f == Self::VALUE {
+                        result.0 = record.value.$try_as().map_err(WktError::value(f))?;
+                    }
+                }
+                Ok(result)
+            }
+        }
+
+        impl From<$t> for $name {
+            fn from(value: $t) -> Self {
+                $name(value)
+            }
+        }
+
+        impl From<$name> for $t {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+    };
+}
+
+scalar_wrapper!(
+    /// Well-known type `google.protobuf.DoubleValue` (wrapper message for `double`).
+    DoubleValue, "google.protobuf.DoubleValue", f64, add_double, as_double, |v| v.to_bits() == 0
+);
+
+scalar_wrapper!(
+    /// Well-known type `google.protobuf.FloatValue` (wrapper message for `float`).
+    FloatValue, "google.protobuf.FloatValue", f32, add_float, as_float, |v| v.to_bits() == 0
+);
+
+scalar_wrapper!(
+    /// Well-known type `google.protobuf.Int64Value` (wrapper message for `int64`).
+    Int64Value, "google.protobuf.Int64Value", i64, add_int64, try_as_int64, |v| v == 0
+);
+
+scalar_wrapper!(
+    /// Well-known type `google.protobuf.UInt64Value` (wrapper message for `uint64`).
+    UInt64Value, "google.protobuf.UInt64Value", u64, add_uint64, try_as_uint64, |v| v == 0
+);
+
+scalar_wrapper!(
+    /// Well-known type `google.protobuf.Int32Value` (wrapper message for `int32`).
+    Int32Value, "google.protobuf.Int32Value", i32, add_int32, try_as_int32, |v| v == 0
+);
+
+scalar_wrapper!(
+    /// Well-known type `google.protobuf.UInt32Value` (wrapper message for `uint32`).
+    UInt32Value, "google.protobuf.UInt32Value", u32, add_uint32, try_as_uint32, |v| v == 0
+);
+
+scalar_wrapper!(
+    /// Well-known type `google.protobuf.BoolValue` (wrapper message for `bool`).
+    BoolValue, "google.protobuf.BoolValue", bool, add_bool, try_as_bool, |v| !v
+);
+
+/// Well-known type `google.protobuf.StringValue` (wrapper message for `string`).
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct StringValue<'a>(pub &'a str);
+
+impl<'a> StringValue<'a> {
+    /// Fully qualified protobuf type name.
+    pub const TYPE_NAME: &'static str = "google.protobuf.StringValue";
+
+    /// Field number of `value`.
+    pub const VALUE: FieldNumber = FieldNumber(1);
+
+    /// Adds the fields of the wrapper (skipped if the value is empty).
+    pub fn add_fields<S: MsgScribe>(&self, s: &mut S) {
+        if !self.0.is_empty() {
+            s.add_string(Self::VALUE, self.0);
+        }
+    }
+
+    /// Adds the wrapper as sub-message field.
+    pub fn add_msg<S: MsgScribe>(&self, s: &mut S, field_number: FieldNumber) {
+        s.start_msg(field_number);
+        self.add_fields(s);
+        s.end_msg(field_number);
+    }
+
+    /// Decodes the wrapper from the encoded message data.
+    pub fn decode(data: &'a [u8]) -> Result<StringValue<'a>, WktError> {
+        let mut result = StringValue::default();
+        for record in MsgDecoder::new(data) {
+            let record = record?;
+            let f = record.field_number;
+            if f == Self::VALUE {
+                let len_ref = record.value.try_as_len().map_err(WktError::value(f))?;
+                result.0 = core::str::from_utf8(len_ref.data)?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Well-known type `google.protobuf.BytesValue` (wrapper message for `bytes`).
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct BytesValue<'a>(pub &'a [u8]);
+
+impl<'a> BytesValue<'a> {
+    /// Fully qualified protobuf type name.
+    pub const TYPE_NAME: &'static str = "google.protobuf.BytesValue";
+
+    /// Field number of `value`.
+    pub const VALUE: FieldNumber = FieldNumber(1);
+
+    /// Adds the fields of the wrapper (skipped if the value is empty).
+    pub fn add_fields<S: MsgScribe>(&self, s: &mut S) {
+        if !self.0.is_empty() {
+            s.add_bytes(Self::VALUE, self.0);
+        }
+    }
+
+    /// Adds the wrapper as sub-message field.
+    pub fn add_msg<S: MsgScribe>(&self, s: &mut S, field_number: FieldNumber) {
+        s.start_msg(field_number);
+        self.add_fields(s);
+        s.end_msg(field_number);
+    }
+
+    /// Decodes the wrapper from the encoded message data.
+    pub fn decode(data: &'a [u8]) -> Result<BytesValue<'a>, WktError> {
+        let mut result = BytesValue::default();
+        for record in MsgDecoder::new(data) {
+            let record = record?;
+            let f = record.field_number;
+            if f == Self::VALUE {
+                result.0 = record.value.try_as_len().map_err(WktError::value(f))?.data;
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::{builders::*, wkt::*};
+
+    /// Serializes the fields of the given wrapper as top level message.
+    macro_rules! encode {
+        ($wrapper:expr) => {{
+            let mut builder = MsgBuilder::new();
+            let mut len = builder.start(None);
+            $wrapper.add_fields(&mut len);
+            let mut ser = len.end();
+            $wrapper.add_fields(&mut ser);
+            ser.end().to_vec()
+        }};
+    }
+
+    #[test]
+    fn scalar_wrappers() {
+        // default values are skipped
+        assert!(encode!(DoubleValue(0.0)).is_empty());
+        assert!(encode!(BoolValue(false)).is_empty());
+        assert!(encode!(UInt64Value(0)).is_empty());
+
+        // negative zero is not a default value
+        let bytes = encode!(DoubleValue(-0.0));
+        assert_eq!(9, bytes.len());
+        assert_eq!(
+            (-0.0f64).to_bits(),
+            DoubleValue::decode(&bytes).unwrap().0.to_bits()
+        );
+
+        // round trips
+        assert_eq!(
+            Ok(DoubleValue(1.5)),
+            DoubleValue::decode(&encode!(DoubleValue(1.5)))
+        );
+        assert_eq!(
+            Ok(FloatValue(-2.5)),
+            FloatValue::decode(&encode!(FloatValue(-2.5)))
+        );
+        assert_eq!(
+            Ok(Int64Value(i64::MIN)),
+            Int64Value::decode(&encode!(Int64Value(i64::MIN)))
+        );
+        assert_eq!(
+            Ok(UInt64Value(u64::MAX)),
+            UInt64Value::decode(&encode!(UInt64Value(u64::MAX)))
+        );
+        assert_eq!(
+            Ok(Int32Value(i32::MAX)),
+            Int32Value::decode(&encode!(Int32Value(i32::MAX)))
+        );
+        assert_eq!(
+            Ok(UInt32Value(u32::MAX)),
+            UInt32Value::decode(&encode!(UInt32Value(u32::MAX)))
+        );
+        assert_eq!(
+            Ok(BoolValue(true)),
+            BoolValue::decode(&encode!(BoolValue(true)))
+        );
+    }
+
+    #[test]
+    fn string_bytes_wrappers() {
+        let bytes = encode!(StringValue("abc"));
+        assert_eq!(&[0x0a, 0x03, b'a', b'b', b'c'], &bytes[..]);
+        assert_eq!(bytes, encode!(BytesValue(b"abc")));
+        assert_eq!(Ok(StringValue("abc")), StringValue::decode(&bytes));
+        assert_eq!(Ok(BytesValue(b"abc")), BytesValue::decode(&bytes));
+        assert!(encode!(StringValue("")).is_empty());
+
+        assert!(matches!(
+            StringValue::decode(&[0x0a, 0x01, 0xff]),
+            Err(WktError::Utf8(_))
+        ));
+        assert!(matches!(
+            Int32Value::decode(&[0x0a, 0x00]),
+            Err(WktError::Value { .. })
+        ));
+    }
+}